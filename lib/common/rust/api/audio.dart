@@ -0,0 +1,12 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../audio_capture/traits.dart';
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+/// 列出当前平台可用的音频设备，`id` 可作为 launch_caption 的 audio_device 传入
+Future<List<AudioDeviceInfo>> listAudioDevices() =>
+    RustLib.instance.api.crateApiAudioListAudioDevices();
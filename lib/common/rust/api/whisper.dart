@@ -4,6 +4,8 @@
 // ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
 
 import '../candle_models/whisper/model.dart';
+import '../caption/config.dart';
+import '../caption/event.dart';
 import '../frb_generated.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
@@ -15,36 +17,28 @@ Future<void> cancelCancellationToken({required String tokenId}) => RustLib
     .api
     .crateApiWhisperCancelCancellationToken(tokenId: tokenId);
 
-Stream<List<Segment>> launchCaption({
+Stream<CaptionEvent> launchCaption({
   required WhisperClient whisperClient,
-  String? audioDevice,
-  bool? audioDeviceIsInput,
-  String? audioLanguage,
   required String cancelTokenId,
-  bool? withTimestamps,
-  bool? verbose,
-  bool? tryWithCuda,
-  int? whisperMaxAudioDuration,
-  BigInt? inferenceInterval,
-  BigInt? whisperDefaultMaxDecodeTokens,
-  double? whisperTemperature,
-  String? vadModelPath,
-  double? vadFiltersValue,
+  required CaptionConfig config,
 }) => RustLib.instance.api.crateApiWhisperLaunchCaption(
   whisperClient: whisperClient,
-  audioDevice: audioDevice,
-  audioDeviceIsInput: audioDeviceIsInput,
-  audioLanguage: audioLanguage,
   cancelTokenId: cancelTokenId,
-  withTimestamps: withTimestamps,
-  verbose: verbose,
-  tryWithCuda: tryWithCuda,
-  whisperMaxAudioDuration: whisperMaxAudioDuration,
-  inferenceInterval: inferenceInterval,
-  whisperDefaultMaxDecodeTokens: whisperDefaultMaxDecodeTokens,
-  whisperTemperature: whisperTemperature,
-  vadModelPath: vadModelPath,
-  vadFiltersValue: vadFiltersValue,
+  config: config,
+);
+
+/// Transcribe an audio file (WAV / FLAC / MP3 / OGG) without live capture.
+/// Returns all segments, timestamps are relative to the start of the file.
+/// Only the model and decoding settings of `config` apply, the capture and output
+/// settings are ignored.
+Future<List<Segment>> transcribeFile({
+  required WhisperClient whisperClient,
+  required String filePath,
+  required CaptionConfig config,
+}) => RustLib.instance.api.crateApiWhisperTranscribeFile(
+  whisperClient: whisperClient,
+  filePath: filePath,
+  config: config,
 );
 
 class WhisperClient {
@@ -0,0 +1,66 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+/// Direction of an audio device
+enum AudioDeviceDirection {
+  /// Microphone / line in
+  input,
+
+  /// Loopback of an output device (speakers, headphones)
+  monitor,
+  ;
+}
+
+/// Audio device that can be passed as `AudioCaptureConfig::device`
+class AudioDeviceInfo {
+  /// Human readable name
+  final String name;
+
+  /// Identifier to pass as `AudioCaptureConfig::device`
+  final String id;
+  final AudioDeviceDirection direction;
+
+  /// Whether this is the system default device for its direction
+  final bool isDefault;
+
+  /// Native sample rate (Hz), 0 if unknown
+  final int sampleRate;
+
+  /// Native channel count, 0 if unknown
+  final int channels;
+
+  const AudioDeviceInfo({
+    required this.name,
+    required this.id,
+    required this.direction,
+    required this.isDefault,
+    required this.sampleRate,
+    required this.channels,
+  });
+
+  @override
+  int get hashCode =>
+      name.hashCode ^
+      id.hashCode ^
+      direction.hashCode ^
+      isDefault.hashCode ^
+      sampleRate.hashCode ^
+      channels.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is AudioDeviceInfo &&
+          runtimeType == other.runtimeType &&
+          name == other.name &&
+          id == other.id &&
+          direction == other.direction &&
+          isDefault == other.isDefault &&
+          sampleRate == other.sampleRate &&
+          channels == other.channels;
+}
@@ -45,6 +45,27 @@ class DecodingResult {
           compressionRatio == other.compressionRatio;
 }
 
+class LanguageProbability {
+  final String language;
+  final double probability;
+
+  const LanguageProbability({
+    required this.language,
+    required this.probability,
+  });
+
+  @override
+  int get hashCode => language.hashCode ^ probability.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is LanguageProbability &&
+          runtimeType == other.runtimeType &&
+          language == other.language &&
+          probability == other.probability;
+}
+
 class Segment {
   final double start;
   final double duration;
@@ -53,6 +74,12 @@ class Segment {
   final String? reasoningLang;
   final BigInt? audioDuration;
   final WhisperStatus status;
+  final List<SubSegment> subSegments;
+  final BigInt id;
+  final bool committed;
+  final List<LanguageProbability> languageProbs;
+  final String? emotion;
+  final String? audioEvent;
 
   const Segment({
     required this.start,
@@ -62,6 +89,12 @@ class Segment {
     this.reasoningLang,
     this.audioDuration,
     required this.status,
+    required this.subSegments,
+    required this.id,
+    required this.committed,
+    required this.languageProbs,
+    this.emotion,
+    this.audioEvent,
   });
 
   @override
@@ -72,7 +105,13 @@ class Segment {
       reasoningDuration.hashCode ^
       reasoningLang.hashCode ^
       audioDuration.hashCode ^
-      status.hashCode;
+      status.hashCode ^
+      subSegments.hashCode ^
+      id.hashCode ^
+      committed.hashCode ^
+      languageProbs.hashCode ^
+      emotion.hashCode ^
+      audioEvent.hashCode;
 
   @override
   bool operator ==(Object other) =>
@@ -85,7 +124,65 @@ class Segment {
           reasoningDuration == other.reasoningDuration &&
           reasoningLang == other.reasoningLang &&
           audioDuration == other.audioDuration &&
-          status == other.status;
+          status == other.status &&
+          subSegments == other.subSegments &&
+          id == other.id &&
+          committed == other.committed &&
+          languageProbs == other.languageProbs &&
+          emotion == other.emotion &&
+          audioEvent == other.audioEvent;
+}
+
+class SubSegment {
+  final double start;
+  final double end;
+  final String text;
+  final List<WordTiming> words;
+
+  const SubSegment({
+    required this.start,
+    required this.end,
+    required this.text,
+    required this.words,
+  });
+
+  @override
+  int get hashCode =>
+      start.hashCode ^ end.hashCode ^ text.hashCode ^ words.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is SubSegment &&
+          runtimeType == other.runtimeType &&
+          start == other.start &&
+          end == other.end &&
+          text == other.text &&
+          words == other.words;
 }
 
 enum WhisperStatus { loading, ready, error, working, exit }
+
+class WordTiming {
+  final double start;
+  final double end;
+  final String word;
+
+  const WordTiming({
+    required this.start,
+    required this.end,
+    required this.word,
+  });
+
+  @override
+  int get hashCode => start.hashCode ^ end.hashCode ^ word.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is WordTiming &&
+          runtimeType == other.runtimeType &&
+          start == other.start &&
+          end == other.end &&
+          word == other.word;
+}
@@ -0,0 +1,238 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+
+/// Settings of a live caption session.
+///
+/// Serializable as JSON or TOML so a config can be saved, shared and loaded by hosts
+/// other than the Flutter app. Missing fields take their default value.
+class CaptionConfig {
+  /// Device id from `list_audio_devices`, None for the system default device
+  final String? audioDevice;
+
+  /// Capture an input device (microphone), `false` captures what an output device plays.
+  /// Default `true`
+  final bool audioDeviceIsInput;
+
+  /// Spoken language such as "en", None lets multilingual models detect it
+  final String? language;
+
+  final bool withTimestamps;
+
+  /// Word-level timestamps in the sub-segments (candle Whisper only)
+  final bool wordTimestamps;
+
+  final bool verbose;
+
+  final bool tryWithCuda;
+
+  /// Audio context fed to the model in seconds, `1..=30`, default 12
+  final int maxAudioDurationSecs;
+
+  /// Time between two inferences in milliseconds, default 2000
+  final BigInt inferenceIntervalMs;
+
+  /// Abort a decode running longer than this, default 2000, None disables the limit
+  final BigInt? inferenceTimeoutMs;
+
+  /// Token limit per segment against hallucination loops, None uses the model limit
+  final int? maxTokensPerSegment;
+
+  /// Sampling temperature, None uses the decoder's temperature fallback
+  final double? temperature;
+
+  /// Silero VAD model file, None disables VAD
+  final String? vadModelPath;
+
+  /// Speech probability threshold in `0.0..=1.0`, default 0.5
+  final double? vadThreshold;
+
+  /// Commit text once n (>= 2) consecutive inferences agree, None disables it
+  final int? localAgreement;
+
+  /// End an utterance after this much silence (requires `vad_model_path` except for streaming
+  /// models), None runs inference every `inference_interval_ms`
+  final int? endpointSilenceMs;
+
+  /// Write the committed captions to this `.srt`, `.vtt` or `.ttml` file while captioning
+  /// (requires `local_agreement`, `endpoint_silence_ms` or a streaming model), None disables it
+  final String? subtitlePath;
+
+  /// Archive the captured audio to this `.wav` or `.flac` file and write a JSONL transcript
+  /// of every segment next to it (same name, `.jsonl`), None disables recording
+  final String? recordPath;
+
+  /// Broadcast the caption events as JSON over WebSocket on this `host:port`,
+  /// a bare port listens on localhost only, None disables it
+  final String? broadcastAddr;
+
+  /// Keep the last `obs_text_lines` caption lines in this text file for an OBS Text source,
+  /// None disables it
+  final String? obsTextPath;
+
+  /// Lines kept in `obs_text_path`, default 3
+  final int obsTextLines;
+
+  /// Send the captions to OBS as native closed captions over obs-websocket v5,
+  /// e.g. "ws://127.0.0.1:4455", None disables it
+  final String? obsWebsocketUrl;
+
+  /// obs-websocket server password, None if authentication is disabled
+  final String? obsWebsocketPassword;
+
+  /// Beam search width at temperature 0 (candle Whisper only), None or 1 decodes greedily
+  final int? beamSize;
+
+  /// Beam search stops once `beam_size * patience` sequences finished, None means 1.0
+  final double? beamPatience;
+
+  /// Length penalty alpha in `0.0..=1.0` for ranking beams, None ranks by average log
+  /// probability
+  final double? lengthPenalty;
+
+  /// Text fed to Whisper as previous context, such as names and jargon to spell right
+  final String? initialPrompt;
+
+  /// Condition each window on up to this many tokens of the committed text (requires
+  /// `local_agreement`, `endpoint_silence_ms` or a streaming model), None disables it
+  final int? conditionOnPreviousText;
+
+  /// Translate the speech to English instead of transcribing it (multilingual Whisper only)
+  final bool translate;
+
+  /// Detect the language again for every window so speakers can switch languages
+  /// (multilingual Whisper, `language` unset), segments then report the distribution
+  final bool redetectLanguage;
+
+  /// Probability in `0.0..=1.0` another language needs to take over, default 0.6
+  final double? languageSwitchThreshold;
+
+  /// Consecutive windows another language has to win before taking over, default 2
+  final int? languageSwitchWindows;
+
+  /// Put the emotion and audio event emoji into the caption text (SenseVoice only), the
+  /// tags are reported in the segment fields either way
+  final bool inlineEmoji;
+
+  /// Inverse text normalization (SenseVoice only): written-form numbers and punctuation,
+  /// `false` keeps the spoken form. Default `false`
+  final bool withItn;
+
+  const CaptionConfig({
+    this.audioDevice,
+    required this.audioDeviceIsInput,
+    this.language,
+    required this.withTimestamps,
+    required this.wordTimestamps,
+    required this.verbose,
+    required this.tryWithCuda,
+    required this.maxAudioDurationSecs,
+    required this.inferenceIntervalMs,
+    this.inferenceTimeoutMs,
+    this.maxTokensPerSegment,
+    this.temperature,
+    this.vadModelPath,
+    this.vadThreshold,
+    this.localAgreement,
+    this.endpointSilenceMs,
+    this.subtitlePath,
+    this.recordPath,
+    this.broadcastAddr,
+    this.obsTextPath,
+    required this.obsTextLines,
+    this.obsWebsocketUrl,
+    this.obsWebsocketPassword,
+    this.beamSize,
+    this.beamPatience,
+    this.lengthPenalty,
+    this.initialPrompt,
+    this.conditionOnPreviousText,
+    required this.translate,
+    required this.redetectLanguage,
+    this.languageSwitchThreshold,
+    this.languageSwitchWindows,
+    required this.inlineEmoji,
+    required this.withItn,
+  });
+
+  @override
+  int get hashCode =>
+      audioDevice.hashCode ^
+      audioDeviceIsInput.hashCode ^
+      language.hashCode ^
+      withTimestamps.hashCode ^
+      wordTimestamps.hashCode ^
+      verbose.hashCode ^
+      tryWithCuda.hashCode ^
+      maxAudioDurationSecs.hashCode ^
+      inferenceIntervalMs.hashCode ^
+      inferenceTimeoutMs.hashCode ^
+      maxTokensPerSegment.hashCode ^
+      temperature.hashCode ^
+      vadModelPath.hashCode ^
+      vadThreshold.hashCode ^
+      localAgreement.hashCode ^
+      endpointSilenceMs.hashCode ^
+      subtitlePath.hashCode ^
+      recordPath.hashCode ^
+      broadcastAddr.hashCode ^
+      obsTextPath.hashCode ^
+      obsTextLines.hashCode ^
+      obsWebsocketUrl.hashCode ^
+      obsWebsocketPassword.hashCode ^
+      beamSize.hashCode ^
+      beamPatience.hashCode ^
+      lengthPenalty.hashCode ^
+      initialPrompt.hashCode ^
+      conditionOnPreviousText.hashCode ^
+      translate.hashCode ^
+      redetectLanguage.hashCode ^
+      languageSwitchThreshold.hashCode ^
+      languageSwitchWindows.hashCode ^
+      inlineEmoji.hashCode ^
+      withItn.hashCode;
+
+  @override
+  bool operator ==(Object other) =>
+      identical(this, other) ||
+      other is CaptionConfig &&
+          runtimeType == other.runtimeType &&
+          audioDevice == other.audioDevice &&
+          audioDeviceIsInput == other.audioDeviceIsInput &&
+          language == other.language &&
+          withTimestamps == other.withTimestamps &&
+          wordTimestamps == other.wordTimestamps &&
+          verbose == other.verbose &&
+          tryWithCuda == other.tryWithCuda &&
+          maxAudioDurationSecs == other.maxAudioDurationSecs &&
+          inferenceIntervalMs == other.inferenceIntervalMs &&
+          inferenceTimeoutMs == other.inferenceTimeoutMs &&
+          maxTokensPerSegment == other.maxTokensPerSegment &&
+          temperature == other.temperature &&
+          vadModelPath == other.vadModelPath &&
+          vadThreshold == other.vadThreshold &&
+          localAgreement == other.localAgreement &&
+          endpointSilenceMs == other.endpointSilenceMs &&
+          subtitlePath == other.subtitlePath &&
+          recordPath == other.recordPath &&
+          broadcastAddr == other.broadcastAddr &&
+          obsTextPath == other.obsTextPath &&
+          obsTextLines == other.obsTextLines &&
+          obsWebsocketUrl == other.obsWebsocketUrl &&
+          obsWebsocketPassword == other.obsWebsocketPassword &&
+          beamSize == other.beamSize &&
+          beamPatience == other.beamPatience &&
+          lengthPenalty == other.lengthPenalty &&
+          initialPrompt == other.initialPrompt &&
+          conditionOnPreviousText == other.conditionOnPreviousText &&
+          translate == other.translate &&
+          redetectLanguage == other.redetectLanguage &&
+          languageSwitchThreshold == other.languageSwitchThreshold &&
+          languageSwitchWindows == other.languageSwitchWindows &&
+          inlineEmoji == other.inlineEmoji &&
+          withItn == other.withItn;
+}
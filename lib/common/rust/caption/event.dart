@@ -0,0 +1,74 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../candle_models/whisper/model.dart';
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'package:freezed_annotation/freezed_annotation.dart' hide protected;
+part 'event.freezed.dart';
+
+enum CaptionErrorKind {
+  modelLoad,
+  audioCapture,
+  vad,
+  inference,
+  internal,
+
+  /// The caption config failed validation
+  config,
+  ;
+}
+
+/// Event streamed by the live caption loop to the UI
+@freezed
+sealed class CaptionEvent with _$CaptionEvent {
+  const CaptionEvent._();
+
+  /// The recognizer is being loaded, `progress` is in `0.0..=1.0` when known
+  const factory CaptionEvent.loading({
+    double? progress,
+  }) = CaptionEvent_Loading;
+  /// Model loaded and audio capture started
+  const factory CaptionEvent.ready({
+    required String backend,
+    /// Compute device, e.g. "cpu", "cuda", "metal"
+    required String device,
+    /// ONNX Runtime execution providers that were registered (empty for candle)
+    required List<String> providers,
+  }) = CaptionEvent_Ready;
+  /// Transcription result of one inference
+  const factory CaptionEvent.segments({
+    required List<Segment> segments,
+  }) = CaptionEvent_Segments;
+  /// Speech probability of the latest VAD check
+  const factory CaptionEvent.vadActivity({
+    required bool speaking,
+    required double probability,
+  }) = CaptionEvent_VadActivity;
+  /// Something went wrong but the caption loop keeps running
+  const factory CaptionEvent.warning({
+    required String msg,
+  }) = CaptionEvent_Warning;
+  const factory CaptionEvent.error({
+    required CaptionErrorKind kind,
+    required String msg,
+  }) = CaptionEvent_Error;
+  /// Always the last event of a caption session
+  const factory CaptionEvent.exit({
+    required CaptionExitReason reason,
+  }) = CaptionEvent_Exit;
+}
+
+enum CaptionExitReason {
+  /// The cancel token was cancelled
+  cancelled,
+
+  /// The audio source was finished (e.g. file replay)
+  endOfStream,
+
+  /// A fatal error was reported before
+  error,
+  ;
+}
@@ -0,0 +1,690 @@
+// GENERATED CODE - DO NOT MODIFY BY HAND
+// coverage:ignore-file
+// ignore_for_file: type=lint
+// ignore_for_file: unused_element, deprecated_member_use, deprecated_member_use_from_same_package, use_function_type_syntax_for_parameters, unnecessary_const, avoid_init_to_null, invalid_override_different_default_values_named, prefer_expression_function_bodies, annotate_overrides, invalid_annotation_target, unnecessary_question_mark
+
+part of 'event.dart';
+
+// **************************************************************************
+// FreezedGenerator
+// **************************************************************************
+
+// dart format off
+T _$identity<T>(T value) => value;
+/// @nodoc
+mixin _$CaptionEvent {
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent);
+}
+
+
+@override
+int get hashCode => runtimeType.hashCode;
+
+@override
+String toString() {
+  return 'CaptionEvent()';
+}
+
+
+}
+
+/// @nodoc
+class $CaptionEventCopyWith<$Res>  {
+$CaptionEventCopyWith(CaptionEvent _, $Res Function(CaptionEvent) __);
+}
+
+
+/// Adds pattern-matching-related methods to [CaptionEvent].
+extension CaptionEventPatterns on CaptionEvent {
+/// A variant of `map` that fallback to returning `orElse`.
+///
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case final Subclass value:
+///     return ...;
+///   case _:
+///     return orElse();
+/// }
+/// ```
+
+@optionalTypeArgs TResult maybeMap<TResult extends Object?>({TResult Function( CaptionEvent_Loading value)?  loading,TResult Function( CaptionEvent_Ready value)?  ready,TResult Function( CaptionEvent_Segments value)?  segments,TResult Function( CaptionEvent_VadActivity value)?  vadActivity,TResult Function( CaptionEvent_Warning value)?  warning,TResult Function( CaptionEvent_Error value)?  error,TResult Function( CaptionEvent_Exit value)?  exit,required TResult orElse(),}){
+final _that = this;
+switch (_that) {
+case CaptionEvent_Loading() when loading != null:
+return loading(_that);case CaptionEvent_Ready() when ready != null:
+return ready(_that);case CaptionEvent_Segments() when segments != null:
+return segments(_that);case CaptionEvent_VadActivity() when vadActivity != null:
+return vadActivity(_that);case CaptionEvent_Warning() when warning != null:
+return warning(_that);case CaptionEvent_Error() when error != null:
+return error(_that);case CaptionEvent_Exit() when exit != null:
+return exit(_that);case _:
+  return orElse();
+
+}
+}
+/// A `switch`-like method, using callbacks.
+///
+/// Callbacks receives the raw object, upcasted.
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case final Subclass value:
+///     return ...;
+///   case final Subclass2 value:
+///     return ...;
+/// }
+/// ```
+
+@optionalTypeArgs TResult map<TResult extends Object?>({required TResult Function( CaptionEvent_Loading value)  loading,required TResult Function( CaptionEvent_Ready value)  ready,required TResult Function( CaptionEvent_Segments value)  segments,required TResult Function( CaptionEvent_VadActivity value)  vadActivity,required TResult Function( CaptionEvent_Warning value)  warning,required TResult Function( CaptionEvent_Error value)  error,required TResult Function( CaptionEvent_Exit value)  exit,}){
+final _that = this;
+switch (_that) {
+case CaptionEvent_Loading():
+return loading(_that);case CaptionEvent_Ready():
+return ready(_that);case CaptionEvent_Segments():
+return segments(_that);case CaptionEvent_VadActivity():
+return vadActivity(_that);case CaptionEvent_Warning():
+return warning(_that);case CaptionEvent_Error():
+return error(_that);case CaptionEvent_Exit():
+return exit(_that);case _:
+  throw StateError('Unexpected subclass');
+
+}
+}
+/// A variant of `map` that fallback to returning `null`.
+///
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case final Subclass value:
+///     return ...;
+///   case _:
+///     return null;
+/// }
+/// ```
+
+@optionalTypeArgs TResult? mapOrNull<TResult extends Object?>({TResult? Function( CaptionEvent_Loading value)?  loading,TResult? Function( CaptionEvent_Ready value)?  ready,TResult? Function( CaptionEvent_Segments value)?  segments,TResult? Function( CaptionEvent_VadActivity value)?  vadActivity,TResult? Function( CaptionEvent_Warning value)?  warning,TResult? Function( CaptionEvent_Error value)?  error,TResult? Function( CaptionEvent_Exit value)?  exit,}){
+final _that = this;
+switch (_that) {
+case CaptionEvent_Loading() when loading != null:
+return loading(_that);case CaptionEvent_Ready() when ready != null:
+return ready(_that);case CaptionEvent_Segments() when segments != null:
+return segments(_that);case CaptionEvent_VadActivity() when vadActivity != null:
+return vadActivity(_that);case CaptionEvent_Warning() when warning != null:
+return warning(_that);case CaptionEvent_Error() when error != null:
+return error(_that);case CaptionEvent_Exit() when exit != null:
+return exit(_that);case _:
+  return null;
+
+}
+}
+/// A variant of `when` that fallback to an `orElse` callback.
+///
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case Subclass(:final field):
+///     return ...;
+///   case _:
+///     return orElse();
+/// }
+/// ```
+
+@optionalTypeArgs TResult maybeWhen<TResult extends Object?>({TResult Function( double? progress)?  loading,TResult Function( String backend,  String device,  List<String> providers)?  ready,TResult Function( List<Segment> segments)?  segments,TResult Function( bool speaking,  double probability)?  vadActivity,TResult Function( String msg)?  warning,TResult Function( CaptionErrorKind kind,  String msg)?  error,TResult Function( CaptionExitReason reason)?  exit,required TResult orElse(),}) {final _that = this;
+switch (_that) {
+case CaptionEvent_Loading() when loading != null:
+return loading(_that.progress);case CaptionEvent_Ready() when ready != null:
+return ready(_that.backend,_that.device,_that.providers);case CaptionEvent_Segments() when segments != null:
+return segments(_that.segments);case CaptionEvent_VadActivity() when vadActivity != null:
+return vadActivity(_that.speaking,_that.probability);case CaptionEvent_Warning() when warning != null:
+return warning(_that.msg);case CaptionEvent_Error() when error != null:
+return error(_that.kind,_that.msg);case CaptionEvent_Exit() when exit != null:
+return exit(_that.reason);case _:
+  return orElse();
+
+}
+}
+/// A `switch`-like method, using callbacks.
+///
+/// As opposed to `map`, this offers destructuring.
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case Subclass(:final field):
+///     return ...;
+///   case Subclass2(:final field2):
+///     return ...;
+/// }
+/// ```
+
+@optionalTypeArgs TResult when<TResult extends Object?>({required TResult Function( double? progress)  loading,required TResult Function( String backend,  String device,  List<String> providers)  ready,required TResult Function( List<Segment> segments)  segments,required TResult Function( bool speaking,  double probability)  vadActivity,required TResult Function( String msg)  warning,required TResult Function( CaptionErrorKind kind,  String msg)  error,required TResult Function( CaptionExitReason reason)  exit,}) {final _that = this;
+switch (_that) {
+case CaptionEvent_Loading():
+return loading(_that.progress);case CaptionEvent_Ready():
+return ready(_that.backend,_that.device,_that.providers);case CaptionEvent_Segments():
+return segments(_that.segments);case CaptionEvent_VadActivity():
+return vadActivity(_that.speaking,_that.probability);case CaptionEvent_Warning():
+return warning(_that.msg);case CaptionEvent_Error():
+return error(_that.kind,_that.msg);case CaptionEvent_Exit():
+return exit(_that.reason);case _:
+  throw StateError('Unexpected subclass');
+
+}
+}
+/// A variant of `when` that fallback to returning `null`
+///
+/// It is equivalent to doing:
+/// ```dart
+/// switch (sealedClass) {
+///   case Subclass(:final field):
+///     return ...;
+///   case _:
+///     return null;
+/// }
+/// ```
+
+@optionalTypeArgs TResult? whenOrNull<TResult extends Object?>({TResult? Function( double? progress)?  loading,TResult? Function( String backend,  String device,  List<String> providers)?  ready,TResult? Function( List<Segment> segments)?  segments,TResult? Function( bool speaking,  double probability)?  vadActivity,TResult? Function( String msg)?  warning,TResult? Function( CaptionErrorKind kind,  String msg)?  error,TResult? Function( CaptionExitReason reason)?  exit,}) {final _that = this;
+switch (_that) {
+case CaptionEvent_Loading() when loading != null:
+return loading(_that.progress);case CaptionEvent_Ready() when ready != null:
+return ready(_that.backend,_that.device,_that.providers);case CaptionEvent_Segments() when segments != null:
+return segments(_that.segments);case CaptionEvent_VadActivity() when vadActivity != null:
+return vadActivity(_that.speaking,_that.probability);case CaptionEvent_Warning() when warning != null:
+return warning(_that.msg);case CaptionEvent_Error() when error != null:
+return error(_that.kind,_that.msg);case CaptionEvent_Exit() when exit != null:
+return exit(_that.reason);case _:
+  return null;
+
+}
+}
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Loading extends CaptionEvent {
+  const CaptionEvent_Loading({this.progress}): super._();
+  
+
+ final  double? progress;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_LoadingCopyWith<CaptionEvent_Loading> get copyWith => _$CaptionEvent_LoadingCopyWithImpl<CaptionEvent_Loading>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Loading&&(identical(other.progress, progress) || other.progress == progress));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,progress);
+
+@override
+String toString() {
+  return 'CaptionEvent.loading(progress: $progress)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_LoadingCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_LoadingCopyWith(CaptionEvent_Loading value, $Res Function(CaptionEvent_Loading) _then) = _$CaptionEvent_LoadingCopyWithImpl;
+@useResult
+$Res call({
+ double? progress
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_LoadingCopyWithImpl<$Res>
+    implements $CaptionEvent_LoadingCopyWith<$Res> {
+  _$CaptionEvent_LoadingCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Loading _self;
+  final $Res Function(CaptionEvent_Loading) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? progress = freezed,}) {
+  return _then(CaptionEvent_Loading(
+progress: freezed == progress ? _self.progress : progress // ignore: cast_nullable_to_non_nullable
+as double?,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Ready extends CaptionEvent {
+  const CaptionEvent_Ready({required this.backend, required this.device, required final  List<String> providers}): _providers = providers,super._();
+  
+
+ final  String backend;
+ final  String device;
+ final  List<String> _providers;
+ List<String> get providers {
+  if (_providers is EqualUnmodifiableListView) return _providers;
+  // ignore: implicit_dynamic_type
+  return EqualUnmodifiableListView(_providers);
+}
+
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_ReadyCopyWith<CaptionEvent_Ready> get copyWith => _$CaptionEvent_ReadyCopyWithImpl<CaptionEvent_Ready>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Ready&&(identical(other.backend, backend) || other.backend == backend)&&(identical(other.device, device) || other.device == device)&&const DeepCollectionEquality().equals(other._providers, _providers));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,backend,device,const DeepCollectionEquality().hash(_providers));
+
+@override
+String toString() {
+  return 'CaptionEvent.ready(backend: $backend, device: $device, providers: $providers)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_ReadyCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_ReadyCopyWith(CaptionEvent_Ready value, $Res Function(CaptionEvent_Ready) _then) = _$CaptionEvent_ReadyCopyWithImpl;
+@useResult
+$Res call({
+ String backend, String device, List<String> providers
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_ReadyCopyWithImpl<$Res>
+    implements $CaptionEvent_ReadyCopyWith<$Res> {
+  _$CaptionEvent_ReadyCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Ready _self;
+  final $Res Function(CaptionEvent_Ready) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? backend = null,Object? device = null,Object? providers = null,}) {
+  return _then(CaptionEvent_Ready(
+backend: null == backend ? _self.backend : backend // ignore: cast_nullable_to_non_nullable
+as String,device: null == device ? _self.device : device // ignore: cast_nullable_to_non_nullable
+as String,providers: null == providers ? _self._providers : providers // ignore: cast_nullable_to_non_nullable
+as List<String>,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Segments extends CaptionEvent {
+  const CaptionEvent_Segments({required final  List<Segment> segments}): _segments = segments,super._();
+  
+
+ final  List<Segment> _segments;
+ List<Segment> get segments {
+  if (_segments is EqualUnmodifiableListView) return _segments;
+  // ignore: implicit_dynamic_type
+  return EqualUnmodifiableListView(_segments);
+}
+
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_SegmentsCopyWith<CaptionEvent_Segments> get copyWith => _$CaptionEvent_SegmentsCopyWithImpl<CaptionEvent_Segments>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Segments&&const DeepCollectionEquality().equals(other._segments, _segments));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,const DeepCollectionEquality().hash(_segments));
+
+@override
+String toString() {
+  return 'CaptionEvent.segments(segments: $segments)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_SegmentsCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_SegmentsCopyWith(CaptionEvent_Segments value, $Res Function(CaptionEvent_Segments) _then) = _$CaptionEvent_SegmentsCopyWithImpl;
+@useResult
+$Res call({
+ List<Segment> segments
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_SegmentsCopyWithImpl<$Res>
+    implements $CaptionEvent_SegmentsCopyWith<$Res> {
+  _$CaptionEvent_SegmentsCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Segments _self;
+  final $Res Function(CaptionEvent_Segments) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? segments = null,}) {
+  return _then(CaptionEvent_Segments(
+segments: null == segments ? _self._segments : segments // ignore: cast_nullable_to_non_nullable
+as List<Segment>,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_VadActivity extends CaptionEvent {
+  const CaptionEvent_VadActivity({required this.speaking, required this.probability}): super._();
+  
+
+ final  bool speaking;
+ final  double probability;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_VadActivityCopyWith<CaptionEvent_VadActivity> get copyWith => _$CaptionEvent_VadActivityCopyWithImpl<CaptionEvent_VadActivity>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_VadActivity&&(identical(other.speaking, speaking) || other.speaking == speaking)&&(identical(other.probability, probability) || other.probability == probability));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,speaking,probability);
+
+@override
+String toString() {
+  return 'CaptionEvent.vadActivity(speaking: $speaking, probability: $probability)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_VadActivityCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_VadActivityCopyWith(CaptionEvent_VadActivity value, $Res Function(CaptionEvent_VadActivity) _then) = _$CaptionEvent_VadActivityCopyWithImpl;
+@useResult
+$Res call({
+ bool speaking, double probability
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_VadActivityCopyWithImpl<$Res>
+    implements $CaptionEvent_VadActivityCopyWith<$Res> {
+  _$CaptionEvent_VadActivityCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_VadActivity _self;
+  final $Res Function(CaptionEvent_VadActivity) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? speaking = null,Object? probability = null,}) {
+  return _then(CaptionEvent_VadActivity(
+speaking: null == speaking ? _self.speaking : speaking // ignore: cast_nullable_to_non_nullable
+as bool,probability: null == probability ? _self.probability : probability // ignore: cast_nullable_to_non_nullable
+as double,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Warning extends CaptionEvent {
+  const CaptionEvent_Warning({required this.msg}): super._();
+  
+
+ final  String msg;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_WarningCopyWith<CaptionEvent_Warning> get copyWith => _$CaptionEvent_WarningCopyWithImpl<CaptionEvent_Warning>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Warning&&(identical(other.msg, msg) || other.msg == msg));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,msg);
+
+@override
+String toString() {
+  return 'CaptionEvent.warning(msg: $msg)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_WarningCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_WarningCopyWith(CaptionEvent_Warning value, $Res Function(CaptionEvent_Warning) _then) = _$CaptionEvent_WarningCopyWithImpl;
+@useResult
+$Res call({
+ String msg
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_WarningCopyWithImpl<$Res>
+    implements $CaptionEvent_WarningCopyWith<$Res> {
+  _$CaptionEvent_WarningCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Warning _self;
+  final $Res Function(CaptionEvent_Warning) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? msg = null,}) {
+  return _then(CaptionEvent_Warning(
+msg: null == msg ? _self.msg : msg // ignore: cast_nullable_to_non_nullable
+as String,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Error extends CaptionEvent {
+  const CaptionEvent_Error({required this.kind, required this.msg}): super._();
+  
+
+ final  CaptionErrorKind kind;
+ final  String msg;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_ErrorCopyWith<CaptionEvent_Error> get copyWith => _$CaptionEvent_ErrorCopyWithImpl<CaptionEvent_Error>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Error&&(identical(other.kind, kind) || other.kind == kind)&&(identical(other.msg, msg) || other.msg == msg));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,kind,msg);
+
+@override
+String toString() {
+  return 'CaptionEvent.error(kind: $kind, msg: $msg)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_ErrorCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_ErrorCopyWith(CaptionEvent_Error value, $Res Function(CaptionEvent_Error) _then) = _$CaptionEvent_ErrorCopyWithImpl;
+@useResult
+$Res call({
+ CaptionErrorKind kind, String msg
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_ErrorCopyWithImpl<$Res>
+    implements $CaptionEvent_ErrorCopyWith<$Res> {
+  _$CaptionEvent_ErrorCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Error _self;
+  final $Res Function(CaptionEvent_Error) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? kind = null,Object? msg = null,}) {
+  return _then(CaptionEvent_Error(
+kind: null == kind ? _self.kind : kind // ignore: cast_nullable_to_non_nullable
+as CaptionErrorKind,msg: null == msg ? _self.msg : msg // ignore: cast_nullable_to_non_nullable
+as String,
+  ));
+}
+
+
+}
+
+/// @nodoc
+
+
+class CaptionEvent_Exit extends CaptionEvent {
+  const CaptionEvent_Exit({required this.reason}): super._();
+  
+
+ final  CaptionExitReason reason;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@JsonKey(includeFromJson: false, includeToJson: false)
+@pragma('vm:prefer-inline')
+$CaptionEvent_ExitCopyWith<CaptionEvent_Exit> get copyWith => _$CaptionEvent_ExitCopyWithImpl<CaptionEvent_Exit>(this, _$identity);
+
+
+
+@override
+bool operator ==(Object other) {
+  return identical(this, other) || (other.runtimeType == runtimeType&&other is CaptionEvent_Exit&&(identical(other.reason, reason) || other.reason == reason));
+}
+
+
+@override
+int get hashCode => Object.hash(runtimeType,reason);
+
+@override
+String toString() {
+  return 'CaptionEvent.exit(reason: $reason)';
+}
+
+
+}
+
+/// @nodoc
+abstract mixin class $CaptionEvent_ExitCopyWith<$Res> implements $CaptionEventCopyWith<$Res> {
+  factory $CaptionEvent_ExitCopyWith(CaptionEvent_Exit value, $Res Function(CaptionEvent_Exit) _then) = _$CaptionEvent_ExitCopyWithImpl;
+@useResult
+$Res call({
+ CaptionExitReason reason
+});
+
+
+
+
+}
+/// @nodoc
+class _$CaptionEvent_ExitCopyWithImpl<$Res>
+    implements $CaptionEvent_ExitCopyWith<$Res> {
+  _$CaptionEvent_ExitCopyWithImpl(this._self, this._then);
+
+  final CaptionEvent_Exit _self;
+  final $Res Function(CaptionEvent_Exit) _then;
+
+/// Create a copy of CaptionEvent
+/// with the given fields replaced by the non-null parameter values.
+@pragma('vm:prefer-inline') $Res call({Object? reason = null,}) {
+  return _then(CaptionEvent_Exit(
+reason: null == reason ? _self.reason : reason // ignore: cast_nullable_to_non_nullable
+as CaptionExitReason,
+  ));
+}
+
+
+}
+
+// dart format on
@@ -3,8 +3,12 @@
 
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
+import 'api/audio.dart';
 import 'api/text_util.dart';
 import 'api/whisper.dart';
+import 'audio_capture/traits.dart';
+import 'caption/config.dart';
+import 'caption/event.dart';
 import 'candle_models/whisper/model.dart';
 import 'dart:async';
 import 'dart:convert';
@@ -87,21 +91,18 @@ abstract class RustLibApi extends BaseApi {
 
   Future<String> crateApiWhisperCreateCancellationToken();
 
-  Stream<List<Segment>> crateApiWhisperLaunchCaption({
+  Stream<CaptionEvent> crateApiWhisperLaunchCaption({
     required WhisperClient whisperClient,
-    String? audioDevice,
-    bool? audioDeviceIsInput,
-    String? audioLanguage,
     required String cancelTokenId,
-    bool? withTimestamps,
-    bool? verbose,
-    bool? tryWithCuda,
-    int? whisperMaxAudioDuration,
-    BigInt? inferenceInterval,
-    BigInt? whisperDefaultMaxDecodeTokens,
-    double? whisperTemperature,
-    String? vadModelPath,
-    double? vadFiltersValue,
+    required CaptionConfig config,
+  });
+
+  Future<List<AudioDeviceInfo>> crateApiAudioListAudioDevices();
+
+  Future<List<Segment>> crateApiWhisperTranscribeFile({
+    required WhisperClient whisperClient,
+    required String filePath,
+    required CaptionConfig config,
   });
 
   Future<WhisperClient> crateApiWhisperWhisperClientNew({
@@ -206,61 +207,26 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       const TaskConstMeta(debugName: "create_cancellation_token", argNames: []);
 
   @override
-  Stream<List<Segment>> crateApiWhisperLaunchCaption({
+  Stream<CaptionEvent> crateApiWhisperLaunchCaption({
     required WhisperClient whisperClient,
-    String? audioDevice,
-    bool? audioDeviceIsInput,
-    String? audioLanguage,
     required String cancelTokenId,
-    bool? withTimestamps,
-    bool? verbose,
-    bool? tryWithCuda,
-    int? whisperMaxAudioDuration,
-    BigInt? inferenceInterval,
-    BigInt? whisperDefaultMaxDecodeTokens,
-    double? whisperTemperature,
-    String? vadModelPath,
-    double? vadFiltersValue,
+    required CaptionConfig config,
   }) {
-    final streamSink = RustStreamSink<List<Segment>>();
+    final streamSink = RustStreamSink<CaptionEvent>();
     unawaited(
       handler.executeNormal(
         NormalTask(
           callFfi: (port_) {
             var arg0 = cst_encode_box_autoadd_whisper_client(whisperClient);
-            var arg1 = cst_encode_StreamSink_list_segment_Dco(streamSink);
-            var arg2 = cst_encode_opt_String(audioDevice);
-            var arg3 = cst_encode_opt_box_autoadd_bool(audioDeviceIsInput);
-            var arg4 = cst_encode_opt_String(audioLanguage);
-            var arg5 = cst_encode_String(cancelTokenId);
-            var arg6 = cst_encode_opt_box_autoadd_bool(withTimestamps);
-            var arg7 = cst_encode_opt_box_autoadd_bool(verbose);
-            var arg8 = cst_encode_opt_box_autoadd_bool(tryWithCuda);
-            var arg9 = cst_encode_opt_box_autoadd_u_32(whisperMaxAudioDuration);
-            var arg10 = cst_encode_opt_box_autoadd_u_64(inferenceInterval);
-            var arg11 = cst_encode_opt_box_autoadd_usize(
-              whisperDefaultMaxDecodeTokens,
-            );
-            var arg12 = cst_encode_opt_box_autoadd_f_32(whisperTemperature);
-            var arg13 = cst_encode_opt_String(vadModelPath);
-            var arg14 = cst_encode_opt_box_autoadd_f_32(vadFiltersValue);
+            var arg1 = cst_encode_StreamSink_caption_event_Dco(streamSink);
+            var arg2 = cst_encode_String(cancelTokenId);
+            var arg3 = cst_encode_box_autoadd_caption_config(config);
             return wire.wire__crate__api__whisper__launch_caption(
               port_,
               arg0,
               arg1,
               arg2,
               arg3,
-              arg4,
-              arg5,
-              arg6,
-              arg7,
-              arg8,
-              arg9,
-              arg10,
-              arg11,
-              arg12,
-              arg13,
-              arg14,
             );
           },
           codec: DcoCodec(
@@ -268,23 +234,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
             decodeErrorData: dco_decode_AnyhowException,
           ),
           constMeta: kCrateApiWhisperLaunchCaptionConstMeta,
-          argValues: [
-            whisperClient,
-            streamSink,
-            audioDevice,
-            audioDeviceIsInput,
-            audioLanguage,
-            cancelTokenId,
-            withTimestamps,
-            verbose,
-            tryWithCuda,
-            whisperMaxAudioDuration,
-            inferenceInterval,
-            whisperDefaultMaxDecodeTokens,
-            whisperTemperature,
-            vadModelPath,
-            vadFiltersValue,
-          ],
+          argValues: [whisperClient, streamSink, cancelTokenId, config],
           apiImpl: this,
         ),
       ),
@@ -295,23 +245,64 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   TaskConstMeta get kCrateApiWhisperLaunchCaptionConstMeta =>
       const TaskConstMeta(
         debugName: "launch_caption",
-        argNames: [
-          "whisperClient",
-          "streamSink",
-          "audioDevice",
-          "audioDeviceIsInput",
-          "audioLanguage",
-          "cancelTokenId",
-          "withTimestamps",
-          "verbose",
-          "tryWithCuda",
-          "whisperMaxAudioDuration",
-          "inferenceInterval",
-          "whisperDefaultMaxDecodeTokens",
-          "whisperTemperature",
-          "vadModelPath",
-          "vadFiltersValue",
-        ],
+        argNames: ["whisperClient", "streamSink", "cancelTokenId", "config"],
+      );
+
+  @override
+  Future<List<AudioDeviceInfo>> crateApiAudioListAudioDevices() {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          return wire.wire__crate__api__audio__list_audio_devices(port_);
+        },
+        codec: DcoCodec(
+          decodeSuccessData: dco_decode_list_audio_device_info,
+          decodeErrorData: dco_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiAudioListAudioDevicesConstMeta,
+        argValues: [],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiAudioListAudioDevicesConstMeta =>
+      const TaskConstMeta(debugName: "list_audio_devices", argNames: []);
+
+  @override
+  Future<List<Segment>> crateApiWhisperTranscribeFile({
+    required WhisperClient whisperClient,
+    required String filePath,
+    required CaptionConfig config,
+  }) {
+    return handler.executeNormal(
+      NormalTask(
+        callFfi: (port_) {
+          var arg0 = cst_encode_box_autoadd_whisper_client(whisperClient);
+          var arg1 = cst_encode_String(filePath);
+          var arg2 = cst_encode_box_autoadd_caption_config(config);
+          return wire.wire__crate__api__whisper__transcribe_file(
+            port_,
+            arg0,
+            arg1,
+            arg2,
+          );
+        },
+        codec: DcoCodec(
+          decodeSuccessData: dco_decode_list_segment,
+          decodeErrorData: dco_decode_AnyhowException,
+        ),
+        constMeta: kCrateApiWhisperTranscribeFileConstMeta,
+        argValues: [whisperClient, filePath, config],
+        apiImpl: this,
+      ),
+    );
+  }
+
+  TaskConstMeta get kCrateApiWhisperTranscribeFileConstMeta =>
+      const TaskConstMeta(
+        debugName: "transcribe_file",
+        argNames: ["whisperClient", "filePath", "config"],
       );
 
   @override
@@ -390,7 +381,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  RustStreamSink<List<Segment>> dco_decode_StreamSink_list_segment_Dco(
+  RustStreamSink<CaptionEvent> dco_decode_StreamSink_caption_event_Dco(
     dynamic raw,
   ) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -409,6 +400,28 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return BigInt.parse(raw);
   }
 
+  @protected
+  AudioDeviceDirection dco_decode_audio_device_direction(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return AudioDeviceDirection.values[raw as int];
+  }
+
+  @protected
+  AudioDeviceInfo dco_decode_audio_device_info(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 6)
+      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+    return AudioDeviceInfo(
+      name: dco_decode_String(arr[0]),
+      id: dco_decode_String(arr[1]),
+      direction: dco_decode_audio_device_direction(arr[2]),
+      isDefault: dco_decode_bool(arr[3]),
+      sampleRate: dco_decode_u_32(arr[4]),
+      channels: dco_decode_u_32(arr[5]),
+    );
+  }
+
   @protected
   bool dco_decode_bool(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -421,6 +434,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw as bool;
   }
 
+  @protected
+  CaptionConfig dco_decode_box_autoadd_caption_config(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return dco_decode_caption_config(raw);
+  }
+
   @protected
   double dco_decode_box_autoadd_f_32(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -451,6 +470,103 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return dco_decode_whisper_client(raw);
   }
 
+  @protected
+  CaptionConfig dco_decode_caption_config(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 34)
+      throw Exception('unexpected arr length: expect 34 but see ${arr.length}');
+    return CaptionConfig(
+      audioDevice: dco_decode_opt_String(arr[0]),
+      audioDeviceIsInput: dco_decode_bool(arr[1]),
+      language: dco_decode_opt_String(arr[2]),
+      withTimestamps: dco_decode_bool(arr[3]),
+      wordTimestamps: dco_decode_bool(arr[4]),
+      verbose: dco_decode_bool(arr[5]),
+      tryWithCuda: dco_decode_bool(arr[6]),
+      maxAudioDurationSecs: dco_decode_u_32(arr[7]),
+      inferenceIntervalMs: dco_decode_u_64(arr[8]),
+      inferenceTimeoutMs: dco_decode_opt_box_autoadd_u_64(arr[9]),
+      maxTokensPerSegment: dco_decode_opt_box_autoadd_u_32(arr[10]),
+      temperature: dco_decode_opt_box_autoadd_f_32(arr[11]),
+      vadModelPath: dco_decode_opt_String(arr[12]),
+      vadThreshold: dco_decode_opt_box_autoadd_f_32(arr[13]),
+      localAgreement: dco_decode_opt_box_autoadd_u_32(arr[14]),
+      endpointSilenceMs: dco_decode_opt_box_autoadd_u_32(arr[15]),
+      subtitlePath: dco_decode_opt_String(arr[16]),
+      recordPath: dco_decode_opt_String(arr[17]),
+      broadcastAddr: dco_decode_opt_String(arr[18]),
+      obsTextPath: dco_decode_opt_String(arr[19]),
+      obsTextLines: dco_decode_u_32(arr[20]),
+      obsWebsocketUrl: dco_decode_opt_String(arr[21]),
+      obsWebsocketPassword: dco_decode_opt_String(arr[22]),
+      beamSize: dco_decode_opt_box_autoadd_u_32(arr[23]),
+      beamPatience: dco_decode_opt_box_autoadd_f_32(arr[24]),
+      lengthPenalty: dco_decode_opt_box_autoadd_f_32(arr[25]),
+      initialPrompt: dco_decode_opt_String(arr[26]),
+      conditionOnPreviousText: dco_decode_opt_box_autoadd_u_32(arr[27]),
+      translate: dco_decode_bool(arr[28]),
+      redetectLanguage: dco_decode_bool(arr[29]),
+      languageSwitchThreshold: dco_decode_opt_box_autoadd_f_32(arr[30]),
+      languageSwitchWindows: dco_decode_opt_box_autoadd_u_32(arr[31]),
+      inlineEmoji: dco_decode_bool(arr[32]),
+      withItn: dco_decode_bool(arr[33]),
+    );
+  }
+
+  @protected
+  CaptionErrorKind dco_decode_caption_error_kind(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return CaptionErrorKind.values[raw as int];
+  }
+
+  @protected
+  CaptionEvent dco_decode_caption_event(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    switch (raw[0]) {
+      case 0:
+        return CaptionEvent_Loading(
+          progress: dco_decode_opt_box_autoadd_f_32(raw[1]),
+        );
+      case 1:
+        return CaptionEvent_Ready(
+          backend: dco_decode_String(raw[1]),
+          device: dco_decode_String(raw[2]),
+          providers: dco_decode_list_String(raw[3]),
+        );
+      case 2:
+        return CaptionEvent_Segments(
+          segments: dco_decode_list_segment(raw[1]),
+        );
+      case 3:
+        return CaptionEvent_VadActivity(
+          speaking: dco_decode_bool(raw[1]),
+          probability: dco_decode_f_32(raw[2]),
+        );
+      case 4:
+        return CaptionEvent_Warning(
+          msg: dco_decode_String(raw[1]),
+        );
+      case 5:
+        return CaptionEvent_Error(
+          kind: dco_decode_caption_error_kind(raw[1]),
+          msg: dco_decode_String(raw[2]),
+        );
+      case 6:
+        return CaptionEvent_Exit(
+          reason: dco_decode_caption_exit_reason(raw[1]),
+        );
+      default:
+        throw Exception("unreachable");
+    }
+  }
+
+  @protected
+  CaptionExitReason dco_decode_caption_exit_reason(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return CaptionExitReason.values[raw as int];
+  }
+
   @protected
   DecodingResult dco_decode_decoding_result(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -485,6 +601,18 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return raw as int;
   }
 
+  @protected
+  List<String> dco_decode_list_String(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_String).toList();
+  }
+
+  @protected
+  List<AudioDeviceInfo> dco_decode_list_audio_device_info(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_audio_device_info).toList();
+  }
+
   @protected
   Uint32List dco_decode_list_prim_u_32_strict(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -515,6 +643,24 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (raw as List<dynamic>).map(dco_decode_segment).toList();
   }
 
+  @protected
+  List<SubSegment> dco_decode_list_sub_segment(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_sub_segment).toList();
+  }
+
+  @protected
+  List<WordTiming> dco_decode_list_word_timing(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_word_timing).toList();
+  }
+
+  @protected
+  List<LanguageProbability> dco_decode_list_language_probability(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    return (raw as List<dynamic>).map(dco_decode_language_probability).toList();
+  }
+
   @protected
   String? dco_decode_opt_String(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
@@ -571,8 +717,8 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   Segment dco_decode_segment(dynamic raw) {
     // Codec=Dco (DartCObject based), see doc to use other codecs
     final arr = raw as List<dynamic>;
-    if (arr.length != 7)
-      throw Exception('unexpected arr length: expect 7 but see ${arr.length}');
+    if (arr.length != 13)
+      throw Exception('unexpected arr length: expect 13 but see ${arr.length}');
     return Segment(
       start: dco_decode_f_64(arr[0]),
       duration: dco_decode_f_64(arr[1]),
@@ -581,6 +727,26 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       reasoningLang: dco_decode_opt_String(arr[4]),
       audioDuration: dco_decode_opt_U128(arr[5]),
       status: dco_decode_whisper_status(arr[6]),
+      subSegments: dco_decode_list_sub_segment(arr[7]),
+      id: dco_decode_u_64(arr[8]),
+      committed: dco_decode_bool(arr[9]),
+      languageProbs: dco_decode_list_language_probability(arr[10]),
+      emotion: dco_decode_opt_String(arr[11]),
+      audioEvent: dco_decode_opt_String(arr[12]),
+    );
+  }
+
+  @protected
+  SubSegment dco_decode_sub_segment(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 4)
+      throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+    return SubSegment(
+      start: dco_decode_f_64(arr[0]),
+      end: dco_decode_f_64(arr[1]),
+      text: dco_decode_String(arr[2]),
+      words: dco_decode_list_word_timing(arr[3]),
     );
   }
 
@@ -636,6 +802,31 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return WhisperStatus.values[raw as int];
   }
 
+  @protected
+  WordTiming dco_decode_word_timing(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 3)
+      throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    return WordTiming(
+      start: dco_decode_f_64(arr[0]),
+      end: dco_decode_f_64(arr[1]),
+      word: dco_decode_String(arr[2]),
+    );
+  }
+
+  @protected
+  LanguageProbability dco_decode_language_probability(dynamic raw) {
+    // Codec=Dco (DartCObject based), see doc to use other codecs
+    final arr = raw as List<dynamic>;
+    if (arr.length != 2)
+      throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+    return LanguageProbability(
+      language: dco_decode_String(arr[0]),
+      probability: dco_decode_f_32(arr[1]),
+    );
+  }
+
   @protected
   AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -653,7 +844,7 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  RustStreamSink<List<Segment>> sse_decode_StreamSink_list_segment_Dco(
+  RustStreamSink<CaptionEvent> sse_decode_StreamSink_caption_event_Dco(
     SseDeserializer deserializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -674,6 +865,34 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return BigInt.parse(inner);
   }
 
+  @protected
+  AudioDeviceDirection sse_decode_audio_device_direction(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return AudioDeviceDirection.values[inner];
+  }
+
+  @protected
+  AudioDeviceInfo sse_decode_audio_device_info(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_name = sse_decode_String(deserializer);
+    var var_id = sse_decode_String(deserializer);
+    var var_direction = sse_decode_audio_device_direction(deserializer);
+    var var_isDefault = sse_decode_bool(deserializer);
+    var var_sampleRate = sse_decode_u_32(deserializer);
+    var var_channels = sse_decode_u_32(deserializer);
+    return AudioDeviceInfo(
+      name: var_name,
+      id: var_id,
+      direction: var_direction,
+      isDefault: var_isDefault,
+      sampleRate: var_sampleRate,
+      channels: var_channels,
+    );
+  }
+
   @protected
   bool sse_decode_bool(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -686,6 +905,14 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (sse_decode_bool(deserializer));
   }
 
+  @protected
+  CaptionConfig sse_decode_box_autoadd_caption_config(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    return (sse_decode_caption_config(deserializer));
+  }
+
   @protected
   double sse_decode_box_autoadd_f_32(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -718,6 +945,140 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return (sse_decode_whisper_client(deserializer));
   }
 
+  @protected
+  CaptionConfig sse_decode_caption_config(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_audioDevice = sse_decode_opt_String(deserializer);
+    var var_audioDeviceIsInput = sse_decode_bool(deserializer);
+    var var_language = sse_decode_opt_String(deserializer);
+    var var_withTimestamps = sse_decode_bool(deserializer);
+    var var_wordTimestamps = sse_decode_bool(deserializer);
+    var var_verbose = sse_decode_bool(deserializer);
+    var var_tryWithCuda = sse_decode_bool(deserializer);
+    var var_maxAudioDurationSecs = sse_decode_u_32(deserializer);
+    var var_inferenceIntervalMs = sse_decode_u_64(deserializer);
+    var var_inferenceTimeoutMs = sse_decode_opt_box_autoadd_u_64(deserializer);
+    var var_maxTokensPerSegment = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_temperature = sse_decode_opt_box_autoadd_f_32(deserializer);
+    var var_vadModelPath = sse_decode_opt_String(deserializer);
+    var var_vadThreshold = sse_decode_opt_box_autoadd_f_32(deserializer);
+    var var_localAgreement = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_endpointSilenceMs = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_subtitlePath = sse_decode_opt_String(deserializer);
+    var var_recordPath = sse_decode_opt_String(deserializer);
+    var var_broadcastAddr = sse_decode_opt_String(deserializer);
+    var var_obsTextPath = sse_decode_opt_String(deserializer);
+    var var_obsTextLines = sse_decode_u_32(deserializer);
+    var var_obsWebsocketUrl = sse_decode_opt_String(deserializer);
+    var var_obsWebsocketPassword = sse_decode_opt_String(deserializer);
+    var var_beamSize = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_beamPatience = sse_decode_opt_box_autoadd_f_32(deserializer);
+    var var_lengthPenalty = sse_decode_opt_box_autoadd_f_32(deserializer);
+    var var_initialPrompt = sse_decode_opt_String(deserializer);
+    var var_conditionOnPreviousText = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_translate = sse_decode_bool(deserializer);
+    var var_redetectLanguage = sse_decode_bool(deserializer);
+    var var_languageSwitchThreshold = sse_decode_opt_box_autoadd_f_32(deserializer);
+    var var_languageSwitchWindows = sse_decode_opt_box_autoadd_u_32(deserializer);
+    var var_inlineEmoji = sse_decode_bool(deserializer);
+    var var_withItn = sse_decode_bool(deserializer);
+    return CaptionConfig(
+      audioDevice: var_audioDevice,
+      audioDeviceIsInput: var_audioDeviceIsInput,
+      language: var_language,
+      withTimestamps: var_withTimestamps,
+      wordTimestamps: var_wordTimestamps,
+      verbose: var_verbose,
+      tryWithCuda: var_tryWithCuda,
+      maxAudioDurationSecs: var_maxAudioDurationSecs,
+      inferenceIntervalMs: var_inferenceIntervalMs,
+      inferenceTimeoutMs: var_inferenceTimeoutMs,
+      maxTokensPerSegment: var_maxTokensPerSegment,
+      temperature: var_temperature,
+      vadModelPath: var_vadModelPath,
+      vadThreshold: var_vadThreshold,
+      localAgreement: var_localAgreement,
+      endpointSilenceMs: var_endpointSilenceMs,
+      subtitlePath: var_subtitlePath,
+      recordPath: var_recordPath,
+      broadcastAddr: var_broadcastAddr,
+      obsTextPath: var_obsTextPath,
+      obsTextLines: var_obsTextLines,
+      obsWebsocketUrl: var_obsWebsocketUrl,
+      obsWebsocketPassword: var_obsWebsocketPassword,
+      beamSize: var_beamSize,
+      beamPatience: var_beamPatience,
+      lengthPenalty: var_lengthPenalty,
+      initialPrompt: var_initialPrompt,
+      conditionOnPreviousText: var_conditionOnPreviousText,
+      translate: var_translate,
+      redetectLanguage: var_redetectLanguage,
+      languageSwitchThreshold: var_languageSwitchThreshold,
+      languageSwitchWindows: var_languageSwitchWindows,
+      inlineEmoji: var_inlineEmoji,
+      withItn: var_withItn,
+    );
+  }
+
+  @protected
+  CaptionErrorKind sse_decode_caption_error_kind(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return CaptionErrorKind.values[inner];
+  }
+
+  @protected
+  CaptionEvent sse_decode_caption_event(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var tag_ = sse_decode_i_32(deserializer);
+    switch (tag_) {
+      case 0:
+        var var_progress = sse_decode_opt_box_autoadd_f_32(deserializer);
+        return CaptionEvent_Loading(progress: var_progress);
+      case 1:
+        var var_backend = sse_decode_String(deserializer);
+        var var_device = sse_decode_String(deserializer);
+        var var_providers = sse_decode_list_String(deserializer);
+        return CaptionEvent_Ready(
+          backend: var_backend,
+          device: var_device,
+          providers: var_providers,
+        );
+      case 2:
+        var var_segments = sse_decode_list_segment(deserializer);
+        return CaptionEvent_Segments(segments: var_segments);
+      case 3:
+        var var_speaking = sse_decode_bool(deserializer);
+        var var_probability = sse_decode_f_32(deserializer);
+        return CaptionEvent_VadActivity(
+          speaking: var_speaking,
+          probability: var_probability,
+        );
+      case 4:
+        var var_msg = sse_decode_String(deserializer);
+        return CaptionEvent_Warning(msg: var_msg);
+      case 5:
+        var var_kind = sse_decode_caption_error_kind(deserializer);
+        var var_msg = sse_decode_String(deserializer);
+        return CaptionEvent_Error(kind: var_kind, msg: var_msg);
+      case 6:
+        var var_reason = sse_decode_caption_exit_reason(deserializer);
+        return CaptionEvent_Exit(reason: var_reason);
+      default:
+        throw UnimplementedError('');
+    }
+  }
+
+  @protected
+  CaptionExitReason sse_decode_caption_exit_reason(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var inner = sse_decode_i_32(deserializer);
+    return CaptionExitReason.values[inner];
+  }
+
   @protected
   DecodingResult sse_decode_decoding_result(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -755,6 +1116,32 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return deserializer.buffer.getInt32();
   }
 
+  @protected
+  List<String> sse_decode_list_String(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <String>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_String(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<AudioDeviceInfo> sse_decode_list_audio_device_info(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <AudioDeviceInfo>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_audio_device_info(deserializer));
+    }
+    return ans_;
+  }
+
   @protected
   Uint32List sse_decode_list_prim_u_32_strict(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -802,6 +1189,44 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return ans_;
   }
 
+  @protected
+  List<SubSegment> sse_decode_list_sub_segment(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <SubSegment>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_sub_segment(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<WordTiming> sse_decode_list_word_timing(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <WordTiming>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_word_timing(deserializer));
+    }
+    return ans_;
+  }
+
+  @protected
+  List<LanguageProbability> sse_decode_list_language_probability(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+
+    var len_ = sse_decode_i_32(deserializer);
+    var ans_ = <LanguageProbability>[];
+    for (var idx_ = 0; idx_ < len_; ++idx_) {
+      ans_.add(sse_decode_language_probability(deserializer));
+    }
+    return ans_;
+  }
+
   @protected
   String? sse_decode_opt_String(SseDeserializer deserializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -899,6 +1324,12 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     var var_reasoningLang = sse_decode_opt_String(deserializer);
     var var_audioDuration = sse_decode_opt_U128(deserializer);
     var var_status = sse_decode_whisper_status(deserializer);
+    var var_subSegments = sse_decode_list_sub_segment(deserializer);
+    var var_id = sse_decode_u_64(deserializer);
+    var var_committed = sse_decode_bool(deserializer);
+    var var_languageProbs = sse_decode_list_language_probability(deserializer);
+    var var_emotion = sse_decode_opt_String(deserializer);
+    var var_audioEvent = sse_decode_opt_String(deserializer);
     return Segment(
       start: var_start,
       duration: var_duration,
@@ -907,6 +1338,27 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
       reasoningLang: var_reasoningLang,
       audioDuration: var_audioDuration,
       status: var_status,
+      subSegments: var_subSegments,
+      id: var_id,
+      committed: var_committed,
+      languageProbs: var_languageProbs,
+      emotion: var_emotion,
+      audioEvent: var_audioEvent,
+    );
+  }
+
+  @protected
+  SubSegment sse_decode_sub_segment(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_start = sse_decode_f_64(deserializer);
+    var var_end = sse_decode_f_64(deserializer);
+    var var_text = sse_decode_String(deserializer);
+    var var_words = sse_decode_list_word_timing(deserializer);
+    return SubSegment(
+      start: var_start,
+      end: var_end,
+      text: var_text,
+      words: var_words,
     );
   }
 
@@ -965,12 +1417,52 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     return WhisperStatus.values[inner];
   }
 
+  @protected
+  WordTiming sse_decode_word_timing(SseDeserializer deserializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_start = sse_decode_f_64(deserializer);
+    var var_end = sse_decode_f_64(deserializer);
+    var var_word = sse_decode_String(deserializer);
+    return WordTiming(start: var_start, end: var_end, word: var_word);
+  }
+
+  @protected
+  LanguageProbability sse_decode_language_probability(
+    SseDeserializer deserializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    var var_language = sse_decode_String(deserializer);
+    var var_probability = sse_decode_f_32(deserializer);
+    return LanguageProbability(
+      language: var_language,
+      probability: var_probability,
+    );
+  }
+
+  @protected
+  int cst_encode_audio_device_direction(AudioDeviceDirection raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    return cst_encode_i_32(raw.index);
+  }
+
   @protected
   bool cst_encode_bool(bool raw) {
     // Codec=Cst (C-struct based), see doc to use other codecs
     return raw;
   }
 
+  @protected
+  int cst_encode_caption_error_kind(CaptionErrorKind raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    return cst_encode_i_32(raw.index);
+  }
+
+  @protected
+  int cst_encode_caption_exit_reason(CaptionExitReason raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    return cst_encode_i_32(raw.index);
+  }
+
   @protected
   double cst_encode_f_32(double raw) {
     // Codec=Cst (C-struct based), see doc to use other codecs
@@ -1035,15 +1527,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
   }
 
   @protected
-  void sse_encode_StreamSink_list_segment_Dco(
-    RustStreamSink<List<Segment>> self,
+  void sse_encode_StreamSink_caption_event_Dco(
+    RustStreamSink<CaptionEvent> self,
     SseSerializer serializer,
   ) {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_String(
       self.setupAndSerialize(
         codec: DcoCodec(
-          decodeSuccessData: dco_decode_list_segment,
+          decodeSuccessData: dco_decode_caption_event,
           decodeErrorData: dco_decode_AnyhowException,
         ),
       ),
@@ -1063,6 +1555,29 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_String(self.toString(), serializer);
   }
 
+  @protected
+  void sse_encode_audio_device_direction(
+    AudioDeviceDirection self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
+  @protected
+  void sse_encode_audio_device_info(
+    AudioDeviceInfo self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_String(self.name, serializer);
+    sse_encode_String(self.id, serializer);
+    sse_encode_audio_device_direction(self.direction, serializer);
+    sse_encode_bool(self.isDefault, serializer);
+    sse_encode_u_32(self.sampleRate, serializer);
+    sse_encode_u_32(self.channels, serializer);
+  }
+
   @protected
   void sse_encode_bool(bool self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -1075,6 +1590,15 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_bool(self, serializer);
   }
 
+  @protected
+  void sse_encode_box_autoadd_caption_config(
+    CaptionConfig self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_caption_config(self, serializer);
+  }
+
   @protected
   void sse_encode_box_autoadd_f_32(double self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -1108,6 +1632,102 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_whisper_client(self, serializer);
   }
 
+  @protected
+  void sse_encode_caption_config(CaptionConfig self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_opt_String(self.audioDevice, serializer);
+    sse_encode_bool(self.audioDeviceIsInput, serializer);
+    sse_encode_opt_String(self.language, serializer);
+    sse_encode_bool(self.withTimestamps, serializer);
+    sse_encode_bool(self.wordTimestamps, serializer);
+    sse_encode_bool(self.verbose, serializer);
+    sse_encode_bool(self.tryWithCuda, serializer);
+    sse_encode_u_32(self.maxAudioDurationSecs, serializer);
+    sse_encode_u_64(self.inferenceIntervalMs, serializer);
+    sse_encode_opt_box_autoadd_u_64(self.inferenceTimeoutMs, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.maxTokensPerSegment, serializer);
+    sse_encode_opt_box_autoadd_f_32(self.temperature, serializer);
+    sse_encode_opt_String(self.vadModelPath, serializer);
+    sse_encode_opt_box_autoadd_f_32(self.vadThreshold, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.localAgreement, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.endpointSilenceMs, serializer);
+    sse_encode_opt_String(self.subtitlePath, serializer);
+    sse_encode_opt_String(self.recordPath, serializer);
+    sse_encode_opt_String(self.broadcastAddr, serializer);
+    sse_encode_opt_String(self.obsTextPath, serializer);
+    sse_encode_u_32(self.obsTextLines, serializer);
+    sse_encode_opt_String(self.obsWebsocketUrl, serializer);
+    sse_encode_opt_String(self.obsWebsocketPassword, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.beamSize, serializer);
+    sse_encode_opt_box_autoadd_f_32(self.beamPatience, serializer);
+    sse_encode_opt_box_autoadd_f_32(self.lengthPenalty, serializer);
+    sse_encode_opt_String(self.initialPrompt, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.conditionOnPreviousText, serializer);
+    sse_encode_bool(self.translate, serializer);
+    sse_encode_bool(self.redetectLanguage, serializer);
+    sse_encode_opt_box_autoadd_f_32(self.languageSwitchThreshold, serializer);
+    sse_encode_opt_box_autoadd_u_32(self.languageSwitchWindows, serializer);
+    sse_encode_bool(self.inlineEmoji, serializer);
+    sse_encode_bool(self.withItn, serializer);
+  }
+
+  @protected
+  void sse_encode_caption_error_kind(
+    CaptionErrorKind self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
+  @protected
+  void sse_encode_caption_event(CaptionEvent self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    switch (self) {
+      case CaptionEvent_Loading(progress: final progress):
+        sse_encode_i_32(0, serializer);
+        sse_encode_opt_box_autoadd_f_32(progress, serializer);
+      case CaptionEvent_Ready(
+        backend: final backend,
+        device: final device,
+        providers: final providers,
+      ):
+        sse_encode_i_32(1, serializer);
+        sse_encode_String(backend, serializer);
+        sse_encode_String(device, serializer);
+        sse_encode_list_String(providers, serializer);
+      case CaptionEvent_Segments(segments: final segments):
+        sse_encode_i_32(2, serializer);
+        sse_encode_list_segment(segments, serializer);
+      case CaptionEvent_VadActivity(
+        speaking: final speaking,
+        probability: final probability,
+      ):
+        sse_encode_i_32(3, serializer);
+        sse_encode_bool(speaking, serializer);
+        sse_encode_f_32(probability, serializer);
+      case CaptionEvent_Warning(msg: final msg):
+        sse_encode_i_32(4, serializer);
+        sse_encode_String(msg, serializer);
+      case CaptionEvent_Error(kind: final kind, msg: final msg):
+        sse_encode_i_32(5, serializer);
+        sse_encode_caption_error_kind(kind, serializer);
+        sse_encode_String(msg, serializer);
+      case CaptionEvent_Exit(reason: final reason):
+        sse_encode_i_32(6, serializer);
+        sse_encode_caption_exit_reason(reason, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_caption_exit_reason(
+    CaptionExitReason self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.index, serializer);
+  }
+
   @protected
   void sse_encode_decoding_result(
     DecodingResult self,
@@ -1140,6 +1760,27 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     serializer.buffer.putInt32(self);
   }
 
+  @protected
+  void sse_encode_list_String(List<String> self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_String(item, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_list_audio_device_info(
+    List<AudioDeviceInfo> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_audio_device_info(item, serializer);
+    }
+  }
+
   @protected
   void sse_encode_list_prim_u_32_strict(
     Uint32List self,
@@ -1193,6 +1834,42 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     }
   }
 
+  @protected
+  void sse_encode_list_sub_segment(
+    List<SubSegment> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_sub_segment(item, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_list_word_timing(
+    List<WordTiming> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_word_timing(item, serializer);
+    }
+  }
+
+  @protected
+  void sse_encode_list_language_probability(
+    List<LanguageProbability> self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_i_32(self.length, serializer);
+    for (final item in self) {
+      sse_encode_language_probability(item, serializer);
+    }
+  }
+
   @protected
   void sse_encode_opt_String(String? self, SseSerializer serializer) {
     // Codec=Sse (Serialization based), see doc to use other codecs
@@ -1286,6 +1963,21 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     sse_encode_opt_String(self.reasoningLang, serializer);
     sse_encode_opt_U128(self.audioDuration, serializer);
     sse_encode_whisper_status(self.status, serializer);
+    sse_encode_list_sub_segment(self.subSegments, serializer);
+    sse_encode_u_64(self.id, serializer);
+    sse_encode_bool(self.committed, serializer);
+    sse_encode_list_language_probability(self.languageProbs, serializer);
+    sse_encode_opt_String(self.emotion, serializer);
+    sse_encode_opt_String(self.audioEvent, serializer);
+  }
+
+  @protected
+  void sse_encode_sub_segment(SubSegment self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_f_64(self.start, serializer);
+    sse_encode_f_64(self.end, serializer);
+    sse_encode_String(self.text, serializer);
+    sse_encode_list_word_timing(self.words, serializer);
   }
 
   @protected
@@ -1333,4 +2025,22 @@ class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
     // Codec=Sse (Serialization based), see doc to use other codecs
     sse_encode_i_32(self.index, serializer);
   }
+
+  @protected
+  void sse_encode_word_timing(WordTiming self, SseSerializer serializer) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_f_64(self.start, serializer);
+    sse_encode_f_64(self.end, serializer);
+    sse_encode_String(self.word, serializer);
+  }
+
+  @protected
+  void sse_encode_language_probability(
+    LanguageProbability self,
+    SseSerializer serializer,
+  ) {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    sse_encode_String(self.language, serializer);
+    sse_encode_f_32(self.probability, serializer);
+  }
 }
@@ -3,8 +3,12 @@
 
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
+import 'api/audio.dart';
 import 'api/text_util.dart';
 import 'api/whisper.dart';
+import 'audio_capture/traits.dart';
+import 'caption/config.dart';
+import 'caption/event.dart';
 import 'candle_models/whisper/model.dart';
 import 'dart:async';
 import 'dart:convert';
@@ -27,7 +31,7 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   Map<String, String> dco_decode_Map_String_String_None(dynamic raw);
 
   @protected
-  RustStreamSink<List<Segment>> dco_decode_StreamSink_list_segment_Dco(
+  RustStreamSink<CaptionEvent> dco_decode_StreamSink_caption_event_Dco(
     dynamic raw,
   );
 
@@ -37,12 +41,21 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   BigInt dco_decode_U128(dynamic raw);
 
+  @protected
+  AudioDeviceDirection dco_decode_audio_device_direction(dynamic raw);
+
+  @protected
+  AudioDeviceInfo dco_decode_audio_device_info(dynamic raw);
+
   @protected
   bool dco_decode_bool(dynamic raw);
 
   @protected
   bool dco_decode_box_autoadd_bool(dynamic raw);
 
+  @protected
+  CaptionConfig dco_decode_box_autoadd_caption_config(dynamic raw);
+
   @protected
   double dco_decode_box_autoadd_f_32(dynamic raw);
 
@@ -58,6 +71,18 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   WhisperClient dco_decode_box_autoadd_whisper_client(dynamic raw);
 
+  @protected
+  CaptionConfig dco_decode_caption_config(dynamic raw);
+
+  @protected
+  CaptionErrorKind dco_decode_caption_error_kind(dynamic raw);
+
+  @protected
+  CaptionEvent dco_decode_caption_event(dynamic raw);
+
+  @protected
+  CaptionExitReason dco_decode_caption_exit_reason(dynamic raw);
+
   @protected
   DecodingResult dco_decode_decoding_result(dynamic raw);
 
@@ -70,6 +95,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int dco_decode_i_32(dynamic raw);
 
+  @protected
+  List<String> dco_decode_list_String(dynamic raw);
+
+  @protected
+  List<AudioDeviceInfo> dco_decode_list_audio_device_info(dynamic raw);
+
   @protected
   Uint32List dco_decode_list_prim_u_32_strict(dynamic raw);
 
@@ -85,6 +116,15 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<Segment> dco_decode_list_segment(dynamic raw);
 
+  @protected
+  List<SubSegment> dco_decode_list_sub_segment(dynamic raw);
+
+  @protected
+  List<WordTiming> dco_decode_list_word_timing(dynamic raw);
+
+  @protected
+  List<LanguageProbability> dco_decode_list_language_probability(dynamic raw);
+
   @protected
   String? dco_decode_opt_String(dynamic raw);
 
@@ -112,6 +152,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   Segment dco_decode_segment(dynamic raw);
 
+  @protected
+  SubSegment dco_decode_sub_segment(dynamic raw);
+
   @protected
   int dco_decode_u_32(dynamic raw);
 
@@ -133,6 +176,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   WhisperStatus dco_decode_whisper_status(dynamic raw);
 
+  @protected
+  WordTiming dco_decode_word_timing(dynamic raw);
+
+  @protected
+  LanguageProbability dco_decode_language_probability(dynamic raw);
+
   @protected
   AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer);
 
@@ -142,7 +191,7 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  RustStreamSink<List<Segment>> sse_decode_StreamSink_list_segment_Dco(
+  RustStreamSink<CaptionEvent> sse_decode_StreamSink_caption_event_Dco(
     SseDeserializer deserializer,
   );
 
@@ -152,12 +201,25 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   BigInt sse_decode_U128(SseDeserializer deserializer);
 
+  @protected
+  AudioDeviceDirection sse_decode_audio_device_direction(
+    SseDeserializer deserializer,
+  );
+
+  @protected
+  AudioDeviceInfo sse_decode_audio_device_info(SseDeserializer deserializer);
+
   @protected
   bool sse_decode_bool(SseDeserializer deserializer);
 
   @protected
   bool sse_decode_box_autoadd_bool(SseDeserializer deserializer);
 
+  @protected
+  CaptionConfig sse_decode_box_autoadd_caption_config(
+    SseDeserializer deserializer,
+  );
+
   @protected
   double sse_decode_box_autoadd_f_32(SseDeserializer deserializer);
 
@@ -175,6 +237,20 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseDeserializer deserializer,
   );
 
+  @protected
+  CaptionConfig sse_decode_caption_config(SseDeserializer deserializer);
+
+  @protected
+  CaptionErrorKind sse_decode_caption_error_kind(SseDeserializer deserializer);
+
+  @protected
+  CaptionEvent sse_decode_caption_event(SseDeserializer deserializer);
+
+  @protected
+  CaptionExitReason sse_decode_caption_exit_reason(
+    SseDeserializer deserializer,
+  );
+
   @protected
   DecodingResult sse_decode_decoding_result(SseDeserializer deserializer);
 
@@ -187,6 +263,14 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   int sse_decode_i_32(SseDeserializer deserializer);
 
+  @protected
+  List<String> sse_decode_list_String(SseDeserializer deserializer);
+
+  @protected
+  List<AudioDeviceInfo> sse_decode_list_audio_device_info(
+    SseDeserializer deserializer,
+  );
+
   @protected
   Uint32List sse_decode_list_prim_u_32_strict(SseDeserializer deserializer);
 
@@ -204,6 +288,17 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   List<Segment> sse_decode_list_segment(SseDeserializer deserializer);
 
+  @protected
+  List<SubSegment> sse_decode_list_sub_segment(SseDeserializer deserializer);
+
+  @protected
+  List<WordTiming> sse_decode_list_word_timing(SseDeserializer deserializer);
+
+  @protected
+  List<LanguageProbability> sse_decode_list_language_probability(
+    SseDeserializer deserializer,
+  );
+
   @protected
   String? sse_decode_opt_String(SseDeserializer deserializer);
 
@@ -233,6 +328,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   Segment sse_decode_segment(SseDeserializer deserializer);
 
+  @protected
+  SubSegment sse_decode_sub_segment(SseDeserializer deserializer);
+
   @protected
   int sse_decode_u_32(SseDeserializer deserializer);
 
@@ -254,6 +352,14 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   WhisperStatus sse_decode_whisper_status(SseDeserializer deserializer);
 
+  @protected
+  WordTiming sse_decode_word_timing(SseDeserializer deserializer);
+
+  @protected
+  LanguageProbability sse_decode_language_probability(
+    SseDeserializer deserializer,
+  );
+
   @protected
   ffi.Pointer<wire_cst_list_prim_u_8_strict> cst_encode_AnyhowException(
     AnyhowException raw,
@@ -273,12 +379,12 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
 
   @protected
   ffi.Pointer<wire_cst_list_prim_u_8_strict>
-  cst_encode_StreamSink_list_segment_Dco(RustStreamSink<List<Segment>> raw) {
+  cst_encode_StreamSink_caption_event_Dco(RustStreamSink<CaptionEvent> raw) {
     // Codec=Cst (C-struct based), see doc to use other codecs
     return cst_encode_String(
       raw.setupAndSerialize(
         codec: DcoCodec(
-          decodeSuccessData: dco_decode_list_segment,
+          decodeSuccessData: dco_decode_caption_event,
           decodeErrorData: dco_decode_AnyhowException,
         ),
       ),
@@ -303,6 +409,16 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     return wire.cst_new_box_autoadd_bool(cst_encode_bool(raw));
   }
 
+  @protected
+  ffi.Pointer<wire_cst_caption_config> cst_encode_box_autoadd_caption_config(
+    CaptionConfig raw,
+  ) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ptr = wire.cst_new_box_autoadd_caption_config();
+    cst_api_fill_to_wire_caption_config(raw, ptr.ref);
+    return ptr;
+  }
+
   @protected
   ffi.Pointer<ffi.Float> cst_encode_box_autoadd_f_32(double raw) {
     // Codec=Cst (C-struct based), see doc to use other codecs
@@ -337,6 +453,27 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     return ptr;
   }
 
+  @protected
+  ffi.Pointer<wire_cst_list_String> cst_encode_list_String(List<String> raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ans = wire.cst_new_list_String(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      ans.ref.ptr[i] = cst_encode_String(raw[i]);
+    }
+    return ans;
+  }
+
+  @protected
+  ffi.Pointer<wire_cst_list_audio_device_info>
+  cst_encode_list_audio_device_info(List<AudioDeviceInfo> raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ans = wire.cst_new_list_audio_device_info(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      cst_api_fill_to_wire_audio_device_info(raw[i], ans.ref.ptr[i]);
+    }
+    return ans;
+  }
+
   @protected
   ffi.Pointer<wire_cst_list_prim_u_32_strict> cst_encode_list_prim_u_32_strict(
     Uint32List raw,
@@ -390,6 +527,41 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     return ans;
   }
 
+  @protected
+  ffi.Pointer<wire_cst_list_sub_segment> cst_encode_list_sub_segment(
+    List<SubSegment> raw,
+  ) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ans = wire.cst_new_list_sub_segment(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      cst_api_fill_to_wire_sub_segment(raw[i], ans.ref.ptr[i]);
+    }
+    return ans;
+  }
+
+  @protected
+  ffi.Pointer<wire_cst_list_word_timing> cst_encode_list_word_timing(
+    List<WordTiming> raw,
+  ) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ans = wire.cst_new_list_word_timing(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      cst_api_fill_to_wire_word_timing(raw[i], ans.ref.ptr[i]);
+    }
+    return ans;
+  }
+
+  @protected
+  ffi.Pointer<wire_cst_list_language_probability>
+  cst_encode_list_language_probability(List<LanguageProbability> raw) {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    final ans = wire.cst_new_list_language_probability(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      cst_api_fill_to_wire_language_probability(raw[i], ans.ref.ptr[i]);
+    }
+    return ans;
+  }
+
   @protected
   ffi.Pointer<wire_cst_list_prim_u_8_strict> cst_encode_opt_String(
     String? raw,
@@ -446,6 +618,27 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     return raw.toSigned(64).toInt();
   }
 
+  @protected
+  void cst_api_fill_to_wire_audio_device_info(
+    AudioDeviceInfo apiObj,
+    wire_cst_audio_device_info wireObj,
+  ) {
+    wireObj.name = cst_encode_String(apiObj.name);
+    wireObj.id = cst_encode_String(apiObj.id);
+    wireObj.direction = cst_encode_audio_device_direction(apiObj.direction);
+    wireObj.is_default = cst_encode_bool(apiObj.isDefault);
+    wireObj.sample_rate = cst_encode_u_32(apiObj.sampleRate);
+    wireObj.channels = cst_encode_u_32(apiObj.channels);
+  }
+
+  @protected
+  void cst_api_fill_to_wire_box_autoadd_caption_config(
+    CaptionConfig apiObj,
+    ffi.Pointer<wire_cst_caption_config> wireObj,
+  ) {
+    cst_api_fill_to_wire_caption_config(apiObj, wireObj.ref);
+  }
+
   @protected
   void cst_api_fill_to_wire_box_autoadd_whisper_client(
     WhisperClient apiObj,
@@ -454,6 +647,126 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     cst_api_fill_to_wire_whisper_client(apiObj, wireObj.ref);
   }
 
+  @protected
+  void cst_api_fill_to_wire_caption_config(
+    CaptionConfig apiObj,
+    wire_cst_caption_config wireObj,
+  ) {
+    wireObj.audio_device = cst_encode_opt_String(apiObj.audioDevice);
+    wireObj.audio_device_is_input = cst_encode_bool(apiObj.audioDeviceIsInput);
+    wireObj.language = cst_encode_opt_String(apiObj.language);
+    wireObj.with_timestamps = cst_encode_bool(apiObj.withTimestamps);
+    wireObj.word_timestamps = cst_encode_bool(apiObj.wordTimestamps);
+    wireObj.verbose = cst_encode_bool(apiObj.verbose);
+    wireObj.try_with_cuda = cst_encode_bool(apiObj.tryWithCuda);
+    wireObj.max_audio_duration_secs = cst_encode_u_32(
+      apiObj.maxAudioDurationSecs,
+    );
+    wireObj.inference_interval_ms = cst_encode_u_64(apiObj.inferenceIntervalMs);
+    wireObj.inference_timeout_ms = cst_encode_opt_box_autoadd_u_64(
+      apiObj.inferenceTimeoutMs,
+    );
+    wireObj.max_tokens_per_segment = cst_encode_opt_box_autoadd_u_32(
+      apiObj.maxTokensPerSegment,
+    );
+    wireObj.temperature = cst_encode_opt_box_autoadd_f_32(apiObj.temperature);
+    wireObj.vad_model_path = cst_encode_opt_String(apiObj.vadModelPath);
+    wireObj.vad_threshold = cst_encode_opt_box_autoadd_f_32(
+      apiObj.vadThreshold,
+    );
+    wireObj.local_agreement = cst_encode_opt_box_autoadd_u_32(
+      apiObj.localAgreement,
+    );
+    wireObj.endpoint_silence_ms = cst_encode_opt_box_autoadd_u_32(
+      apiObj.endpointSilenceMs,
+    );
+    wireObj.subtitle_path = cst_encode_opt_String(apiObj.subtitlePath);
+    wireObj.record_path = cst_encode_opt_String(apiObj.recordPath);
+    wireObj.broadcast_addr = cst_encode_opt_String(apiObj.broadcastAddr);
+    wireObj.obs_text_path = cst_encode_opt_String(apiObj.obsTextPath);
+    wireObj.obs_text_lines = cst_encode_u_32(apiObj.obsTextLines);
+    wireObj.obs_websocket_url = cst_encode_opt_String(apiObj.obsWebsocketUrl);
+    wireObj.obs_websocket_password = cst_encode_opt_String(apiObj.obsWebsocketPassword);
+    wireObj.beam_size = cst_encode_opt_box_autoadd_u_32(apiObj.beamSize);
+    wireObj.beam_patience = cst_encode_opt_box_autoadd_f_32(
+      apiObj.beamPatience,
+    );
+    wireObj.length_penalty = cst_encode_opt_box_autoadd_f_32(
+      apiObj.lengthPenalty,
+    );
+    wireObj.initial_prompt = cst_encode_opt_String(apiObj.initialPrompt);
+    wireObj.condition_on_previous_text = cst_encode_opt_box_autoadd_u_32(
+      apiObj.conditionOnPreviousText,
+    );
+    wireObj.translate = cst_encode_bool(apiObj.translate);
+    wireObj.redetect_language = cst_encode_bool(apiObj.redetectLanguage);
+    wireObj.language_switch_threshold = cst_encode_opt_box_autoadd_f_32(
+      apiObj.languageSwitchThreshold,
+    );
+    wireObj.language_switch_windows = cst_encode_opt_box_autoadd_u_32(
+      apiObj.languageSwitchWindows,
+    );
+    wireObj.inline_emoji = cst_encode_bool(apiObj.inlineEmoji);
+    wireObj.with_itn = cst_encode_bool(apiObj.withItn);
+  }
+
+  @protected
+  void cst_api_fill_to_wire_caption_event(
+    CaptionEvent apiObj,
+    wire_cst_caption_event wireObj,
+  ) {
+    if (apiObj is CaptionEvent_Loading) {
+      var pre_progress = cst_encode_opt_box_autoadd_f_32(apiObj.progress);
+      wireObj.tag = 0;
+      wireObj.kind.Loading.progress = pre_progress;
+      return;
+    }
+    if (apiObj is CaptionEvent_Ready) {
+      var pre_backend = cst_encode_String(apiObj.backend);
+      var pre_device = cst_encode_String(apiObj.device);
+      var pre_providers = cst_encode_list_String(apiObj.providers);
+      wireObj.tag = 1;
+      wireObj.kind.Ready.backend = pre_backend;
+      wireObj.kind.Ready.device = pre_device;
+      wireObj.kind.Ready.providers = pre_providers;
+      return;
+    }
+    if (apiObj is CaptionEvent_Segments) {
+      var pre_segments = cst_encode_list_segment(apiObj.segments);
+      wireObj.tag = 2;
+      wireObj.kind.Segments.segments = pre_segments;
+      return;
+    }
+    if (apiObj is CaptionEvent_VadActivity) {
+      var pre_speaking = cst_encode_bool(apiObj.speaking);
+      var pre_probability = cst_encode_f_32(apiObj.probability);
+      wireObj.tag = 3;
+      wireObj.kind.VadActivity.speaking = pre_speaking;
+      wireObj.kind.VadActivity.probability = pre_probability;
+      return;
+    }
+    if (apiObj is CaptionEvent_Warning) {
+      var pre_msg = cst_encode_String(apiObj.msg);
+      wireObj.tag = 4;
+      wireObj.kind.Warning.msg = pre_msg;
+      return;
+    }
+    if (apiObj is CaptionEvent_Error) {
+      var pre_kind = cst_encode_caption_error_kind(apiObj.kind);
+      var pre_msg = cst_encode_String(apiObj.msg);
+      wireObj.tag = 5;
+      wireObj.kind.Error.kind = pre_kind;
+      wireObj.kind.Error.msg = pre_msg;
+      return;
+    }
+    if (apiObj is CaptionEvent_Exit) {
+      var pre_reason = cst_encode_caption_exit_reason(apiObj.reason);
+      wireObj.tag = 6;
+      wireObj.kind.Exit.reason = pre_reason;
+      return;
+    }
+  }
+
   @protected
   void cst_api_fill_to_wire_decoding_result(
     DecodingResult apiObj,
@@ -485,6 +798,25 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     wireObj.reasoning_lang = cst_encode_opt_String(apiObj.reasoningLang);
     wireObj.audio_duration = cst_encode_opt_U128(apiObj.audioDuration);
     wireObj.status = cst_encode_whisper_status(apiObj.status);
+    wireObj.sub_segments = cst_encode_list_sub_segment(apiObj.subSegments);
+    wireObj.id = cst_encode_u_64(apiObj.id);
+    wireObj.committed = cst_encode_bool(apiObj.committed);
+    wireObj.language_probs = cst_encode_list_language_probability(
+      apiObj.languageProbs,
+    );
+    wireObj.emotion = cst_encode_opt_String(apiObj.emotion);
+    wireObj.audio_event = cst_encode_opt_String(apiObj.audioEvent);
+  }
+
+  @protected
+  void cst_api_fill_to_wire_sub_segment(
+    SubSegment apiObj,
+    wire_cst_sub_segment wireObj,
+  ) {
+    wireObj.start = cst_encode_f_64(apiObj.start);
+    wireObj.end = cst_encode_f_64(apiObj.end);
+    wireObj.text = cst_encode_String(apiObj.text);
+    wireObj.words = cst_encode_list_word_timing(apiObj.words);
   }
 
   @protected
@@ -500,9 +832,37 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     wireObj.model_type = cst_encode_String(apiObj.modelType);
   }
 
+  @protected
+  void cst_api_fill_to_wire_word_timing(
+    WordTiming apiObj,
+    wire_cst_word_timing wireObj,
+  ) {
+    wireObj.start = cst_encode_f_64(apiObj.start);
+    wireObj.end = cst_encode_f_64(apiObj.end);
+    wireObj.word = cst_encode_String(apiObj.word);
+  }
+
+  @protected
+  void cst_api_fill_to_wire_language_probability(
+    LanguageProbability apiObj,
+    wire_cst_language_probability wireObj,
+  ) {
+    wireObj.language = cst_encode_String(apiObj.language);
+    wireObj.probability = cst_encode_f_32(apiObj.probability);
+  }
+
+  @protected
+  int cst_encode_audio_device_direction(AudioDeviceDirection raw);
+
   @protected
   bool cst_encode_bool(bool raw);
 
+  @protected
+  int cst_encode_caption_error_kind(CaptionErrorKind raw);
+
+  @protected
+  int cst_encode_caption_exit_reason(CaptionExitReason raw);
+
   @protected
   double cst_encode_f_32(double raw);
 
@@ -537,8 +897,8 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   );
 
   @protected
-  void sse_encode_StreamSink_list_segment_Dco(
-    RustStreamSink<List<Segment>> self,
+  void sse_encode_StreamSink_caption_event_Dco(
+    RustStreamSink<CaptionEvent> self,
     SseSerializer serializer,
   );
 
@@ -548,12 +908,30 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_U128(BigInt self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_audio_device_direction(
+    AudioDeviceDirection self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_audio_device_info(
+    AudioDeviceInfo self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_bool(bool self, SseSerializer serializer);
 
   @protected
   void sse_encode_box_autoadd_bool(bool self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_box_autoadd_caption_config(
+    CaptionConfig self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_box_autoadd_f_32(double self, SseSerializer serializer);
 
@@ -572,6 +950,24 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
     SseSerializer serializer,
   );
 
+  @protected
+  void sse_encode_caption_config(CaptionConfig self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_caption_error_kind(
+    CaptionErrorKind self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_caption_event(CaptionEvent self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_caption_exit_reason(
+    CaptionExitReason self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_decoding_result(
     DecodingResult self,
@@ -587,6 +983,15 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_i_32(int self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_list_String(List<String> self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_list_audio_device_info(
+    List<AudioDeviceInfo> self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_list_prim_u_32_strict(
     Uint32List self,
@@ -611,6 +1016,24 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_list_segment(List<Segment> self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_list_sub_segment(
+    List<SubSegment> self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_list_word_timing(
+    List<WordTiming> self,
+    SseSerializer serializer,
+  );
+
+  @protected
+  void sse_encode_list_language_probability(
+    List<LanguageProbability> self,
+    SseSerializer serializer,
+  );
+
   @protected
   void sse_encode_opt_String(String? self, SseSerializer serializer);
 
@@ -641,6 +1064,9 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
   @protected
   void sse_encode_segment(Segment self, SseSerializer serializer);
 
+  @protected
+  void sse_encode_sub_segment(SubSegment self, SseSerializer serializer);
+
   @protected
   void sse_encode_u_32(int self, SseSerializer serializer);
 
@@ -661,6 +1087,15 @@ abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
 
   @protected
   void sse_encode_whisper_status(WhisperStatus self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_word_timing(WordTiming self, SseSerializer serializer);
+
+  @protected
+  void sse_encode_language_probability(
+    LanguageProbability self,
+    SseSerializer serializer,
+  );
 }
 
 // Section: wire_class
@@ -760,37 +1195,15 @@ class RustLibWire implements BaseWire {
     int port_,
     ffi.Pointer<wire_cst_whisper_client> whisper_client,
     ffi.Pointer<wire_cst_list_prim_u_8_strict> stream_sink,
-    ffi.Pointer<wire_cst_list_prim_u_8_strict> audio_device,
-    ffi.Pointer<ffi.Bool> audio_device_is_input,
-    ffi.Pointer<wire_cst_list_prim_u_8_strict> audio_language,
     ffi.Pointer<wire_cst_list_prim_u_8_strict> cancel_token_id,
-    ffi.Pointer<ffi.Bool> with_timestamps,
-    ffi.Pointer<ffi.Bool> verbose,
-    ffi.Pointer<ffi.Bool> try_with_cuda,
-    ffi.Pointer<ffi.Uint32> whisper_max_audio_duration,
-    ffi.Pointer<ffi.Uint64> inference_interval,
-    ffi.Pointer<ffi.UintPtr> whisper_default_max_decode_tokens,
-    ffi.Pointer<ffi.Float> whisper_temperature,
-    ffi.Pointer<wire_cst_list_prim_u_8_strict> vad_model_path,
-    ffi.Pointer<ffi.Float> vad_filters_value,
+    ffi.Pointer<wire_cst_caption_config> config,
   ) {
     return _wire__crate__api__whisper__launch_caption(
       port_,
       whisper_client,
       stream_sink,
-      audio_device,
-      audio_device_is_input,
-      audio_language,
       cancel_token_id,
-      with_timestamps,
-      verbose,
-      try_with_cuda,
-      whisper_max_audio_duration,
-      inference_interval,
-      whisper_default_max_decode_tokens,
-      whisper_temperature,
-      vad_model_path,
-      vad_filters_value,
+      config,
     );
   }
 
@@ -801,18 +1214,7 @@ class RustLibWire implements BaseWire {
         ffi.Pointer<wire_cst_whisper_client>,
         ffi.Pointer<wire_cst_list_prim_u_8_strict>,
         ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-        ffi.Pointer<ffi.Bool>,
-        ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-        ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-        ffi.Pointer<ffi.Bool>,
-        ffi.Pointer<ffi.Bool>,
-        ffi.Pointer<ffi.Bool>,
-        ffi.Pointer<ffi.Uint32>,
-        ffi.Pointer<ffi.Uint64>,
-        ffi.Pointer<ffi.UintPtr>,
-        ffi.Pointer<ffi.Float>,
-        ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-        ffi.Pointer<ffi.Float>,
+        ffi.Pointer<wire_cst_caption_config>,
       )
     >
   >('frbgen_fl_caption_wire__crate__api__whisper__launch_caption');
@@ -824,18 +1226,54 @@ class RustLibWire implements BaseWire {
               ffi.Pointer<wire_cst_whisper_client>,
               ffi.Pointer<wire_cst_list_prim_u_8_strict>,
               ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-              ffi.Pointer<ffi.Bool>,
-              ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-              ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-              ffi.Pointer<ffi.Bool>,
-              ffi.Pointer<ffi.Bool>,
-              ffi.Pointer<ffi.Bool>,
-              ffi.Pointer<ffi.Uint32>,
-              ffi.Pointer<ffi.Uint64>,
-              ffi.Pointer<ffi.UintPtr>,
-              ffi.Pointer<ffi.Float>,
+              ffi.Pointer<wire_cst_caption_config>,
+            )
+          >();
+
+  void wire__crate__api__audio__list_audio_devices(int port_) {
+    return _wire__crate__api__audio__list_audio_devices(port_);
+  }
+
+  late final _wire__crate__api__audio__list_audio_devicesPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>(
+        'frbgen_fl_caption_wire__crate__api__audio__list_audio_devices',
+      );
+  late final _wire__crate__api__audio__list_audio_devices =
+      _wire__crate__api__audio__list_audio_devicesPtr
+          .asFunction<void Function(int)>();
+
+  void wire__crate__api__whisper__transcribe_file(
+    int port_,
+    ffi.Pointer<wire_cst_whisper_client> whisper_client,
+    ffi.Pointer<wire_cst_list_prim_u_8_strict> file_path,
+    ffi.Pointer<wire_cst_caption_config> config,
+  ) {
+    return _wire__crate__api__whisper__transcribe_file(
+      port_,
+      whisper_client,
+      file_path,
+      config,
+    );
+  }
+
+  late final _wire__crate__api__whisper__transcribe_filePtr = _lookup<
+    ffi.NativeFunction<
+      ffi.Void Function(
+        ffi.Int64,
+        ffi.Pointer<wire_cst_whisper_client>,
+        ffi.Pointer<wire_cst_list_prim_u_8_strict>,
+        ffi.Pointer<wire_cst_caption_config>,
+      )
+    >
+  >('frbgen_fl_caption_wire__crate__api__whisper__transcribe_file');
+  late final _wire__crate__api__whisper__transcribe_file =
+      _wire__crate__api__whisper__transcribe_filePtr
+          .asFunction<
+            void Function(
+              int,
+              ffi.Pointer<wire_cst_whisper_client>,
               ffi.Pointer<wire_cst_list_prim_u_8_strict>,
-              ffi.Pointer<ffi.Float>,
+              ffi.Pointer<wire_cst_caption_config>,
             )
           >();
 
@@ -898,6 +1336,17 @@ class RustLibWire implements BaseWire {
       _cst_new_box_autoadd_boolPtr
           .asFunction<ffi.Pointer<ffi.Bool> Function(bool)>();
 
+  ffi.Pointer<wire_cst_caption_config> cst_new_box_autoadd_caption_config() {
+    return _cst_new_box_autoadd_caption_config();
+  }
+
+  late final _cst_new_box_autoadd_caption_configPtr = _lookup<
+    ffi.NativeFunction<ffi.Pointer<wire_cst_caption_config> Function()>
+  >('frbgen_fl_caption_cst_new_box_autoadd_caption_config');
+  late final _cst_new_box_autoadd_caption_config =
+      _cst_new_box_autoadd_caption_configPtr
+          .asFunction<ffi.Pointer<wire_cst_caption_config> Function()>();
+
   ffi.Pointer<ffi.Float> cst_new_box_autoadd_f_32(double value) {
     return _cst_new_box_autoadd_f_32(value);
   }
@@ -956,6 +1405,34 @@ class RustLibWire implements BaseWire {
       _cst_new_box_autoadd_whisper_clientPtr
           .asFunction<ffi.Pointer<wire_cst_whisper_client> Function()>();
 
+  ffi.Pointer<wire_cst_list_String> cst_new_list_String(int len) {
+    return _cst_new_list_String(len);
+  }
+
+  late final _cst_new_list_StringPtr = _lookup<
+    ffi.NativeFunction<ffi.Pointer<wire_cst_list_String> Function(ffi.Int32)>
+  >('frbgen_fl_caption_cst_new_list_String');
+  late final _cst_new_list_String =
+      _cst_new_list_StringPtr
+          .asFunction<ffi.Pointer<wire_cst_list_String> Function(int)>();
+
+  ffi.Pointer<wire_cst_list_audio_device_info> cst_new_list_audio_device_info(
+    int len,
+  ) {
+    return _cst_new_list_audio_device_info(len);
+  }
+
+  late final _cst_new_list_audio_device_infoPtr = _lookup<
+    ffi.NativeFunction<
+      ffi.Pointer<wire_cst_list_audio_device_info> Function(ffi.Int32)
+    >
+  >('frbgen_fl_caption_cst_new_list_audio_device_info');
+  late final _cst_new_list_audio_device_info =
+      _cst_new_list_audio_device_infoPtr
+          .asFunction<
+            ffi.Pointer<wire_cst_list_audio_device_info> Function(int)
+          >();
+
   ffi.Pointer<wire_cst_list_prim_u_32_strict> cst_new_list_prim_u_32_strict(
     int len,
   ) {
@@ -1034,6 +1511,48 @@ class RustLibWire implements BaseWire {
       _cst_new_list_segmentPtr
           .asFunction<ffi.Pointer<wire_cst_list_segment> Function(int)>();
 
+  ffi.Pointer<wire_cst_list_sub_segment> cst_new_list_sub_segment(int len) {
+    return _cst_new_list_sub_segment(len);
+  }
+
+  late final _cst_new_list_sub_segmentPtr = _lookup<
+    ffi.NativeFunction<
+      ffi.Pointer<wire_cst_list_sub_segment> Function(ffi.Int32)
+    >
+  >('frbgen_fl_caption_cst_new_list_sub_segment');
+  late final _cst_new_list_sub_segment =
+      _cst_new_list_sub_segmentPtr
+          .asFunction<ffi.Pointer<wire_cst_list_sub_segment> Function(int)>();
+
+  ffi.Pointer<wire_cst_list_word_timing> cst_new_list_word_timing(int len) {
+    return _cst_new_list_word_timing(len);
+  }
+
+  late final _cst_new_list_word_timingPtr = _lookup<
+    ffi.NativeFunction<
+      ffi.Pointer<wire_cst_list_word_timing> Function(ffi.Int32)
+    >
+  >('frbgen_fl_caption_cst_new_list_word_timing');
+  late final _cst_new_list_word_timing =
+      _cst_new_list_word_timingPtr
+          .asFunction<ffi.Pointer<wire_cst_list_word_timing> Function(int)>();
+
+  ffi.Pointer<wire_cst_list_language_probability>
+  cst_new_list_language_probability(int len) {
+    return _cst_new_list_language_probability(len);
+  }
+
+  late final _cst_new_list_language_probabilityPtr = _lookup<
+    ffi.NativeFunction<
+      ffi.Pointer<wire_cst_list_language_probability> Function(ffi.Int32)
+    >
+  >('frbgen_fl_caption_cst_new_list_language_probability');
+  late final _cst_new_list_language_probability =
+      _cst_new_list_language_probabilityPtr
+          .asFunction<
+            ffi.Pointer<wire_cst_list_language_probability> Function(int)
+          >();
+
   int dummy_method_to_enforce_bundling() {
     return _dummy_method_to_enforce_bundling();
   }
@@ -1123,6 +1642,56 @@ final class wire_cst_decoding_result extends ffi.Struct {
   external double compression_ratio;
 }
 
+final class wire_cst_word_timing extends ffi.Struct {
+  @ffi.Double()
+  external double start;
+
+  @ffi.Double()
+  external double end;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> word;
+}
+
+final class wire_cst_list_word_timing extends ffi.Struct {
+  external ffi.Pointer<wire_cst_word_timing> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
+final class wire_cst_sub_segment extends ffi.Struct {
+  @ffi.Double()
+  external double start;
+
+  @ffi.Double()
+  external double end;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> text;
+
+  external ffi.Pointer<wire_cst_list_word_timing> words;
+}
+
+final class wire_cst_list_sub_segment extends ffi.Struct {
+  external ffi.Pointer<wire_cst_sub_segment> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
+final class wire_cst_language_probability extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> language;
+
+  @ffi.Float()
+  external double probability;
+}
+
+final class wire_cst_list_language_probability extends ffi.Struct {
+  external ffi.Pointer<wire_cst_language_probability> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
 final class wire_cst_segment extends ffi.Struct {
   @ffi.Double()
   external double start;
@@ -1140,6 +1709,20 @@ final class wire_cst_segment extends ffi.Struct {
 
   @ffi.Int32()
   external int status;
+
+  external ffi.Pointer<wire_cst_list_sub_segment> sub_segments;
+
+  @ffi.Uint64()
+  external int id;
+
+  @ffi.Bool()
+  external bool committed;
+
+  external ffi.Pointer<wire_cst_list_language_probability> language_probs;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> emotion;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> audio_event;
 }
 
 final class wire_cst_list_segment extends ffi.Struct {
@@ -1148,3 +1731,180 @@ final class wire_cst_list_segment extends ffi.Struct {
   @ffi.Int32()
   external int len;
 }
+
+final class wire_cst_audio_device_info extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> name;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> id;
+
+  @ffi.Int32()
+  external int direction;
+
+  @ffi.Bool()
+  external bool is_default;
+
+  @ffi.Uint32()
+  external int sample_rate;
+
+  @ffi.Uint32()
+  external int channels;
+}
+
+final class wire_cst_list_audio_device_info extends ffi.Struct {
+  external ffi.Pointer<wire_cst_audio_device_info> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
+final class wire_cst_list_String extends ffi.Struct {
+  external ffi.Pointer<ffi.Pointer<wire_cst_list_prim_u_8_strict>> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
+final class wire_cst_CaptionEvent_Loading extends ffi.Struct {
+  external ffi.Pointer<ffi.Float> progress;
+}
+
+final class wire_cst_CaptionEvent_Ready extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> backend;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> device;
+
+  external ffi.Pointer<wire_cst_list_String> providers;
+}
+
+final class wire_cst_CaptionEvent_Segments extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_segment> segments;
+}
+
+final class wire_cst_CaptionEvent_VadActivity extends ffi.Struct {
+  @ffi.Bool()
+  external bool speaking;
+
+  @ffi.Float()
+  external double probability;
+}
+
+final class wire_cst_CaptionEvent_Warning extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> msg;
+}
+
+final class wire_cst_CaptionEvent_Error extends ffi.Struct {
+  @ffi.Int32()
+  external int kind;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> msg;
+}
+
+final class wire_cst_CaptionEvent_Exit extends ffi.Struct {
+  @ffi.Int32()
+  external int reason;
+}
+
+final class CaptionEventKind extends ffi.Union {
+  external wire_cst_CaptionEvent_Loading Loading;
+
+  external wire_cst_CaptionEvent_Ready Ready;
+
+  external wire_cst_CaptionEvent_Segments Segments;
+
+  external wire_cst_CaptionEvent_VadActivity VadActivity;
+
+  external wire_cst_CaptionEvent_Warning Warning;
+
+  external wire_cst_CaptionEvent_Error Error;
+
+  external wire_cst_CaptionEvent_Exit Exit;
+}
+
+final class wire_cst_caption_event extends ffi.Struct {
+  @ffi.Int32()
+  external int tag;
+
+  external CaptionEventKind kind;
+}
+
+final class wire_cst_caption_config extends ffi.Struct {
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> audio_device;
+
+  @ffi.Bool()
+  external bool audio_device_is_input;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> language;
+
+  @ffi.Bool()
+  external bool with_timestamps;
+
+  @ffi.Bool()
+  external bool word_timestamps;
+
+  @ffi.Bool()
+  external bool verbose;
+
+  @ffi.Bool()
+  external bool try_with_cuda;
+
+  @ffi.Uint32()
+  external int max_audio_duration_secs;
+
+  @ffi.Uint64()
+  external int inference_interval_ms;
+
+  external ffi.Pointer<ffi.Uint64> inference_timeout_ms;
+
+  external ffi.Pointer<ffi.Uint32> max_tokens_per_segment;
+
+  external ffi.Pointer<ffi.Float> temperature;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> vad_model_path;
+
+  external ffi.Pointer<ffi.Float> vad_threshold;
+
+  external ffi.Pointer<ffi.Uint32> local_agreement;
+
+  external ffi.Pointer<ffi.Uint32> endpoint_silence_ms;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> subtitle_path;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> record_path;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> broadcast_addr;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> obs_text_path;
+
+  @ffi.Uint32()
+  external int obs_text_lines;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> obs_websocket_url;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> obs_websocket_password;
+
+  external ffi.Pointer<ffi.Uint32> beam_size;
+
+  external ffi.Pointer<ffi.Float> beam_patience;
+
+  external ffi.Pointer<ffi.Float> length_penalty;
+
+  external ffi.Pointer<wire_cst_list_prim_u_8_strict> initial_prompt;
+
+  external ffi.Pointer<ffi.Uint32> condition_on_previous_text;
+
+  @ffi.Bool()
+  external bool translate;
+
+  @ffi.Bool()
+  external bool redetect_language;
+
+  external ffi.Pointer<ffi.Float> language_switch_threshold;
+
+  external ffi.Pointer<ffi.Uint32> language_switch_windows;
+
+  @ffi.Bool()
+  external bool inline_emoji;
+
+  @ffi.Bool()
+  external bool with_itn;
+}
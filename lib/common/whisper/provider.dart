@@ -1,6 +1,8 @@
 import 'dart:io';
 
-import 'package:fl_caption/common/rust/candle_models/whisper/model.dart' show WhisperStatus;
+import 'package:fl_caption/common/rust/candle_models/whisper/model.dart' show Segment, WhisperStatus;
+import 'package:fl_caption/common/rust/caption/config.dart';
+import 'package:fl_caption/common/rust/caption/event.dart';
 import 'package:fl_caption/common/whisper/onnx_models.dart';
 import 'package:fl_caption/pages/settings/settings_provider.dart';
 import 'package:flutter/foundation.dart';
@@ -153,50 +155,70 @@ class DartWhisperCaption extends _$DartWhisperCaption {
           .launchCaption(
             whisperClient: dartWhisper.client,
             cancelTokenId: _cancelToken ?? "",
-            audioDeviceIsInput: false,
-            audioLanguage: dartWhisper.client.isMultilingual ? appSettings.audioLanguage : null,
-            tryWithCuda: appSettings.tryWithCuda,
-            whisperMaxAudioDuration: appSettings.whisperMaxAudioDuration.toInt(),
-            inferenceInterval: BigInt.from(appSettings.inferenceInterval),
-            whisperDefaultMaxDecodeTokens: BigInt.from(appSettings.whisperDefaultMaxDecodeTokens),
-            whisperTemperature: appSettings.whisperTemperature,
-            vadModelPath: vadModelPath,
-            vadFiltersValue: appSettings.vadThreshold,
+            config: CaptionConfig(
+              audioDeviceIsInput: false,
+              language: dartWhisper.client.isMultilingual ? appSettings.audioLanguage : null,
+              withTimestamps: false,
+              wordTimestamps: false,
+              verbose: false,
+              tryWithCuda: appSettings.tryWithCuda,
+              maxAudioDurationSecs: appSettings.whisperMaxAudioDuration.toInt(),
+              inferenceIntervalMs: BigInt.from(appSettings.inferenceInterval),
+              // 推理耗时不能超过推理间隔，否则识别会越来越落后于音频
+              inferenceTimeoutMs: BigInt.from(appSettings.inferenceInterval),
+              maxTokensPerSegment: appSettings.whisperDefaultMaxDecodeTokens,
+              temperature: appSettings.whisperTemperature,
+              vadModelPath: vadModelPath,
+              vadThreshold: appSettings.vadThreshold,
+              obsTextLines: 3,
+              translate: false,
+              redetectLanguage: false,
+              inlineEmoji: false,
+              withItn: false,
+            ),
           )
           .listen(
-            (data) async {
-              if (data.isNotEmpty) {
-                final resultList = data.map((e) => e.dr).toList();
-                final newResultList = List.from(resultList);
-                for (final r in resultList) {
-                  debugPrint(
-                    "[DartWhisperCaption] Result:  ${r.text} avgLogprob =${r.avgLogprob.toStringAsFixed(6)} noSpeechProb= ${r.noSpeechProb.toStringAsFixed(6)}",
+            (event) {
+              switch (event) {
+                case CaptionEvent_Loading():
+                  state = AsyncData(DartWhisperCaptionResult(text: "", errorType: dartWhisper.errorType));
+                case CaptionEvent_Ready(:final backend, :final device, :final providers):
+                  debugPrint("[DartWhisperCaption] Ready: $backend on $device, providers: $providers");
+                  state = AsyncData(
+                    DartWhisperCaptionResult(
+                      text: "",
+                      errorType: dartWhisper.errorType,
+                      whisperStatus: WhisperStatus.ready,
+                    ),
                   );
-                }
-                newResultList.removeWhere((e) {
-                  // 移除低置信度的结果
-                  if (e.avgLogprob < -1.5) {
-                    debugPrint("[DartWhisperCaption] Remove low logprob: ${e.avgLogprob}");
-                    return true;
-                  }
-                  if (e.noSpeechProb > 0.8) {
-                    debugPrint("[DartWhisperCaption] Remove high no speech prob: ${e.noSpeechProb}");
-                    return true;
+                case CaptionEvent_Segments(:final segments):
+                  _onSegments(segments, dartWhisper);
+                case CaptionEvent_VadActivity():
+                  break;
+                case CaptionEvent_Warning(:final msg):
+                  debugPrint("[DartWhisperCaption] Warning: $msg");
+                case CaptionEvent_Error(:final kind, :final msg):
+                  debugPrint("[DartWhisperCaption] Error (${kind.name}): $msg");
+                  state = AsyncData(
+                    DartWhisperCaptionResult(
+                      text: state.value?.text ?? "",
+                      errorType: dartWhisper.errorType,
+                      errorMessage: msg,
+                      whisperStatus: WhisperStatus.error,
+                    ),
+                  );
+                case CaptionEvent_Exit(:final reason):
+                  debugPrint("[DartWhisperCaption] Exit: ${reason.name}");
+                  // 出错退出时保留错误信息
+                  if (reason != CaptionExitReason.error) {
+                    state = AsyncData(
+                      DartWhisperCaptionResult(
+                        text: "",
+                        errorType: dartWhisper.errorType,
+                        whisperStatus: WhisperStatus.exit,
+                      ),
+                    );
                   }
-                  return false;
-                });
-                if (newResultList.isEmpty) return;
-                final newData = newResultList.map((e) => e.text).toList().join(" ");
-                state = AsyncData(
-                  DartWhisperCaptionResult(
-                    text: newData,
-                    reasoningDuration: Duration(milliseconds: data.lastOrNull?.reasoningDuration?.toInt() ?? 0),
-                    audioDuration: Duration(milliseconds: data.lastOrNull?.audioDuration?.toInt() ?? 0),
-                    reasoningLang: data.lastOrNull?.reasoningLang,
-                    errorType: dartWhisper.errorType,
-                    whisperStatus: data.lastOrNull?.status ?? WhisperStatus.loading,
-                  ),
-                );
               }
             },
             onDone: () {
@@ -227,6 +249,41 @@ class DartWhisperCaption extends _$DartWhisperCaption {
     return DartWhisperCaptionResult(text: "", errorType: dartWhisper.errorType);
   }
 
+  void _onSegments(List<Segment> data, DartWhisperClient dartWhisper) {
+    if (data.isEmpty) return;
+    final resultList = data.map((e) => e.dr).toList();
+    final newResultList = List.from(resultList);
+    for (final r in resultList) {
+      debugPrint(
+        "[DartWhisperCaption] Result:  ${r.text} avgLogprob =${r.avgLogprob.toStringAsFixed(6)} noSpeechProb= ${r.noSpeechProb.toStringAsFixed(6)}",
+      );
+    }
+    newResultList.removeWhere((e) {
+      // 移除低置信度的结果
+      if (e.avgLogprob < -1.5) {
+        debugPrint("[DartWhisperCaption] Remove low logprob: ${e.avgLogprob}");
+        return true;
+      }
+      if (e.noSpeechProb > 0.8) {
+        debugPrint("[DartWhisperCaption] Remove high no speech prob: ${e.noSpeechProb}");
+        return true;
+      }
+      return false;
+    });
+    if (newResultList.isEmpty) return;
+    final newData = newResultList.map((e) => e.text).toList().join(" ");
+    state = AsyncData(
+      DartWhisperCaptionResult(
+        text: newData,
+        reasoningDuration: Duration(milliseconds: data.lastOrNull?.reasoningDuration?.toInt() ?? 0),
+        audioDuration: Duration(milliseconds: data.lastOrNull?.audioDuration?.toInt() ?? 0),
+        reasoningLang: data.lastOrNull?.reasoningLang,
+        errorType: dartWhisper.errorType,
+        whisperStatus: WhisperStatus.working,
+      ),
+    );
+  }
+
   bool get isPaused => _isPaused;
 
   void pause() {
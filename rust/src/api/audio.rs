@@ -0,0 +1,6 @@
+use crate::audio_capture::{AudioCapture, AudioDeviceInfo, PlatformAudioCapture};
+
+/// 列出当前平台可用的音频设备，`id` 可作为 launch_caption 的 audio_device 传入
+pub fn list_audio_devices() -> anyhow::Result<Vec<AudioDeviceInfo>> {
+    PlatformAudioCapture::list_devices()
+}
@@ -1,2 +1,3 @@
+pub mod audio;
 pub mod whisper;
 pub mod text_util;
@@ -1,9 +1,10 @@
-use crate::onnx_models;
+use crate::caption::{CaptionConfig, CaptionErrorKind, CaptionEvent, CaptionExitReason};
 use crate::{candle_models, frb_generated::StreamSink};
+use crate::{caption, onnx_models};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 pub type CancellationToken = tokio_util::sync::CancellationToken;
@@ -61,20 +62,9 @@ impl WhisperClient {
 
 pub async fn launch_caption(
     whisper_client: WhisperClient,
-    stream_sink: StreamSink<Vec<candle_models::whisper::model::Segment>>,
-    audio_device: Option<String>,
-    audio_device_is_input: Option<bool>,
-    audio_language: Option<String>,
+    stream_sink: StreamSink<CaptionEvent>,
     cancel_token_id: String,
-    with_timestamps: Option<bool>,
-    verbose: Option<bool>,
-    try_with_cuda: Option<bool>,
-    whisper_max_audio_duration: Option<u32>, // 音频上下文长度
-    inference_interval: Option<u64>,         // 推理间隔时间
-    whisper_default_max_decode_tokens: Option<usize>, // 最大推理token长度
-    whisper_temperature: Option<f32>,        // 温度参数
-    vad_model_path: Option<String>,          // VAD模型路径
-    vad_filters_value: Option<f32>,          // VAD过滤值
+    config: CaptionConfig,
 ) -> anyhow::Result<()> {
     let stream_sink_clone = stream_sink.clone();
 
@@ -87,44 +77,73 @@ pub async fn launch_caption(
         }
     };
 
+    if let Err(e) = config.validate(&whisper_client.model_type, whisper_client.is_multilingual) {
+        eprintln!("Invalid caption config: {e}");
+        let _ = stream_sink.add(CaptionEvent::Error {
+            kind: CaptionErrorKind::Config,
+            msg: e.to_string(),
+        });
+        let _ = stream_sink.add(CaptionEvent::Exit {
+            reason: CaptionExitReason::Error,
+        });
+        return Ok(());
+    }
+
     let p = candle_models::whisper::LaunchCaptionParams {
         models: whisper_client.models,
         config_data: whisper_client.config,
         model_type: whisper_client.model_type,
         is_quantized: whisper_client.is_quantized,
         tokenizer_data: whisper_client.tokenizer,
-        audio_device,
-        audio_device_is_input,
-        audio_language,
         is_multilingual: Some(whisper_client.is_multilingual),
-        cancel_token,
-        with_timestamps,
-        verbose,
-        try_with_cuda: try_with_cuda.unwrap_or(false),
-        inference_timeout: inference_interval.map(|ms| Duration::from_millis(ms)),
-        max_tokens_per_segment: whisper_default_max_decode_tokens,
-        whisper_max_audio_duration,
-        inference_interval_ms: inference_interval,
-        whisper_temperature,
-        vad_model_path,
-        vad_filters_value,
+        ..config.into_params(cancel_token)
     };
 
+    // 引擎内部的致命错误已经以 Error + Exit 事件发出，这里只补发引擎之外的错误
+    let exited = Arc::new(AtomicBool::new(false));
+    let exited_clone = exited.clone();
+    let callback = move |event: CaptionEvent| {
+        if matches!(event, CaptionEvent::Exit { .. }) {
+            exited_clone.store(true, Ordering::Relaxed);
+        }
+        let _ = stream_sink.add(event);
+    };
     let r = if p.model_type.ends_with("_onnx") {
-        onnx_models::launch_caption(p, move |segments| {
-            let _ = stream_sink.add(segments);
-        })
-        .await
+        onnx_models::launch_caption(p, callback).await
     } else {
-        candle_models::whisper::launch_caption(p, move |segments| {
-            let _ = stream_sink.add(segments);
-        })
-        .await
+        candle_models::whisper::launch_caption(p, callback).await
     };
     if let Err(e) = r {
-        stream_sink_clone
-            .add_error(format!("Error in whisper captioning: {e}"))
-            .unwrap_or(());
+        if !exited.load(Ordering::Relaxed) {
+            let _ = stream_sink_clone.add(CaptionEvent::error(CaptionErrorKind::Internal, &e));
+            let _ = stream_sink_clone.add(CaptionEvent::Exit {
+                reason: CaptionExitReason::Error,
+            });
+        }
     }
     Ok(())
 }
+
+/// Transcribe an audio file (WAV / FLAC / MP3 / OGG) without live capture.
+/// Returns all segments, timestamps are relative to the start of the file.
+/// Only the model and decoding settings of `config` apply, the capture and output
+/// settings are ignored.
+pub fn transcribe_file(
+    whisper_client: WhisperClient,
+    file_path: String,
+    config: CaptionConfig,
+) -> anyhow::Result<Vec<candle_models::whisper::model::Segment>> {
+    config.validate(&whisper_client.model_type, whisper_client.is_multilingual)?;
+    let p = candle_models::whisper::LaunchCaptionParams {
+        models: whisper_client.models,
+        config_data: whisper_client.config,
+        model_type: whisper_client.model_type,
+        is_quantized: whisper_client.is_quantized,
+        tokenizer_data: whisper_client.tokenizer,
+        is_multilingual: Some(whisper_client.is_multilingual),
+        inference_timeout: None, // 离线转写不限制推理时间
+        ..config.into_params(CancellationToken::new())
+    };
+
+    caption::file::transcribe_file(&p, &file_path)
+}
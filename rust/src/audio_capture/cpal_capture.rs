@@ -1,4 +1,7 @@
-use super::traits::{AudioCapture, AudioCaptureConfig, AudioCaptureInfo};
+use super::traits::{
+    AudioCapture, AudioCaptureConfig, AudioCaptureInfo, AudioDeviceDirection, AudioDeviceInfo,
+};
+use super::utils::merge_channels;
 use anyhow::Result;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::mpsc;
@@ -44,7 +47,7 @@ impl AudioCapture for CpalAudioCapture {
         let sample_rate = device_config.sample_rate().0;
         let channels = device_config.channels() as u32;
 
-        println!("CPAL audio device -> {device_name:?} config -> {device_config:?}");
+        eprintln!("CPAL audio device -> {device_name:?} config -> {device_config:?}");
 
         Ok(Self {
             config,
@@ -54,6 +57,40 @@ impl AudioCapture for CpalAudioCapture {
         })
     }
 
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> {
+        let host = cpal::default_host();
+        let default_input = host.default_input_device().and_then(|d| d.name().ok());
+        let default_output = host.default_output_device().and_then(|d| d.name().ok());
+
+        let mut devices = vec![];
+        for device in host.input_devices()? {
+            let Ok(name) = device.name() else { continue };
+            let config = device.default_input_config().ok();
+            devices.push(AudioDeviceInfo {
+                id: name.clone(),
+                is_default: default_input.as_ref() == Some(&name),
+                name,
+                direction: AudioDeviceDirection::Input,
+                sample_rate: config.as_ref().map_or(0, |c| c.sample_rate().0),
+                channels: config.as_ref().map_or(0, |c| c.channels() as u32),
+            });
+        }
+        // WASAPI 下输出设备以 loopback 方式采集
+        for device in host.output_devices()? {
+            let Ok(name) = device.name() else { continue };
+            let config = device.default_output_config().ok();
+            devices.push(AudioDeviceInfo {
+                id: name.clone(),
+                is_default: default_output.as_ref() == Some(&name),
+                name,
+                direction: AudioDeviceDirection::Monitor,
+                sample_rate: config.as_ref().map_or(0, |c| c.sample_rate().0),
+                channels: config.as_ref().map_or(0, |c| c.channels() as u32),
+            });
+        }
+        Ok(devices)
+    }
+
     fn get_info(&self) -> AudioCaptureInfo {
         AudioCaptureInfo {
             device_name: self.device_name.clone(),
@@ -135,46 +172,13 @@ impl AudioCapture for CpalAudioCapture {
             }
 
             drop(stream);
-            println!("CPAL audio stream stopped");
+            eprintln!("CPAL audio stream stopped");
         });
 
         Ok(rx)
     }
 }
 
-/// Merge multi-channel audio to mono
-fn merge_channels(pcm: &[f32], channel_count: usize) -> Vec<f32> {
-    if channel_count == 1 {
-        return pcm.to_vec();
-    }
-
-    let complete_groups = pcm.len() / channel_count;
-    let remaining_samples = pcm.len() % channel_count;
-    let result_capacity = complete_groups + if remaining_samples > 0 { 1 } else { 0 };
-    let mut mono_pcm = Vec::with_capacity(result_capacity);
-
-    // Process complete sample groups
-    for i in 0..complete_groups {
-        let mut sample_sum = 0.0;
-        for ch in 0..channel_count {
-            sample_sum += pcm[i * channel_count + ch];
-        }
-        mono_pcm.push(sample_sum / (channel_count as f32));
-    }
-
-    // Process incomplete sample group if exists
-    if remaining_samples > 0 {
-        let start_idx = complete_groups * channel_count;
-        let mut sample_sum = 0.0;
-        for ch in 0..remaining_samples {
-            sample_sum += pcm[start_idx + ch];
-        }
-        mono_pcm.push(sample_sum / (remaining_samples as f32));
-    }
-
-    mono_pcm
-}
-
 /// Simple audio resampling using linear interpolation
 fn resample_audio(pcm: &[f32], ratio: f64) -> Vec<f32> {
     if (ratio - 1.0).abs() < f64::EPSILON {
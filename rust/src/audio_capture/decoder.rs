@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::utils::{merge_channels, resample_audio};
+
+/// Decoded audio file (f32, mono, resampled to the requested sample rate)
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    pub pcm: Vec<f32>,
+    pub sample_rate: u32,
+    /// Sample rate of the source file before resampling
+    pub source_sample_rate: u32,
+    /// Channel count of the source file before down mixing
+    pub source_channels: usize,
+}
+
+impl DecodedAudio {
+    pub fn duration_secs(&self) -> f64 {
+        self.pcm.len() as f64 / self.sample_rate as f64
+    }
+}
+
+/// Decode an audio file (WAV / FLAC / MP3 / OGG Vorbis) to mono f32 pcm at `target_sample_rate`
+pub fn decode_audio_file(path: impl AsRef<Path>, target_sample_rate: u32) -> Result<DecodedAudio> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .with_context(|| format!("failed to open audio file: {}", path.display()))?;
+    let extension = path.extension().and_then(|e| e.to_str());
+    decode_media(Box::new(file), extension, path, target_sample_rate)
+}
+
+/// Decode an in-memory audio file such as an upload, `extension` helps guessing the format
+pub fn decode_audio_bytes(
+    data: Vec<u8>,
+    extension: Option<&str>,
+    target_sample_rate: u32,
+) -> Result<DecodedAudio> {
+    let source = Box::new(std::io::Cursor::new(data));
+    decode_media(source, extension, Path::new("<memory>"), target_sample_rate)
+}
+
+fn decode_media(
+    source: Box<dyn MediaSource>,
+    extension: Option<&str>,
+    path: &Path,
+    target_sample_rate: u32,
+) -> Result<DecodedAudio> {
+    let mss = MediaSourceStream::new(source, Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = extension {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .with_context(|| format!("unsupported audio format: {}", path.display()))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("no audio track found in {}", path.display()))?;
+    let track_id = track.id;
+    let source_sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("unknown sample rate in {}", path.display()))?;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut source_channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(1);
+    let mut mono_pcm = Vec::new();
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 跳过损坏的数据包
+            Err(SymphoniaError::DecodeError(e)) => {
+                eprintln!("skip undecodable packet: {e}");
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        source_channels = spec.channels.count();
+        let buf = sample_buf
+            .get_or_insert_with(|| SampleBuffer::<f32>::new(decoded.capacity() as u64, spec));
+        if buf.capacity() < decoded.capacity() * source_channels {
+            *buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        }
+        buf.copy_interleaved_ref(decoded);
+        mono_pcm.extend(merge_channels(buf.samples(), source_channels));
+    }
+
+    let pcm = resample_audio(&mono_pcm, source_sample_rate, target_sample_rate);
+    Ok(DecodedAudio {
+        pcm,
+        sample_rate: target_sample_rate,
+        source_sample_rate,
+        source_channels,
+    })
+}
@@ -0,0 +1,112 @@
+use super::decoder::decode_audio_file;
+use super::traits::{AudioCapture, AudioCaptureConfig, AudioCaptureInfo, AudioDeviceInfo};
+use super::FILE_DEVICE_PREFIX;
+use anyhow::Result;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Replays an audio file (WAV / FLAC / MP3 / OGG) as if it was captured live.
+/// The receiver is closed when the end of the file is reached (unless looping).
+pub struct FileAudioCapture {
+    config: AudioCaptureConfig,
+    path: String,
+    /// Playback speed, 1.0 is real time, 0.0 pushes samples as fast as possible
+    speed: f32,
+    /// Restart from the beginning when the end of the file is reached
+    looped: bool,
+    /// Duration of each pushed chunk
+    chunk_duration: Duration,
+}
+
+impl FileAudioCapture {
+    pub fn with_options(
+        config: AudioCaptureConfig,
+        path: impl Into<String>,
+        speed: f32,
+        looped: bool,
+    ) -> Result<Self> {
+        let path = path.into();
+        if !std::path::Path::new(&path).exists() {
+            anyhow::bail!("audio file does not exist: {path}");
+        }
+        if !speed.is_finite() || speed < 0.0 {
+            anyhow::bail!("invalid playback speed: {speed}");
+        }
+        Ok(Self {
+            config,
+            path,
+            speed,
+            looped,
+            chunk_duration: Duration::from_millis(100),
+        })
+    }
+}
+
+impl AudioCapture for FileAudioCapture {
+    /// Use `config.device` (with or without the `file:` prefix) as the file path, real time speed
+    fn new(config: AudioCaptureConfig) -> Result<Self> {
+        let path = config
+            .device
+            .as_deref()
+            .map(|d| d.strip_prefix(FILE_DEVICE_PREFIX).unwrap_or(d).to_string())
+            .ok_or_else(|| anyhow::anyhow!("no audio file specified"))?;
+        Self::with_options(config, path, 1.0, false)
+    }
+
+    /// Files are not enumerable devices
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> {
+        Ok(vec![])
+    }
+
+    fn get_info(&self) -> AudioCaptureInfo {
+        AudioCaptureInfo {
+            device_name: format!("{FILE_DEVICE_PREFIX}{}", self.path),
+            sample_rate: self.config.target_sample_rate,
+            channels: self.config.target_channels,
+        }
+    }
+
+    fn start_capture(&self, cancel_token: CancellationToken) -> Result<mpsc::Receiver<Vec<f32>>> {
+        // 在调用线程解码，以便文件错误能直接返回给调用方
+        let audio = decode_audio_file(&self.path, self.config.target_sample_rate)?;
+        eprintln!(
+            "File capture: {} ({:.1}s, speed {}x, looped: {})",
+            self.path,
+            audio.duration_secs(),
+            self.speed,
+            self.looped
+        );
+
+        let (tx, rx) = mpsc::channel::<Vec<f32>>();
+        let chunk_samples = ((self.config.target_sample_rate as f64
+            * self.chunk_duration.as_secs_f64()) as usize)
+            .max(1);
+        let interval = if self.speed > 0.0 {
+            Some(self.chunk_duration.div_f32(self.speed))
+        } else {
+            None
+        };
+        let looped = self.looped;
+
+        thread::spawn(move || {
+            'playback: loop {
+                for chunk in audio.pcm.chunks(chunk_samples) {
+                    if cancel_token.is_cancelled() || tx.send(chunk.to_vec()).is_err() {
+                        break 'playback;
+                    }
+                    if let Some(interval) = interval {
+                        thread::sleep(interval);
+                    }
+                }
+                if !looped || audio.pcm.is_empty() {
+                    break;
+                }
+            }
+            eprintln!("File capture stopped");
+        });
+
+        Ok(rx)
+    }
+}
@@ -1,4 +1,7 @@
-use super::traits::{AudioCapture, AudioCaptureConfig, AudioCaptureInfo};
+use super::traits::{
+    AudioCapture, AudioCaptureConfig, AudioCaptureInfo, AudioDeviceDirection, AudioDeviceInfo,
+};
+use super::utils::resample_audio;
 use anyhow::Result;
 use core_media_rs::cm_sample_buffer::CMSampleBuffer;
 use screencapturekit::{
@@ -22,6 +25,8 @@ extern "C" {}
 #[link (name = "ScreencaptureKit", kind = "framework" )]
 extern "C" {}
 
+const DEVICE_NAME: &str = "macOS System Audio (ScreenCaptureKit)";
+
 pub struct MacosAudioCapture {
     config: AudioCaptureConfig,
 }
@@ -52,14 +57,26 @@ impl AudioCapture for MacosAudioCapture {
             anyhow::bail!("Failed to access ScreenCaptureKit - ensure app has screen recording permissions");
         }
         
-        println!("ScreenCaptureKit audio capture initialized");
+        eprintln!("ScreenCaptureKit audio capture initialized");
         
         Ok(Self { config })
     }
 
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> {
+        // ScreenCaptureKit 只能采集整个系统的音频输出
+        Ok(vec![AudioDeviceInfo {
+            name: DEVICE_NAME.to_string(),
+            id: DEVICE_NAME.to_string(),
+            direction: AudioDeviceDirection::Monitor,
+            is_default: true,
+            sample_rate: 48000,
+            channels: 2,
+        }])
+    }
+
     fn get_info(&self) -> AudioCaptureInfo {
         AudioCaptureInfo {
-            device_name: "macOS System Audio (ScreenCaptureKit)".to_string(),
+            device_name: DEVICE_NAME.to_string(),
             sample_rate: self.config.target_sample_rate,
             channels: self.config.target_channels,
         }
@@ -86,7 +103,7 @@ fn run_screencapturekit_capture(
     tx: mpsc::Sender<Vec<f32>>,
     cancel_token: CancellationToken,
 ) -> Result<()> {
-    println!("ScreenCaptureKit: initializing system audio capture with config: {:?}", config);
+    eprintln!("ScreenCaptureKit: initializing system audio capture with config: {:?}", config);
     
     // Create a channel for receiving audio samples from ScreenCaptureKit
     let (sc_tx, sc_rx) = mpsc::channel::<CMSampleBuffer>();
@@ -106,7 +123,7 @@ fn run_screencapturekit_capture(
     }
     
     let display = &displays[0];
-    println!("ScreenCaptureKit: using display for audio capture");
+    eprintln!("ScreenCaptureKit: using display for audio capture");
     
     // Create content filter - we only want audio, so exclude all windows
     let filter = SCContentFilter::new().with_display_excluding_windows(display, &[]);
@@ -124,14 +141,14 @@ fn run_screencapturekit_capture(
     stream.start_capture()
         .map_err(|e| anyhow::anyhow!("Failed to start capture: {:?}", e))?;
     
-    println!("ScreenCaptureKit: audio capture started");
+    eprintln!("ScreenCaptureKit: audio capture started");
     
     // Process audio samples in a loop
     let target_sample_rate = config.target_sample_rate;
     
     loop {
         if cancel_token.is_cancelled() {
-            println!("ScreenCaptureKit: cancellation requested, stopping capture");
+            eprintln!("ScreenCaptureKit: cancellation requested, stopping capture");
             break;
         }
         
@@ -165,7 +182,7 @@ fn run_screencapturekit_capture(
     // Give some time for cleanup
     thread::sleep(Duration::from_millis(100));
     
-    println!("ScreenCaptureKit: audio capture stopped");
+    eprintln!("ScreenCaptureKit: audio capture stopped");
     Ok(())
 }
 
@@ -198,7 +215,7 @@ fn process_sample_buffer(sample_buffer: &CMSampleBuffer, target_sample_rate: u32
     } else if data_size % (num_channels * 2) == 0 {
         (2, false) // 16-bit PCM
     } else {
-        println!("Unable to determine audio format from data size: {} bytes, {} channels", data_size, num_channels);
+        eprintln!("Unable to determine audio format from data size: {} bytes, {} channels", data_size, num_channels);
         return None;
     };
     
@@ -208,7 +225,7 @@ fn process_sample_buffer(sample_buffer: &CMSampleBuffer, target_sample_rate: u32
         return None;
     }
     
-    // println!("Processing audio: {} channels, {} bytes per sample, {} frames, actual rate: {}Hz, target rate: {}Hz", 
+    // eprintln!("Processing audio: {} channels, {} bytes per sample, {} frames, actual rate: {}Hz, target rate: {}Hz", 
     //          num_channels, bytes_per_sample, frames_count, actual_sample_rate, target_sample_rate);
     
     // Convert to mono f32 samples - similar to extracting PCM data from CMBlockBuffer
@@ -274,35 +291,3 @@ fn process_sample_buffer(sample_buffer: &CMSampleBuffer, target_sample_rate: u32
     
     Some(resampled_samples)
 }
-
-/// Simple audio resampling using linear interpolation
-/// Similar to the implementation in cpal_capture.rs and pipewire_capture.rs
-fn resample_audio(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
-    if input_rate == output_rate {
-        return input.to_vec();
-    }
-
-    let ratio = output_rate as f64 / input_rate as f64;
-    let output_len = (input.len() as f64 * ratio).ceil() as usize;
-    let mut output = Vec::with_capacity(output_len);
-
-    for i in 0..output_len {
-        let src_index = i as f64 / ratio;
-        let src_index_floor = src_index.floor() as usize;
-        let src_index_ceil = (src_index_floor + 1).min(input.len() - 1);
-        let fraction = src_index - src_index_floor as f64;
-
-        if src_index_floor < input.len() {
-            let sample = if src_index_ceil == src_index_floor {
-                input[src_index_floor]
-            } else {
-                let sample1 = input[src_index_floor];
-                let sample2 = input[src_index_ceil];
-                sample1 + (sample2 - sample1) * fraction as f32
-            };
-            output.push(sample);
-        }
-    }
-
-    output
-}
\ No newline at end of file
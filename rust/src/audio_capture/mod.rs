@@ -1,4 +1,8 @@
+pub mod decoder;
+pub mod file_capture;
+pub mod synthetic_capture;
 pub mod traits;
+pub mod utils;
 
 #[cfg(target_os = "windows")]
 pub mod cpal_capture;
@@ -9,6 +13,8 @@ pub mod pipewire_capture;
 #[cfg(target_os = "macos")]
 pub mod macos_capture;
 
+pub use file_capture::FileAudioCapture;
+pub use synthetic_capture::{SyntheticAudioCapture, SyntheticSignal};
 pub use traits::*;
 
 #[cfg(target_os = "windows")]
@@ -19,3 +25,21 @@ pub use pipewire_capture::PipewireAudioCapture as PlatformAudioCapture;
 
 #[cfg(target_os = "macos")]
 pub use macos_capture::MacosAudioCapture as PlatformAudioCapture;
+
+/// Device name prefix selecting [`FileAudioCapture`], e.g. `file:/path/to/audio.wav`
+pub const FILE_DEVICE_PREFIX: &str = "file:";
+/// Device name prefix selecting [`SyntheticAudioCapture`], e.g. `synthetic:tone:440`
+pub const SYNTHETIC_DEVICE_PREFIX: &str = "synthetic:";
+
+/// Create the audio capture for `config.device`:
+/// `file:<path>` replays a file, `synthetic:<signal>` generates audio, anything else uses the platform backend
+pub fn new_audio_capture(config: AudioCaptureConfig) -> anyhow::Result<Box<dyn AudioCapture>> {
+    let device = config.device.as_deref().unwrap_or_default();
+    if device.starts_with(FILE_DEVICE_PREFIX) {
+        Ok(Box::new(FileAudioCapture::new(config)?))
+    } else if device.starts_with(SYNTHETIC_DEVICE_PREFIX) {
+        Ok(Box::new(SyntheticAudioCapture::new(config)?))
+    } else {
+        Ok(Box::new(PlatformAudioCapture::new(config)?))
+    }
+}
@@ -1,12 +1,17 @@
-use super::traits::{AudioCapture, AudioCaptureConfig, AudioCaptureInfo};
+use super::traits::{
+    AudioCapture, AudioCaptureConfig, AudioCaptureInfo, AudioDeviceDirection, AudioDeviceInfo,
+};
+use super::utils::resample_audio;
 use anyhow::Result;
 use pipewire as pw;
 use pw::{properties::properties, spa};
 use spa::param::format::{MediaSubtype, MediaType};
 use spa::param::format_utils;
 use spa::pod::Pod;
+use std::cell::{Cell, RefCell};
 use std::convert::TryInto;
 use std::mem;
+use std::rc::Rc;
 use std::sync::mpsc;
 use std::thread;
 use tokio_util::sync::CancellationToken;
@@ -30,6 +35,11 @@ impl AudioCapture for PipewireAudioCapture {
         Ok(Self { config })
     }
 
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> {
+        pw::init();
+        list_pipewire_devices()
+    }
+
     fn get_info(&self) -> AudioCaptureInfo {
         AudioCaptureInfo {
             device_name: self.config.device.clone().unwrap_or_else(|| "Default PipeWire Device".to_string()),
@@ -54,21 +64,146 @@ impl AudioCapture for PipewireAudioCapture {
     }
 }
 
+/// Enumerate audio source / sink nodes from the registry.
+/// Sinks are reported as monitor devices, as the capture stream records their monitor port.
+fn list_pipewire_devices() -> Result<Vec<AudioDeviceInfo>> {
+    let mainloop = pw::main_loop::MainLoopRc::new(None)?;
+    let context = pw::context::ContextRc::new(&mainloop, None)?;
+    let core = context.connect_rc(None)?;
+    let registry = core.get_registry_rc()?;
+
+    let devices = Rc::new(RefCell::new(Vec::<AudioDeviceInfo>::new()));
+    // (default.audio.source, default.audio.sink)
+    let defaults = Rc::new(RefCell::new((None::<String>, None::<String>)));
+    let metadata = Rc::new(RefCell::new(Vec::new()));
+
+    let _registry_listener = registry
+        .add_listener_local()
+        .global({
+            let devices = devices.clone();
+            let defaults = defaults.clone();
+            let metadata = metadata.clone();
+            let registry = registry.downgrade();
+            move |global| {
+                let Some(props) = global.props else {
+                    return;
+                };
+                match global.type_ {
+                    pw::types::ObjectType::Node => {
+                        let direction = match props.get("media.class") {
+                            Some("Audio/Source") => AudioDeviceDirection::Input,
+                            Some("Audio/Sink") => AudioDeviceDirection::Monitor,
+                            _ => return,
+                        };
+                        let Some(id) = props.get(*pw::keys::NODE_NAME) else {
+                            return;
+                        };
+                        let name = props
+                            .get(*pw::keys::NODE_DESCRIPTION)
+                            .or_else(|| props.get(*pw::keys::NODE_NICK))
+                            .unwrap_or(id);
+                        devices.borrow_mut().push(AudioDeviceInfo {
+                            name: name.to_string(),
+                            id: id.to_string(),
+                            direction,
+                            is_default: false,
+                            sample_rate: props
+                                .get("audio.rate")
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(0),
+                            channels: props
+                                .get("audio.channels")
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(0),
+                        });
+                    }
+                    pw::types::ObjectType::Metadata
+                        if props.get("metadata.name") == Some("default") =>
+                    {
+                        let Some(registry) = registry.upgrade() else {
+                            return;
+                        };
+                        let Ok(meta) = registry.bind::<pw::metadata::Metadata, _>(global) else {
+                            return;
+                        };
+                        let defaults = defaults.clone();
+                        let listener = meta
+                            .add_listener_local()
+                            .property(move |_, key, _, value| {
+                                // value 形如 {"name":"alsa_output.pci-0000_00_1f.3.analog-stereo"}
+                                let name = value
+                                    .and_then(|v| serde_json::from_str::<serde_json::Value>(v).ok())
+                                    .and_then(|v| v["name"].as_str().map(|s| s.to_string()));
+                                match key {
+                                    Some("default.audio.source") => defaults.borrow_mut().0 = name,
+                                    Some("default.audio.sink") => defaults.borrow_mut().1 = name,
+                                    _ => {}
+                                }
+                                0
+                            })
+                            .register();
+                        metadata.borrow_mut().push((meta, listener));
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .register();
+
+    // 第一次 roundtrip 收集全局对象，第二次等待 metadata 属性
+    pipewire_roundtrip(&mainloop, &core)?;
+    pipewire_roundtrip(&mainloop, &core)?;
+
+    let (default_source, default_sink) = defaults.borrow().clone();
+    let mut devices = devices.take();
+    for device in devices.iter_mut() {
+        let default = match device.direction {
+            AudioDeviceDirection::Input => &default_source,
+            AudioDeviceDirection::Monitor => &default_sink,
+        };
+        device.is_default = default.as_deref() == Some(device.id.as_str());
+    }
+    Ok(devices)
+}
+
+/// Block until the server has processed all pending requests
+fn pipewire_roundtrip(mainloop: &pw::main_loop::MainLoopRc, core: &pw::core::CoreRc) -> Result<()> {
+    let done = Rc::new(Cell::new(false));
+    let pending = core.sync(0)?;
+    let _listener = core
+        .add_listener_local()
+        .done({
+            let done = done.clone();
+            let mainloop = mainloop.clone();
+            move |id, seq| {
+                if id == pw::core::PW_ID_CORE && seq == pending {
+                    done.set(true);
+                    mainloop.quit();
+                }
+            }
+        })
+        .register();
+    while !done.get() {
+        mainloop.run();
+    }
+    Ok(())
+}
+
 fn run_pipewire_capture(
     config: AudioCaptureConfig,
     tx: mpsc::Sender<Vec<f32>>,
     cancel_token: CancellationToken,
 ) -> Result<()> {
-    println!("PipeWire: initializing capture with config: {:?}", config);
+    eprintln!("PipeWire: initializing capture with config: {:?}", config);
     
     let mainloop = pw::main_loop::MainLoop::new(None)?;
-    println!("PipeWire: mainloop created");
+    eprintln!("PipeWire: mainloop created");
     
     let context = pw::context::Context::new(&mainloop)?;
-    println!("PipeWire: context created");
+    eprintln!("PipeWire: context created");
     
     let core = context.connect(None)?;
-    println!("PipeWire: core connected");
+    eprintln!("PipeWire: core connected");
 
     let user_data = UserData {
         format: Default::default(),
@@ -87,20 +222,20 @@ fn run_pipewire_capture(
     // For capturing from speakers (output), we need to capture from sink monitor
     if !config.is_input {
         props.insert(*pw::keys::STREAM_CAPTURE_SINK, "true");
-        println!("PipeWire: configured for sink monitor capture (speakers)");
+        eprintln!("PipeWire: configured for sink monitor capture (speakers)");
     } else {
-        println!("PipeWire: configured for microphone capture");
+        eprintln!("PipeWire: configured for microphone capture");
     }
 
     // Set target device if specified
     if let Some(ref device) = config.device {
         props.insert(*pw::keys::TARGET_OBJECT, device.clone());
-        println!("PipeWire: target device set to: {}", device);
+        eprintln!("PipeWire: target device set to: {}", device);
     }
 
-    println!("PipeWire: creating stream with properties: {:?}", props);
+    eprintln!("PipeWire: creating stream with properties: {:?}", props);
     let stream = pw::stream::Stream::new(&core, "audio-capture", props)?;
-    println!("PipeWire: stream created successfully");
+    eprintln!("PipeWire: stream created successfully");
 
     let _listener = stream
         .add_local_listener_with_user_data(user_data)
@@ -109,32 +244,32 @@ fn run_pipewire_capture(
                 return;
             }
 
-            println!("PipeWire: param_changed callback called with id: {:?}", id);
+            eprintln!("PipeWire: param_changed callback called with id: {:?}", id);
 
             let Some(param) = param else {
-                println!("PipeWire: param is None, clearing format");
+                eprintln!("PipeWire: param is None, clearing format");
                 return;
             };
             
             if id != pw::spa::param::ParamType::Format.as_raw() {
-                println!("PipeWire: ignoring non-format param: {:?}", id);
+                eprintln!("PipeWire: ignoring non-format param: {:?}", id);
                 return;
             }
 
             let (media_type, media_subtype) = match format_utils::parse_format(param) {
                 Ok(v) => {
-                    println!("PipeWire: parsed format - media_type: {:?}, media_subtype: {:?}", v.0, v.1);
+                    eprintln!("PipeWire: parsed format - media_type: {:?}, media_subtype: {:?}", v.0, v.1);
                     v
                 },
                 Err(e) => {
-                    println!("PipeWire: failed to parse format: {:?}", e);
+                    eprintln!("PipeWire: failed to parse format: {:?}", e);
                     return;
                 },
             };
 
             // Only accept raw audio
             if media_type != MediaType::Audio || media_subtype != MediaSubtype::Raw {
-                println!("PipeWire: rejecting non-raw audio format");
+                eprintln!("PipeWire: rejecting non-raw audio format");
                 return;
             }
 
@@ -143,7 +278,7 @@ fn run_pipewire_capture(
                 return;
             }
 
-            println!(
+            eprintln!(
                 "PipeWire capturing rate:{} channels:{}",
                 user_data.format.rate(),
                 user_data.format.channels()
@@ -156,12 +291,12 @@ fn run_pipewire_capture(
 
             match stream.dequeue_buffer() {
                 None => {
-                    println!("PipeWire: out of buffers");
+                    eprintln!("PipeWire: out of buffers");
                 }
                 Some(mut buffer) => {
                     let datas = buffer.datas_mut();
                     if datas.is_empty() {
-                        println!("PipeWire: empty buffer data");
+                        eprintln!("PipeWire: empty buffer data");
                         return;
                     }
 
@@ -175,14 +310,14 @@ fn run_pipewire_capture(
                     static DEBUG_COUNTER: AtomicU32 = AtomicU32::new(0);
                     let counter = DEBUG_COUNTER.fetch_add(1, Ordering::Relaxed);
                     // if counter % 100 == 0 {  // Print every 100 buffers
-                    //     println!("PipeWire: processing buffer #{} with {} samples, {} channels, {} Hz", 
+                    //     eprintln!("PipeWire: processing buffer #{} with {} samples, {} channels, {} Hz", 
                     //             counter, n_samples, n_channels, sample_rate);
                     // }
 
                     if let Some(samples) = data.data() {
                         // Only print this debug info occasionally
                         // if counter % 100 == 0 {
-                        //     println!("PipeWire: raw buffer size: {} bytes", samples.len());
+                        //     eprintln!("PipeWire: raw buffer size: {} bytes", samples.len());
                         // }
                         
                         // Convert raw bytes to f32 samples
@@ -215,14 +350,14 @@ fn run_pipewire_capture(
                         }
 
                         if counter % 100 == 0 {
-                            println!("PipeWire: converted to {} mono samples", audio_samples.len());
+                            eprintln!("PipeWire: converted to {} mono samples", audio_samples.len());
                         }
 
                         // Resample if necessary
                         let resampled_samples = if sample_rate != user_data.target_sample_rate {
                             let resampled = resample_audio(&audio_samples, sample_rate, user_data.target_sample_rate);
                             if counter % 100 == 0 {
-                                println!("PipeWire: resampled from {} Hz to {} Hz: {} -> {} samples", 
+                                eprintln!("PipeWire: resampled from {} Hz to {} Hz: {} -> {} samples", 
                                         sample_rate, user_data.target_sample_rate, audio_samples.len(), resampled.len());
                             }
                             resampled
@@ -232,16 +367,16 @@ fn run_pipewire_capture(
 
                         if !resampled_samples.is_empty() {
                             if counter % 100 == 0 {
-                                // println!("PipeWire: sending {} samples to channel", resampled_samples.len());
+                                // eprintln!("PipeWire: sending {} samples to channel", resampled_samples.len());
                             }
                             if let Err(e) = user_data.tx.send(resampled_samples) {
-                                println!("PipeWire: failed to send samples: {}", e);
+                                eprintln!("PipeWire: failed to send samples: {}", e);
                             }
                         } else if counter % 100 == 0 {
-                            println!("PipeWire: no samples to send");
+                            eprintln!("PipeWire: no samples to send");
                         }
                     } else {
-                        println!("PipeWire: no sample data in buffer");
+                        eprintln!("PipeWire: no sample data in buffer");
                     }
                 }
             }
@@ -255,7 +390,7 @@ fn run_pipewire_capture(
     audio_info.set_rate(config.target_sample_rate);
     audio_info.set_channels(config.target_channels);
     
-    println!("PipeWire: requesting format F32LE, rate {}, channels {}", 
+    eprintln!("PipeWire: requesting format F32LE, rate {}, channels {}", 
              config.target_sample_rate, config.target_channels);
     
     let obj = pw::spa::pod::Object {
@@ -272,10 +407,10 @@ fn run_pipewire_capture(
     .into_inner();
 
     let mut params = [Pod::from_bytes(&values).ok_or_else(|| anyhow::anyhow!("Failed to create Pod from bytes"))?];
-    println!("PipeWire: created audio format parameters");
+    eprintln!("PipeWire: created audio format parameters");
 
     // Connect the stream
-    println!("PipeWire: connecting stream...");
+    eprintln!("PipeWire: connecting stream...");
     stream.connect(
         spa::utils::Direction::Input,
         None,
@@ -284,10 +419,10 @@ fn run_pipewire_capture(
             | pw::stream::StreamFlags::RT_PROCESS,
         &mut params,
     )?;
-    println!("PipeWire: stream connected successfully");
+    eprintln!("PipeWire: stream connected successfully");
 
     // Run the main loop properly
-    println!("PipeWire: starting main loop...");
+    eprintln!("PipeWire: starting main loop...");
     
     // We need to handle the main loop differently
     // Let's try running it in a separate thread and use a channel to signal completion
@@ -299,50 +434,19 @@ fn run_pipewire_capture(
         while !cancel_clone.is_cancelled() {
             std::thread::sleep(std::time::Duration::from_millis(100));
         }
-        println!("PipeWire: cancel detected, sending quit signal");
+        eprintln!("PipeWire: cancel detected, sending quit signal");
         // TODO: Need to find a way to signal the mainloop to quit
     });
     
     // Start the main loop in a blocking manner
-    println!("PipeWire: calling mainloop.run() - this should start processing audio");
+    eprintln!("PipeWire: calling mainloop.run() - this should start processing audio");
     
     // The mainloop.run() should block and process events
     // We'll need to find another way to exit it when cancelled
     mainloop.run();
     
-    println!("PipeWire: mainloop.run() completed");
+    eprintln!("PipeWire: mainloop.run() completed");
 
-    println!("PipeWire audio capture stopped");
+    eprintln!("PipeWire audio capture stopped");
     Ok(())
 }
-
-/// Simple audio resampling using linear interpolation
-fn resample_audio(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
-    if input_rate == output_rate {
-        return input.to_vec();
-    }
-
-    let ratio = output_rate as f64 / input_rate as f64;
-    let output_len = (input.len() as f64 * ratio).ceil() as usize;
-    let mut output = Vec::with_capacity(output_len);
-
-    for i in 0..output_len {
-        let src_index = i as f64 / ratio;
-        let src_index_floor = src_index.floor() as usize;
-        let src_index_ceil = (src_index_floor + 1).min(input.len() - 1);
-        let fraction = src_index - src_index_floor as f64;
-
-        if src_index_floor < input.len() {
-            let sample = if src_index_ceil == src_index_floor {
-                input[src_index_floor]
-            } else {
-                let sample1 = input[src_index_floor];
-                let sample2 = input[src_index_ceil];
-                sample1 + (sample2 - sample1) * fraction as f32
-            };
-            output.push(sample);
-        }
-    }
-
-    output
-}
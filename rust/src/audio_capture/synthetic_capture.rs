@@ -0,0 +1,190 @@
+use super::traits::{
+    AudioCapture, AudioCaptureConfig, AudioCaptureInfo, AudioDeviceDirection, AudioDeviceInfo,
+};
+use super::SYNTHETIC_DEVICE_PREFIX;
+use anyhow::Result;
+use rand::Rng;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Signal generated by [`SyntheticAudioCapture`]
+#[derive(Debug, Clone)]
+pub enum SyntheticSignal {
+    Silence,
+    /// Sine wave
+    Tone {
+        frequency: f32,
+        amplitude: f32,
+    },
+    /// Uniform white noise
+    Noise {
+        amplitude: f32,
+    },
+    /// Pre-recorded pcm (f32, mono, target sample rate), replayed in a loop
+    Buffer(Arc<Vec<f32>>),
+}
+
+impl SyntheticSignal {
+    /// Parse a signal name: `silence`, `tone`, `tone:<hz>`, `noise`
+    pub fn parse(name: &str) -> Result<Self> {
+        let (kind, arg) = match name.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (name, None),
+        };
+        match kind {
+            "" | "silence" => Ok(Self::Silence),
+            "tone" => {
+                let frequency = match arg {
+                    Some(hz) => hz
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("invalid tone frequency: {hz}"))?,
+                    None => 440.0,
+                };
+                Ok(Self::Tone {
+                    frequency,
+                    amplitude: 0.5,
+                })
+            }
+            "noise" => Ok(Self::Noise { amplitude: 0.1 }),
+            _ => anyhow::bail!("unknown synthetic signal: {name}"),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Silence => "silence".to_string(),
+            Self::Tone { frequency, .. } => format!("tone:{frequency}"),
+            Self::Noise { .. } => "noise".to_string(),
+            Self::Buffer(_) => "buffer".to_string(),
+        }
+    }
+}
+
+/// Generates audio in process, for running the caption loop without any audio server.
+pub struct SyntheticAudioCapture {
+    config: AudioCaptureConfig,
+    signal: SyntheticSignal,
+    /// Stop (close the receiver) after this much audio, None for endless
+    duration: Option<Duration>,
+    /// Generation speed, 1.0 is real time, 0.0 pushes samples as fast as possible
+    speed: f32,
+    /// Duration of each pushed chunk
+    chunk_duration: Duration,
+}
+
+impl SyntheticAudioCapture {
+    pub fn with_options(
+        config: AudioCaptureConfig,
+        signal: SyntheticSignal,
+        duration: Option<Duration>,
+        speed: f32,
+    ) -> Result<Self> {
+        if !speed.is_finite() || speed < 0.0 {
+            anyhow::bail!("invalid generation speed: {speed}");
+        }
+        if duration.is_none() && speed == 0.0 {
+            anyhow::bail!("an endless synthetic signal must be generated in real time");
+        }
+        Ok(Self {
+            config,
+            signal,
+            duration,
+            speed,
+            chunk_duration: Duration::from_millis(100),
+        })
+    }
+}
+
+impl AudioCapture for SyntheticAudioCapture {
+    /// Parse the signal from `config.device` (`synthetic:tone:440`, `synthetic:noise` ...), endless, real time
+    fn new(config: AudioCaptureConfig) -> Result<Self> {
+        let name = config.device.as_deref().unwrap_or_default();
+        let signal =
+            SyntheticSignal::parse(name.strip_prefix(SYNTHETIC_DEVICE_PREFIX).unwrap_or(name))?;
+        Self::with_options(config, signal, None, 1.0)
+    }
+
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> {
+        let default_config = AudioCaptureConfig::default();
+        Ok([
+            SyntheticSignal::Silence,
+            SyntheticSignal::parse("tone")?,
+            SyntheticSignal::parse("noise")?,
+        ]
+        .iter()
+        .map(|signal| AudioDeviceInfo {
+            name: format!("Synthetic {}", signal.name()),
+            id: format!("{SYNTHETIC_DEVICE_PREFIX}{}", signal.name()),
+            direction: AudioDeviceDirection::Input,
+            is_default: false,
+            sample_rate: default_config.target_sample_rate,
+            channels: default_config.target_channels,
+        })
+        .collect())
+    }
+
+    fn get_info(&self) -> AudioCaptureInfo {
+        AudioCaptureInfo {
+            device_name: format!("{SYNTHETIC_DEVICE_PREFIX}{}", self.signal.name()),
+            sample_rate: self.config.target_sample_rate,
+            channels: self.config.target_channels,
+        }
+    }
+
+    fn start_capture(&self, cancel_token: CancellationToken) -> Result<mpsc::Receiver<Vec<f32>>> {
+        let (tx, rx) = mpsc::channel::<Vec<f32>>();
+        let sample_rate = self.config.target_sample_rate;
+        let chunk_samples =
+            ((sample_rate as f64 * self.chunk_duration.as_secs_f64()) as usize).max(1);
+        let total_samples = self
+            .duration
+            .map(|d| (d.as_secs_f64() * sample_rate as f64) as usize);
+        let interval = if self.speed > 0.0 {
+            Some(self.chunk_duration.div_f32(self.speed))
+        } else {
+            None
+        };
+        let signal = self.signal.clone();
+
+        thread::spawn(move || {
+            let mut rng = rand::rng();
+            let mut position = 0usize;
+            while !cancel_token.is_cancelled() {
+                let len = match total_samples {
+                    Some(total) if position >= total => break,
+                    Some(total) => chunk_samples.min(total - position),
+                    None => chunk_samples,
+                };
+                let chunk: Vec<f32> = (position..position + len)
+                    .map(|i| match &signal {
+                        SyntheticSignal::Silence => 0.0,
+                        SyntheticSignal::Tone {
+                            frequency,
+                            amplitude,
+                        } => {
+                            let t = i as f32 / sample_rate as f32;
+                            amplitude * (2.0 * std::f32::consts::PI * frequency * t).sin()
+                        }
+                        SyntheticSignal::Noise { amplitude } => {
+                            rng.random_range(-*amplitude..=*amplitude)
+                        }
+                        SyntheticSignal::Buffer(pcm) if pcm.is_empty() => 0.0,
+                        SyntheticSignal::Buffer(pcm) => pcm[i % pcm.len()],
+                    })
+                    .collect();
+                position += len;
+                if tx.send(chunk).is_err() {
+                    break;
+                }
+                if let Some(interval) = interval {
+                    thread::sleep(interval);
+                }
+            }
+            eprintln!("Synthetic capture stopped");
+        });
+
+        Ok(rx)
+    }
+}
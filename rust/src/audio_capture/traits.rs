@@ -34,11 +34,39 @@ pub struct AudioCaptureInfo {
     pub channels: u32,
 }
 
+/// Direction of an audio device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioDeviceDirection {
+    /// Microphone / line in
+    Input,
+    /// Loopback of an output device (speakers, headphones)
+    Monitor,
+}
+
+/// Audio device that can be passed as `AudioCaptureConfig::device`
+#[derive(Debug, Clone)]
+pub struct AudioDeviceInfo {
+    /// Human readable name
+    pub name: String,
+    /// Identifier to pass as `AudioCaptureConfig::device`
+    pub id: String,
+    pub direction: AudioDeviceDirection,
+    /// Whether this is the system default device for its direction
+    pub is_default: bool,
+    /// Native sample rate (Hz), 0 if unknown
+    pub sample_rate: u32,
+    /// Native channel count, 0 if unknown
+    pub channels: u32,
+}
+
 /// Trait for audio capture implementations
 pub trait AudioCapture: Send + Sync {
     /// Create a new audio capture instance
     fn new(config: AudioCaptureConfig) -> Result<Self> where Self: Sized;
     
+    /// List the devices this implementation can capture from
+    fn list_devices() -> Result<Vec<AudioDeviceInfo>> where Self: Sized;
+
     /// Get audio capture information
     fn get_info(&self) -> AudioCaptureInfo;
     
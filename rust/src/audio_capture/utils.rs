@@ -0,0 +1,65 @@
+//! Audio helpers shared by the capture backends and the file decoder
+
+/// Merge multi-channel audio to mono
+pub fn merge_channels(pcm: &[f32], channel_count: usize) -> Vec<f32> {
+    if channel_count == 1 {
+        return pcm.to_vec();
+    }
+
+    let complete_groups = pcm.len() / channel_count;
+    let remaining_samples = pcm.len() % channel_count;
+    let result_capacity = complete_groups + if remaining_samples > 0 { 1 } else { 0 };
+    let mut mono_pcm = Vec::with_capacity(result_capacity);
+
+    // Process complete sample groups
+    for i in 0..complete_groups {
+        let mut sample_sum = 0.0;
+        for ch in 0..channel_count {
+            sample_sum += pcm[i * channel_count + ch];
+        }
+        mono_pcm.push(sample_sum / (channel_count as f32));
+    }
+
+    // Process incomplete sample group if exists
+    if remaining_samples > 0 {
+        let start_idx = complete_groups * channel_count;
+        let mut sample_sum = 0.0;
+        for ch in 0..remaining_samples {
+            sample_sum += pcm[start_idx + ch];
+        }
+        mono_pcm.push(sample_sum / (remaining_samples as f32));
+    }
+
+    mono_pcm
+}
+
+/// Simple audio resampling using linear interpolation
+pub fn resample_audio(input: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
+    if input_rate == output_rate {
+        return input.to_vec();
+    }
+
+    let ratio = output_rate as f64 / input_rate as f64;
+    let output_len = (input.len() as f64 * ratio).ceil() as usize;
+    let mut output = Vec::with_capacity(output_len);
+
+    for i in 0..output_len {
+        let src_index = i as f64 / ratio;
+        let src_index_floor = src_index.floor() as usize;
+        let src_index_ceil = (src_index_floor + 1).min(input.len() - 1);
+        let fraction = src_index - src_index_floor as f64;
+
+        if src_index_floor < input.len() {
+            let sample = if src_index_ceil == src_index_floor {
+                input[src_index_floor]
+            } else {
+                let sample1 = input[src_index_floor];
+                let sample2 = input[src_index_ceil];
+                sample1 + (sample2 - sample1) * fraction as f32
+            };
+            output.push(sample);
+        }
+    }
+
+    output
+}
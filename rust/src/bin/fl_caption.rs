@@ -0,0 +1,518 @@
+//! Headless captioner: live captions from an audio device and file transcription,
+//! using the same engine as the Flutter app.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rust_lib_fl_caption::api::audio::list_audio_devices;
+use rust_lib_fl_caption::audio_capture::decoder;
+use rust_lib_fl_caption::candle_models::whisper::model::{Segment, Task};
+use rust_lib_fl_caption::candle_models::whisper::LaunchCaptionParams;
+use rust_lib_fl_caption::caption::model_files::ModelFiles;
+use rust_lib_fl_caption::caption::subtitles::{self, SubtitleOptions};
+use rust_lib_fl_caption::caption::{self, CaptionConfig, CaptionEvent};
+use rust_lib_fl_caption::caption::{broadcast, transcription_server};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Parser)]
+#[command(
+    name = "fl-caption",
+    version,
+    about = "Headless live captions and file transcription"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Caption live audio until Ctrl+C (or the end of a `file:` device)
+    Live {
+        #[command(flatten)]
+        model: ModelArgs,
+        #[command(flatten)]
+        caption: Box<CaptionArgs>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Transcribe audio files (WAV / FLAC / MP3 / OGG)
+    Transcribe {
+        #[command(flatten)]
+        model: ModelArgs,
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Spoken language such as "en", detected when not set
+        #[arg(long)]
+        language: Option<String>,
+        /// Word-level timestamps (candle Whisper only)
+        #[arg(long)]
+        word_timestamps: bool,
+        /// Run the model on the GPU when available
+        #[arg(long)]
+        cuda: bool,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// Also write a subtitle file next to each input file
+        #[arg(long, value_parser = ["srt", "vtt", "ttml"])]
+        subtitles: Option<String>,
+        #[command(flatten)]
+        decoding: DecodingArgs,
+    },
+    /// Serve an OpenAI compatible `POST /v1/audio/transcriptions` endpoint
+    Serve {
+        #[command(flatten)]
+        model: ModelArgs,
+        /// `host:port` to listen on, a bare port listens on localhost
+        #[arg(long, default_value = "127.0.0.1:8000")]
+        listen: String,
+        /// Load the word alignment so requests can ask for word timestamps
+        #[arg(long)]
+        word_timestamps: bool,
+        /// Run the model on the GPU when available
+        #[arg(long)]
+        cuda: bool,
+        #[command(flatten)]
+        decoding: DecodingArgs,
+    },
+    /// List the audio devices, the id can be passed as `--device`
+    Devices,
+}
+
+#[derive(Args)]
+struct ModelArgs {
+    /// Directory containing the model files
+    #[arg(long)]
+    model_dir: PathBuf,
+    /// whisper, sense-voice_onnx, whisper-olive_onnx or zipformer_onnx
+    #[arg(long, default_value = "whisper")]
+    model_type: String,
+    /// Override the multilingual detection of the model
+    #[arg(long)]
+    multilingual: Option<bool>,
+}
+
+impl ModelArgs {
+    fn load(&self) -> anyhow::Result<ModelFiles> {
+        let mut files = ModelFiles::from_dir(&self.model_dir, &self.model_type)?;
+        if let Some(multilingual) = self.multilingual {
+            files.is_multilingual = multilingual;
+        }
+        Ok(files)
+    }
+}
+
+/// Options of `launch_caption`, they override the values of `--config`
+#[derive(Args)]
+struct CaptionArgs {
+    /// CaptionConfig file, JSON when the extension is `.json`, TOML otherwise
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Audio device id, `file:<path>` replays a file
+    #[arg(long)]
+    device: Option<String>,
+    /// Capture what the device plays instead of its input
+    #[arg(long)]
+    loopback: bool,
+    #[arg(long)]
+    language: Option<String>,
+    #[arg(long)]
+    cuda: bool,
+    #[arg(long)]
+    vad_model: Option<String>,
+    #[arg(long)]
+    vad_threshold: Option<f32>,
+    #[arg(long)]
+    interval_ms: Option<u64>,
+    #[arg(long)]
+    timeout_ms: Option<u64>,
+    #[arg(long)]
+    max_audio_secs: Option<u32>,
+    #[arg(long)]
+    local_agreement: Option<u32>,
+    #[arg(long)]
+    endpoint_silence_ms: Option<u32>,
+    #[arg(long)]
+    timestamps: bool,
+    /// Append the committed captions to a `.srt`, `.vtt` or `.ttml` file
+    #[arg(long)]
+    subtitles: Option<String>,
+    /// Archive the audio to a `.wav` or `.flac` file with a `.jsonl` transcript next to it
+    #[arg(long)]
+    record: Option<String>,
+    /// Broadcast the captions over WebSocket on `host:port` (a bare port listens on localhost)
+    #[arg(long)]
+    broadcast: Option<String>,
+    /// Keep the last caption lines in a text file for an OBS Text source
+    #[arg(long)]
+    obs_text: Option<String>,
+    #[arg(long)]
+    obs_text_lines: Option<u32>,
+    /// Send closed captions to OBS through obs-websocket, e.g. ws://127.0.0.1:4455
+    #[arg(long)]
+    obs_websocket: Option<String>,
+    #[arg(long, env = "OBS_WEBSOCKET_PASSWORD", hide_env_values = true)]
+    obs_password: Option<String>,
+    /// Put the SenseVoice emotion and audio event emoji into the text
+    #[arg(long)]
+    inline_emoji: bool,
+    /// Inverse text normalization of the SenseVoice output (written-form numbers and
+    /// punctuation)
+    #[arg(long)]
+    itn: bool,
+    #[command(flatten)]
+    decoding: DecodingArgs,
+}
+
+/// Whisper decoding options
+#[derive(Args)]
+struct DecodingArgs {
+    /// Beam search width, greedy decoding when not set (candle Whisper only)
+    #[arg(long)]
+    beam_size: Option<u32>,
+    #[arg(long, requires = "beam_size")]
+    patience: Option<f32>,
+    #[arg(long, requires = "beam_size")]
+    length_penalty: Option<f32>,
+    /// Initial prompt, such as names and jargon to spell right
+    #[arg(long)]
+    prompt: Option<String>,
+    /// Condition each window on up to this many tokens of the previous text
+    #[arg(long)]
+    condition_on_previous_text: Option<u32>,
+    /// Translate the speech to English (multilingual Whisper only)
+    #[arg(long)]
+    translate: bool,
+    /// Detect the language of every window, for speakers switching languages
+    #[arg(long)]
+    redetect_language: bool,
+    /// Probability another language needs to take over, default 0.6
+    #[arg(long, requires = "redetect_language")]
+    language_switch_threshold: Option<f32>,
+    /// Windows another language has to win in a row before taking over, default 2
+    #[arg(long, requires = "redetect_language")]
+    language_switch_windows: Option<u32>,
+}
+
+impl DecodingArgs {
+    fn into_params(self) -> LaunchCaptionParams {
+        LaunchCaptionParams {
+            whisper_beam_size: self.beam_size.map(|n| n as usize),
+            whisper_beam_patience: self.patience,
+            whisper_length_penalty: self.length_penalty,
+            whisper_initial_prompt: self.prompt,
+            whisper_previous_text_tokens: self.condition_on_previous_text.map(|n| n as usize),
+            whisper_task: self.translate.then_some(Task::Translate),
+            whisper_redetect_language: Some(self.redetect_language),
+            whisper_language_switch_threshold: self.language_switch_threshold,
+            whisper_language_switch_windows: self.language_switch_windows.map(|n| n as usize),
+            ..Default::default()
+        }
+    }
+}
+
+impl CaptionArgs {
+    fn to_config(&self) -> anyhow::Result<CaptionConfig> {
+        let mut config = match &self.config {
+            Some(path) => {
+                let s = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    CaptionConfig::from_json(&s)?
+                } else {
+                    CaptionConfig::from_toml(&s)?
+                }
+            }
+            None => CaptionConfig::default(),
+        };
+        if self.device.is_some() {
+            config.audio_device = self.device.clone();
+        }
+        if self.loopback {
+            config.audio_device_is_input = false;
+        }
+        if self.language.is_some() {
+            config.language = self.language.clone();
+        }
+        config.try_with_cuda |= self.cuda;
+        config.with_timestamps |= self.timestamps;
+        if self.vad_model.is_some() {
+            config.vad_model_path = self.vad_model.clone();
+        }
+        if self.vad_threshold.is_some() {
+            config.vad_threshold = self.vad_threshold;
+        }
+        if let Some(interval) = self.interval_ms {
+            config.inference_interval_ms = interval;
+        }
+        if self.timeout_ms.is_some() {
+            config.inference_timeout_ms = self.timeout_ms;
+        }
+        if let Some(secs) = self.max_audio_secs {
+            config.max_audio_duration_secs = secs;
+        }
+        if self.local_agreement.is_some() {
+            config.local_agreement = self.local_agreement;
+        }
+        if self.endpoint_silence_ms.is_some() {
+            config.endpoint_silence_ms = self.endpoint_silence_ms;
+        }
+        if self.subtitles.is_some() {
+            config.subtitle_path = self.subtitles.clone();
+        }
+        if self.record.is_some() {
+            config.record_path = self.record.clone();
+        }
+        if self.broadcast.is_some() {
+            config.broadcast_addr = self.broadcast.clone();
+        }
+        if self.obs_text.is_some() {
+            config.obs_text_path = self.obs_text.clone();
+        }
+        if let Some(lines) = self.obs_text_lines {
+            config.obs_text_lines = lines;
+        }
+        if self.obs_websocket.is_some() {
+            config.obs_websocket_url = self.obs_websocket.clone();
+        }
+        if self.obs_password.is_some() {
+            config.obs_websocket_password = self.obs_password.clone();
+        }
+        if self.decoding.beam_size.is_some() {
+            config.beam_size = self.decoding.beam_size;
+            config.beam_patience = self.decoding.patience;
+            config.length_penalty = self.decoding.length_penalty;
+        }
+        if self.decoding.prompt.is_some() {
+            config.initial_prompt = self.decoding.prompt.clone();
+        }
+        if self.decoding.condition_on_previous_text.is_some() {
+            config.condition_on_previous_text = self.decoding.condition_on_previous_text;
+        }
+        config.translate |= self.decoding.translate;
+        config.inline_emoji |= self.inline_emoji;
+        config.with_itn |= self.itn;
+        if self.decoding.redetect_language {
+            config.redetect_language = true;
+            config.language_switch_threshold = self.decoding.language_switch_threshold;
+            config.language_switch_windows = self.decoding.language_switch_windows;
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Plain text, one line per result
+    Text,
+    /// One JSON object per line
+    Jsonl,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Live {
+            model,
+            caption,
+            format,
+        } => live(model, *caption, format).await,
+        Command::Transcribe {
+            model,
+            files,
+            language,
+            word_timestamps,
+            cuda,
+            format,
+            subtitles,
+            decoding,
+        } => {
+            let params = LaunchCaptionParams {
+                audio_language: language,
+                with_timestamps: Some(true),
+                word_timestamps: Some(word_timestamps),
+                try_with_cuda: cuda,
+                ..decoding.into_params()
+            };
+            transcribe(
+                model.load()?.into_params(params),
+                &files,
+                format,
+                subtitles.as_deref(),
+            )
+        }
+        Command::Serve {
+            model,
+            listen,
+            word_timestamps,
+            cuda,
+            decoding,
+        } => {
+            let params = LaunchCaptionParams {
+                with_timestamps: Some(true),
+                word_timestamps: Some(word_timestamps),
+                try_with_cuda: cuda,
+                ..decoding.into_params()
+            };
+            serve(model.load()?.into_params(params), &listen).await
+        }
+        Command::Devices => {
+            for device in list_audio_devices()? {
+                println!(
+                    "{}\t{:?}{}\t{}",
+                    device.id,
+                    device.direction,
+                    if device.is_default { " (default)" } else { "" },
+                    device.name
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn live(model: ModelArgs, args: CaptionArgs, format: OutputFormat) -> anyhow::Result<()> {
+    let files = model.load()?;
+    let config = args.to_config()?;
+    config.validate(&files.model_type, files.is_multilingual)?;
+
+    let cancel_token = CancellationToken::new();
+    let ctrl_c_token = cancel_token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_token.cancel();
+        }
+    });
+
+    let mut output = std::io::stdout();
+    let params = files.into_params(config.into_params(cancel_token));
+    caption::engine::run_caption(params, caption::new_recognizer, move |event| {
+        if let Err(e) = write_event(&mut output, &event, format) {
+            eprintln!("Failed to write caption: {e}");
+        }
+    })
+    .await
+}
+
+async fn serve(params: LaunchCaptionParams, listen: &str) -> anyhow::Result<()> {
+    let addr = broadcast::parse_addr(listen)
+        .with_context(|| format!("invalid listen address {listen}"))?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    let recognizer = caption::new_recognizer(&params)?;
+
+    let cancel_token = CancellationToken::new();
+    let ctrl_c_token = cancel_token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_token.cancel();
+        }
+    });
+    transcription_server::serve(
+        listener,
+        recognizer,
+        params.word_timestamps.unwrap_or(false),
+        cancel_token,
+    )
+    .await
+}
+
+fn write_event(
+    output: &mut impl Write,
+    event: &CaptionEvent,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Jsonl => writeln!(output, "{}", serde_json::to_string(event)?)?,
+        OutputFormat::Text => match event {
+            CaptionEvent::Segments { segments } => {
+                let text = segments
+                    .iter()
+                    .map(|segment| segment.dr.text.trim())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if !text.is_empty() {
+                    writeln!(output, "{text}")?;
+                }
+            }
+            CaptionEvent::Ready {
+                backend, device, ..
+            } => eprintln!("{backend} ready on {device}"),
+            CaptionEvent::Warning { msg } => eprintln!("warning: {msg}"),
+            CaptionEvent::Error { kind, msg } => eprintln!("error ({kind:?}): {msg}"),
+            _ => {}
+        },
+    }
+    output.flush()?;
+    Ok(())
+}
+
+fn transcribe(
+    params: LaunchCaptionParams,
+    files: &[PathBuf],
+    format: OutputFormat,
+    subtitles: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut output = std::io::stdout().lock();
+    let mut recognizer = caption::new_recognizer(&params)?;
+    for file in files {
+        let audio = decoder::decode_audio_file(file, 16000)?;
+        // 每个文件单独检测语言，不沿用上一个文件的文本作为上下文
+        recognizer.reset();
+        let segments = caption::file::transcribe_pcm(
+            recognizer.as_mut(),
+            &audio.pcm,
+            params.audio_language.as_deref(),
+        )?;
+        write_segments(&mut output, file, &segments, format)?;
+        if let Some(extension) = subtitles {
+            let options = SubtitleOptions {
+                language: params.audio_language.clone(),
+                ..Default::default()
+            };
+            subtitles::write_file(file.with_extension(extension), &segments, &options)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_segments(
+    output: &mut impl Write,
+    file: &Path,
+    segments: &[Segment],
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    for segment in segments {
+        match format {
+            OutputFormat::Jsonl => {
+                let line = serde_json::json!({ "file": file, "segment": segment });
+                writeln!(output, "{line}")?;
+            }
+            OutputFormat::Text => writeln!(
+                output,
+                "[{} --> {}] {}",
+                timestamp(segment.start),
+                timestamp(segment.start + segment.duration),
+                segment.dr.text.trim()
+            )?,
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// hh:mm:ss.mmm
+fn timestamp(seconds: f64) -> String {
+    let ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
@@ -0,0 +1,433 @@
+//! Word level timestamps by DTW over the decoder cross-attention.
+//! https://github.com/openai/whisper/blob/main/whisper/timing.py
+//!
+//! candle 的 TextDecoder 不暴露 cross-attention 权重，这里按相同的权重布局重新实现一个
+//! 只用于对齐的解码器（无 kv cache，一次前向整个 token 序列）。
+
+use candle_core::{IndexOp, Tensor};
+use candle_nn::{Embedding, LayerNorm, Linear, Module, VarBuilder};
+use candle_transformers::models::whisper::Config;
+use tokenizers::Tokenizer;
+
+/// Encoder output frames per second (20 ms per frame)
+pub const TOKENS_PER_SECOND: f64 = 50.0;
+const MEDIAN_FILTER_WIDTH: usize = 7;
+/// Languages without spaces between words, split on unicode boundaries instead
+const UNICODE_SPLIT_LANGUAGES: [&str; 6] = ["zh", "ja", "th", "lo", "my", "yue"];
+const PREPEND_PUNCTUATIONS: &str = "\"'“¿([{-";
+const APPEND_PUNCTUATIONS: &str = "\"'.。,，!！?？:：”)]}、";
+
+fn layer_norm(size: usize, vb: VarBuilder) -> candle_core::Result<LayerNorm> {
+    let weight = vb.get(size, "weight")?;
+    let bias = vb.get(size, "bias")?;
+    Ok(LayerNorm::new(weight, bias, 1e-5))
+}
+
+struct Attention {
+    query: Linear,
+    key: Linear,
+    value: Linear,
+    out: Linear,
+    n_head: usize,
+}
+
+impl Attention {
+    fn load(n_state: usize, n_head: usize, vb: VarBuilder) -> candle_core::Result<Self> {
+        Ok(Self {
+            query: candle_nn::linear(n_state, n_state, vb.pp("q_proj"))?,
+            key: candle_nn::linear_no_bias(n_state, n_state, vb.pp("k_proj"))?,
+            value: candle_nn::linear(n_state, n_state, vb.pp("v_proj"))?,
+            out: candle_nn::linear(n_state, n_state, vb.pp("out_proj"))?,
+            n_head,
+        })
+    }
+
+    fn reshape_head(&self, x: &Tensor) -> candle_core::Result<Tensor> {
+        let (n_batch, n_ctx, n_state) = x.dims3()?;
+        x.reshape((n_batch, n_ctx, self.n_head, n_state / self.n_head))?
+            .transpose(1, 2)
+    }
+
+    /// Returns the attention output and the scaled qk logits (batch, head, query, key)
+    fn forward(
+        &self,
+        x: &Tensor,
+        xa: Option<&Tensor>,
+        mask: Option<&Tensor>,
+    ) -> candle_core::Result<(Tensor, Tensor)> {
+        let q = self.query.forward(x)?;
+        let kv = xa.unwrap_or(x);
+        let k = self.key.forward(kv)?;
+        let v = self.value.forward(kv)?;
+
+        let (_, n_ctx, n_state) = q.dims3()?;
+        let scale = ((n_state / self.n_head) as f64).powf(-0.25);
+        let q = (self.reshape_head(&q)? * scale)?;
+        let k = (self.reshape_head(&k)?.transpose(2, 3)? * scale)?;
+        let v = self.reshape_head(&v)?.contiguous()?;
+        let mut qk = q.matmul(&k)?;
+        if let Some(mask) = mask {
+            qk = qk.broadcast_add(&mask.i((0..n_ctx, 0..n_ctx))?)?;
+        }
+        let w = candle_nn::ops::softmax_last_dim(&qk)?;
+        let wv = w.matmul(&v)?.transpose(1, 2)?.flatten_from(2)?;
+        Ok((self.out.forward(&wv)?, qk))
+    }
+}
+
+struct Block {
+    attn: Attention,
+    attn_ln: LayerNorm,
+    cross_attn: Attention,
+    cross_attn_ln: LayerNorm,
+    mlp_linear1: Linear,
+    mlp_linear2: Linear,
+    mlp_ln: LayerNorm,
+}
+
+impl Block {
+    fn load(n_state: usize, n_head: usize, vb: VarBuilder) -> candle_core::Result<Self> {
+        Ok(Self {
+            attn: Attention::load(n_state, n_head, vb.pp("self_attn"))?,
+            attn_ln: layer_norm(n_state, vb.pp("self_attn_layer_norm"))?,
+            cross_attn: Attention::load(n_state, n_head, vb.pp("encoder_attn"))?,
+            cross_attn_ln: layer_norm(n_state, vb.pp("encoder_attn_layer_norm"))?,
+            mlp_linear1: candle_nn::linear(n_state, n_state * 4, vb.pp("fc1"))?,
+            mlp_linear2: candle_nn::linear(n_state * 4, n_state, vb.pp("fc2"))?,
+            mlp_ln: layer_norm(n_state, vb.pp("final_layer_norm"))?,
+        })
+    }
+
+    /// Returns the block output and the cross-attention qk logits
+    fn forward(
+        &self,
+        x: &Tensor,
+        xa: &Tensor,
+        mask: &Tensor,
+    ) -> candle_core::Result<(Tensor, Tensor)> {
+        let (attn, _) = self
+            .attn
+            .forward(&self.attn_ln.forward(x)?, None, Some(mask))?;
+        let x = (x + attn)?;
+        let (cross_attn, cross_qk) =
+            self.cross_attn
+                .forward(&self.cross_attn_ln.forward(&x)?, Some(xa), None)?;
+        let x = (x + cross_attn)?;
+        let mlp = self.mlp_linear2.forward(
+            &self
+                .mlp_linear1
+                .forward(&self.mlp_ln.forward(&x)?)?
+                .gelu()?,
+        )?;
+        Ok(((x + mlp)?, cross_qk))
+    }
+}
+
+/// Aligns decoded text tokens with the audio frames, see [`WordAligner::align`]
+pub struct WordAligner {
+    token_embedding: Embedding,
+    positional_embedding: Tensor,
+    blocks: Vec<Block>,
+    mask: Tensor,
+    /// (layer, head) pairs whose cross-attention is used for the alignment
+    alignment_heads: Vec<(usize, usize)>,
+}
+
+impl WordAligner {
+    /// Load the decoder weights of a (non quantized) safetensors Whisper model.
+    /// Without per-model alignment heads, every head of the second half of the decoder layers is used,
+    /// the same fallback as OpenAI whisper.
+    pub fn load(vb: VarBuilder, config: &Config) -> candle_core::Result<Self> {
+        let vb = vb.pp("model.decoder");
+        let n_state = config.d_model;
+        let n_head = config.decoder_attention_heads;
+        let n_ctx = config.max_target_positions;
+        let n_layer = config.decoder_layers;
+
+        let token_embedding =
+            candle_nn::embedding(config.vocab_size, n_state, vb.pp("embed_tokens"))?;
+        let positional_embedding = vb.get((n_ctx, n_state), "embed_positions.weight")?;
+        let blocks = (0..n_layer)
+            .map(|i| Block::load(n_state, n_head, vb.pp(format!("layers.{i}"))))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let mask: Vec<_> = (0..n_ctx)
+            .flat_map(|i| (0..n_ctx).map(move |j| if j > i { f32::NEG_INFINITY } else { 0f32 }))
+            .collect();
+        let mask = Tensor::from_vec(mask, (n_ctx, n_ctx), vb.device())?;
+        let alignment_heads = (n_layer / 2..n_layer)
+            .flat_map(|layer| (0..n_head).map(move |head| (layer, head)))
+            .collect();
+
+        Ok(Self {
+            token_embedding,
+            positional_embedding,
+            blocks,
+            mask,
+            alignment_heads,
+        })
+    }
+
+    /// Find when each of `text_tokens` starts, relative to the start of the window.
+    /// `prompt` is the sot sequence (sot, language, task) used for decoding.
+    /// Returns `text_tokens.len() + 1` times, the last one being the end of the last token.
+    pub fn align(
+        &self,
+        audio_features: &Tensor,
+        prompt: &[u32],
+        no_timestamps_token: u32,
+        text_tokens: &[u32],
+        eot_token: u32,
+    ) -> anyhow::Result<Vec<f64>> {
+        if text_tokens.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut tokens = prompt.to_vec();
+        tokens.push(no_timestamps_token);
+        tokens.extend_from_slice(text_tokens);
+        tokens.push(eot_token);
+        if tokens.len() > self.positional_embedding.dim(0)? {
+            anyhow::bail!("too many tokens to align: {}", tokens.len());
+        }
+
+        let tokens_t = Tensor::new(tokens.as_slice(), audio_features.device())?.unsqueeze(0)?;
+        let mut x = self
+            .token_embedding
+            .forward(&tokens_t)?
+            .broadcast_add(&self.positional_embedding.narrow(0, 0, tokens.len())?)?;
+        let last_layer = self
+            .alignment_heads
+            .iter()
+            .map(|(layer, _)| *layer)
+            .max()
+            .unwrap_or(0);
+        let mut weights = vec![];
+        for (layer, block) in self.blocks.iter().enumerate().take(last_layer + 1) {
+            let (y, cross_qk) = block.forward(&x, audio_features, &self.mask)?;
+            x = y;
+            for (_, head) in self.alignment_heads.iter().filter(|(l, _)| *l == layer) {
+                weights.push(cross_qk.i((0, *head))?);
+            }
+        }
+        // (head, token, frame)
+        let weights = candle_nn::ops::softmax_last_dim(&Tensor::stack(&weights, 0)?)?;
+        let weights: Vec<Vec<Vec<f32>>> = weights.to_vec3()?;
+
+        let matrix = attention_matrix(weights);
+        // 去掉 sot 序列和最后的 eot，剩下 no_timestamps + 文本 token 共 len + 1 行
+        let matrix = &matrix[prompt.len()..tokens.len() - 1];
+        let cost: Vec<Vec<f32>> = matrix
+            .iter()
+            .map(|row| row.iter().map(|v| -v).collect())
+            .collect();
+        let (text_indices, time_indices) = dtw(&cost);
+
+        // 每个 token 第一次出现在路径上的帧即为该 token 的开始时间
+        let mut jump_times = Vec::with_capacity(text_tokens.len() + 1);
+        for (k, (&text_index, &time_index)) in
+            text_indices.iter().zip(time_indices.iter()).enumerate()
+        {
+            if k == 0 || text_index != text_indices[k - 1] {
+                jump_times.push(time_index as f64 / TOKENS_PER_SECOND);
+            }
+        }
+        Ok(jump_times)
+    }
+}
+
+/// Normalize each head along the token axis, median filter along the frame axis, then average the heads
+fn attention_matrix(mut weights: Vec<Vec<Vec<f32>>>) -> Vec<Vec<f32>> {
+    let n_heads = weights.len();
+    let n_tokens = weights.first().map_or(0, |w| w.len());
+    let n_frames = weights
+        .first()
+        .and_then(|w| w.first())
+        .map_or(0, |w| w.len());
+
+    for head in weights.iter_mut() {
+        for frame in 0..n_frames {
+            let mean = head.iter().map(|row| row[frame]).sum::<f32>() / n_tokens as f32;
+            let var = head
+                .iter()
+                .map(|row| (row[frame] - mean).powi(2))
+                .sum::<f32>()
+                / n_tokens as f32;
+            let std = var.sqrt().max(1e-10);
+            for row in head.iter_mut() {
+                row[frame] = (row[frame] - mean) / std;
+            }
+        }
+        for row in head.iter_mut() {
+            *row = median_filter(row, MEDIAN_FILTER_WIDTH);
+        }
+    }
+
+    let mut matrix = vec![vec![0f32; n_frames]; n_tokens];
+    for head in &weights {
+        for (t, row) in head.iter().enumerate() {
+            for (f, v) in row.iter().enumerate() {
+                matrix[t][f] += v / n_heads as f32;
+            }
+        }
+    }
+    matrix
+}
+
+/// Median filter with reflect padding
+fn median_filter(x: &[f32], width: usize) -> Vec<f32> {
+    let pad = width / 2;
+    if x.len() <= pad {
+        return x.to_vec();
+    }
+    let reflect = |i: isize| -> f32 {
+        let n = x.len() as isize;
+        let i = if i < 0 {
+            -i
+        } else if i >= n {
+            2 * (n - 1) - i
+        } else {
+            i
+        };
+        x[i.clamp(0, n - 1) as usize]
+    };
+    let mut window = vec![0f32; width];
+    (0..x.len() as isize)
+        .map(|i| {
+            for (k, w) in window.iter_mut().enumerate() {
+                *w = reflect(i + k as isize - pad as isize);
+            }
+            window.sort_by(|a, b| a.total_cmp(b));
+            window[pad]
+        })
+        .collect()
+}
+
+/// Dynamic time warping, returns the (text index, time index) path
+fn dtw(x: &[Vec<f32>]) -> (Vec<usize>, Vec<usize>) {
+    let n = x.len();
+    let m = x.first().map_or(0, |r| r.len());
+    let mut cost = vec![vec![f32::INFINITY; m + 1]; n + 1];
+    let mut trace = vec![vec![-1i8; m + 1]; n + 1];
+    cost[0][0] = 0.0;
+    for j in 1..=m {
+        for i in 1..=n {
+            let c0 = cost[i - 1][j - 1];
+            let c1 = cost[i - 1][j];
+            let c2 = cost[i][j - 1];
+            let (c, t) = if c0 < c1 && c0 < c2 {
+                (c0, 0)
+            } else if c1 < c0 && c1 < c2 {
+                (c1, 1)
+            } else {
+                (c2, 2)
+            };
+            cost[i][j] = x[i - 1][j - 1] + c;
+            trace[i][j] = t;
+        }
+    }
+
+    for t in trace[0].iter_mut() {
+        *t = 2;
+    }
+    for row in trace.iter_mut() {
+        row[0] = 1;
+    }
+    let (mut i, mut j) = (n, m);
+    let mut text_indices = vec![];
+    let mut time_indices = vec![];
+    while i > 0 || j > 0 {
+        text_indices.push(i.saturating_sub(1));
+        time_indices.push(j.saturating_sub(1));
+        match trace[i][j] {
+            0 => {
+                i -= 1;
+                j -= 1;
+            }
+            1 => i -= 1,
+            _ => j -= 1,
+        }
+    }
+    text_indices.reverse();
+    time_indices.reverse();
+    (text_indices, time_indices)
+}
+
+/// Group text tokens into words, returns (word, token count) pairs.
+/// Languages written without spaces are split on unicode characters.
+pub fn split_words(
+    tokenizer: &Tokenizer,
+    tokens: &[u32],
+    language: Option<&str>,
+) -> anyhow::Result<Vec<(String, usize)>> {
+    // 先按完整的 unicode 字符切分（一个汉字可能由多个 token 组成）
+    let mut unicode_words: Vec<(String, usize)> = vec![];
+    let mut current = vec![];
+    for &token in tokens {
+        current.push(token);
+        let decoded = tokenizer
+            .decode(&current, false)
+            .map_err(anyhow::Error::msg)?;
+        if !decoded.contains('\u{FFFD}') {
+            unicode_words.push((decoded, current.len()));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        let decoded = tokenizer
+            .decode(&current, false)
+            .map_err(anyhow::Error::msg)?;
+        unicode_words.push((decoded, current.len()));
+    }
+
+    if language.is_some_and(|l| UNICODE_SPLIT_LANGUAGES.contains(&l)) {
+        return Ok(merge_punctuations(unicode_words));
+    }
+
+    let mut words: Vec<(String, usize)> = vec![];
+    for (subword, count) in unicode_words {
+        let with_space = subword.starts_with(' ');
+        let trimmed = subword.trim();
+        let punctuation = !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_punctuation());
+        match words.last_mut() {
+            Some(last) if !with_space && !punctuation => {
+                last.0.push_str(&subword);
+                last.1 += count;
+            }
+            _ => words.push((subword, count)),
+        }
+    }
+    Ok(merge_punctuations(words))
+}
+
+/// Attach leading punctuations to the next word and trailing ones to the previous word
+fn merge_punctuations(words: Vec<(String, usize)>) -> Vec<(String, usize)> {
+    let mut merged: Vec<(String, usize)> = vec![];
+    let mut pending: Option<(String, usize)> = None;
+    for (word, count) in words {
+        let trimmed = word.trim();
+        let is_append = !trimmed.is_empty()
+            && !word.starts_with(' ')
+            && trimmed.chars().all(|c| APPEND_PUNCTUATIONS.contains(c));
+        let is_prepend =
+            !trimmed.is_empty() && trimmed.chars().all(|c| PREPEND_PUNCTUATIONS.contains(c));
+        if is_append && pending.is_none() {
+            if let Some(last) = merged.last_mut() {
+                last.0.push_str(&word);
+                last.1 += count;
+                continue;
+            }
+        }
+        let (word, count) = match pending.take() {
+            Some((prefix, prefix_count)) => (prefix + &word, prefix_count + count),
+            None => (word, count),
+        };
+        if is_prepend {
+            pending = Some((word, count));
+        } else {
+            merged.push((word, count));
+        }
+    }
+    if let Some(pending) = pending {
+        merged.push(pending);
+    }
+    merged
+}
@@ -0,0 +1,179 @@
+//! Beam search bookkeeping, ported from OpenAI Whisper's `BeamSearchDecoder` and
+//! `MaximumLikelihoodRanker`. The decoder runs the model on all beams as one batch and
+//! feeds the log probabilities of the last position to [`Beams::update`].
+
+/// Beam search settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeamSearch {
+    pub beam_size: usize,
+    /// Stop once `round(beam_size * patience)` sequences finished, default 1.0
+    pub patience: f32,
+    /// None ranks finished sequences by their average log probability, otherwise by the
+    /// Google NMT length penalty `((5 + length) / 6) ^ length_penalty`
+    pub length_penalty: Option<f32>,
+}
+
+impl BeamSearch {
+    pub fn new(beam_size: usize, patience: Option<f32>, length_penalty: Option<f32>) -> Self {
+        Self {
+            beam_size,
+            patience: patience.unwrap_or(1.0),
+            length_penalty,
+        }
+    }
+}
+
+/// Token sequence with the sum of its sampled token log probabilities
+type Sequence = (Vec<u32>, f64);
+
+pub(crate) struct Beams {
+    options: BeamSearch,
+    eot_token: u32,
+    sample_begin: usize,
+    max_candidates: usize,
+    /// Unfinished sequences, always `beam_size` of them with the same length
+    beams: Vec<Sequence>,
+    finished: Vec<Sequence>,
+}
+
+impl Beams {
+    /// All beams start with `prefix` (sot sequence)
+    pub fn new(options: BeamSearch, prefix: Vec<u32>, eot_token: u32) -> Self {
+        let max_candidates =
+            ((options.beam_size as f32 * options.patience).round() as usize).max(1);
+        Self {
+            options,
+            eot_token,
+            sample_begin: prefix.len(),
+            max_candidates,
+            beams: vec![(prefix, 0.0); options.beam_size],
+            finished: vec![],
+        }
+    }
+
+    /// Tokens of every beam, row-major `(beam_size, len)`
+    pub fn tokens(&self) -> Vec<u32> {
+        self.beams
+            .iter()
+            .flat_map(|(tokens, _)| tokens.iter().copied())
+            .collect()
+    }
+
+    /// Length of every beam
+    pub fn seq_len(&self) -> usize {
+        self.beams[0].0.len()
+    }
+
+    /// Extend the beams with the next token log probabilities of each beam
+    pub fn update(&mut self, logprobs: &[Vec<f32>]) {
+        let beam_size = self.options.beam_size;
+        // 每个 beam 取 beam_size + 1 个候选，保证去掉结束的序列后仍有 beam_size 个
+        let mut candidates: Vec<Sequence> = vec![];
+        for ((tokens, sum_logprob), logprobs) in self.beams.iter().zip(logprobs) {
+            for (token, logprob) in top_k(logprobs, beam_size + 1) {
+                let mut sequence = tokens.clone();
+                sequence.push(token);
+                // 首步所有 beam 相同，相同的序列只保留一个
+                if !candidates.iter().any(|(s, _)| *s == sequence) {
+                    candidates.push((sequence, sum_logprob + logprob as f64));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let mut beams = vec![];
+        for (sequence, score) in candidates {
+            if sequence.last() == Some(&self.eot_token) {
+                if self.finished.len() < self.max_candidates {
+                    self.finished.push((sequence, score));
+                }
+            } else {
+                beams.push((sequence, score));
+                if beams.len() == beam_size {
+                    break;
+                }
+            }
+        }
+        self.beams = beams;
+    }
+
+    /// Enough sequences finished
+    pub fn is_done(&self) -> bool {
+        self.finished.len() >= self.max_candidates
+    }
+
+    /// Best finished sequence (the unfinished beams when none finished) and its
+    /// average log probability
+    pub fn best(self) -> (Vec<u32>, f64) {
+        let finished = if self.finished.is_empty() {
+            self.beams
+        } else {
+            self.finished
+        };
+        let sampled_len = |tokens: &[u32]| {
+            tokens[self.sample_begin..]
+                .iter()
+                .filter(|&&t| t != self.eot_token)
+                .count() as f64
+        };
+        let length_penalty = self.options.length_penalty;
+        let score = |(tokens, sum_logprob): &Sequence| {
+            let length = sampled_len(tokens);
+            let penalty = match length_penalty {
+                None => length.max(1.0),
+                Some(alpha) => ((5.0 + length) / 6.0).powf(alpha as f64),
+            };
+            sum_logprob / penalty
+        };
+        let (tokens, sum_logprob) = finished
+            .into_iter()
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .unwrap_or_default();
+        // 与 OpenAI 相同，平均值把结束 token 也算在内
+        let avg_logprob = sum_logprob / (sampled_len(&tokens) + 1.0);
+        (tokens, avg_logprob)
+    }
+}
+
+/// Indices and values of the `k` largest entries
+fn top_k(values: &[f32], k: usize) -> Vec<(u32, f32)> {
+    let mut indexed: Vec<(u32, f32)> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (i as u32, v))
+        .collect();
+    let k = k.min(indexed.len());
+    if k == 0 {
+        return vec![];
+    }
+    indexed.select_nth_unstable_by(k - 1, |a, b| b.1.total_cmp(&a.1));
+    indexed.truncate(k);
+    indexed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_search_prefers_likely_sequence() {
+        // 词表：0 = sot, 1 = eot, 2 = "a", 3 = "b"
+        let ln = |p: f32| p.ln();
+        let mut beams = Beams::new(BeamSearch::new(2, Some(0.5), None), vec![0], 1);
+        // 贪心会选 "a"，但 "a" 之后都不确定；"b" 之后几乎一定结束
+        beams.update(&[
+            vec![f32::NEG_INFINITY, ln(0.1), ln(0.5), ln(0.4)],
+            vec![f32::NEG_INFINITY, ln(0.1), ln(0.5), ln(0.4)],
+        ]);
+        assert_eq!(beams.tokens(), vec![0, 2, 0, 3]);
+        assert!(!beams.is_done());
+        beams.update(&[
+            vec![f32::NEG_INFINITY, ln(0.3), ln(0.35), ln(0.35)],
+            vec![f32::NEG_INFINITY, ln(0.9), ln(0.05), ln(0.05)],
+        ]);
+        assert!(beams.is_done());
+        let (tokens, avg_logprob) = beams.best();
+        assert_eq!(tokens, vec![0, 3, 1]);
+        assert!((avg_logprob - (0.4f64.ln() + 0.9f64.ln()) / 2.0).abs() < 1e-5);
+    }
+}
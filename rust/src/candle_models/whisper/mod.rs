@@ -1,18 +1,25 @@
+pub mod alignment;
+pub mod beam_search;
 pub mod model;
 pub mod multilingual;
+pub mod prompt;
 
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::audio_capture::{AudioCapture, AudioCaptureConfig, PlatformAudioCapture};
-use crate::candle_models::whisper::model::{Model, Segment};
-use crate::{get_device, onnx_models};
+use crate::candle_models::whisper::beam_search::BeamSearch;
+use crate::candle_models::whisper::model::{LanguageProbability, Model, Segment, Task};
+use crate::candle_models::whisper::multilingual::LanguageTracker;
+use crate::candle_models::whisper::prompt::WhisperPrompt;
+use crate::caption::{self, CaptionEvent, SpeechRecognizer};
+use crate::get_device;
+use candle_core::{Device, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::whisper::{self as m, audio, Config};
 use tokenizers::Tokenizer;
-use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
+#[derive(Default)]
 pub struct LaunchCaptionParams {
     pub models: HashMap<String, String>,
     pub config_data: String,
@@ -25,6 +32,7 @@ pub struct LaunchCaptionParams {
     pub is_multilingual: Option<bool>,
     pub cancel_token: CancellationToken,
     pub with_timestamps: Option<bool>,
+    pub word_timestamps: Option<bool>, // 词级时间戳（隐含 with_timestamps）
     pub verbose: Option<bool>,
     pub try_with_cuda: bool,
     pub inference_timeout: Option<Duration>, // 推理总超时参数
@@ -34,356 +42,313 @@ pub struct LaunchCaptionParams {
     pub whisper_temperature: Option<f32>,    // 温度参数
     pub vad_model_path: Option<String>,      // VAD模型路径
     pub vad_filters_value: Option<f32>,      // VAD模型阈值
+    pub local_agreement: Option<usize>,      // 连续 n 次推理一致的文本才提交，None 表示关闭
+    pub endpoint_silence_ms: Option<u32>, // 启用 VAD 断句：静音超过该时长即结束一句（需要 VAD 模型）
+    pub subtitle_path: Option<String>,    // 实时写入已提交字幕的 .srt / .vtt / .ttml 文件
+    pub record_path: Option<String>,      // 录音存档（.wav / .flac），旁边写入 JSONL 转写记录
+    pub broadcast_addr: Option<String>, // WebSocket 广播地址（host:port，只写端口则监听 localhost）
+    pub obs_text_path: Option<String>,  // OBS 文本源读取的字幕文件，保留最后几行
+    pub obs_text_lines: Option<u32>,    // OBS 字幕文件保留的行数，默认 3
+    pub obs_websocket_url: Option<String>, // obs-websocket 地址，推送为 OBS 原生闭路字幕
+    pub obs_websocket_password: Option<String>, // obs-websocket 密码
+    pub whisper_beam_size: Option<usize>, // 温度为 0 时的 beam search 宽度，None 或 1 为贪心解码
+    pub whisper_beam_patience: Option<f32>, // beam search 耐心系数，默认 1.0
+    pub whisper_length_penalty: Option<f32>, // beam 排序的长度惩罚，None 按平均对数概率
+    pub whisper_initial_prompt: Option<String>, // 初始提示（人名、术语表），引导拼写与风格
+    pub whisper_previous_text_tokens: Option<usize>, // 以最近已提交文本为上下文的 token 数，None 关闭
+    pub whisper_task: Option<Task>,                  // 转写或翻译为英文，None 为转写
+    pub whisper_redetect_language: Option<bool>, // 每个窗口重新检测语言（多语种交替），需未指定语言
+    pub whisper_language_switch_threshold: Option<f32>, // 切换到新语言所需的最低概率，默认 0.6
+    pub whisper_language_switch_windows: Option<usize>, // 新语言需连续胜出的窗口数，默认 2
+    pub sense_voice_inline_emoji: Option<bool>,  // SenseVoice 情感 / 事件 emoji 写入文本
+    pub sense_voice_use_itn: Option<bool>, // SenseVoice 逆文本正则化（书面数字和标点），默认关闭
 }
 
 pub async fn launch_caption<F>(
     params: LaunchCaptionParams,
-    mut result_callback: F,
+    result_callback: F,
 ) -> anyhow::Result<()>
 where
-    F: FnMut(Vec<Segment>) + Send + 'static,
+    F: FnMut(CaptionEvent) + Send + 'static,
 {
-    let LaunchCaptionParams {
-        models,
-        config_data,
-        is_quantized,
-        tokenizer_data,
-        audio_device,
-        audio_device_is_input,
-        audio_language,
-        is_multilingual,
-        cancel_token,
-        with_timestamps,
-        verbose,
-        try_with_cuda,
-        inference_timeout,
-        max_tokens_per_segment,
-        whisper_max_audio_duration,
-        inference_interval_ms,
-        whisper_temperature,
-        vad_model_path,
-        vad_filters_value,
-        ..
-    } = params;
-
-    let model_path: String = models.values().next().unwrap().to_string();
-    result_callback(_make_status_response(model::WhisperStatus::Loading));
-    let device = get_device(try_with_cuda)?;
-    let arg_is_multilingual = is_multilingual.unwrap_or(false);
-    let arg_language = audio_language;
-    let arg_device = audio_device;
-    let is_input = audio_device_is_input.unwrap_or(true);
-
-    let config: Config = serde_json::from_str(&config_data)?;
-    let tokenizer = Tokenizer::from_bytes(tokenizer_data).unwrap();
-
-    // check model path
-    if !std::path::Path::new(&model_path).exists() {
-        anyhow::bail!("model path does not exist: {model_path}");
-    }
-
-    let model = if is_quantized {
-        let vb = candle_transformers::quantized_var_builder::VarBuilder::from_gguf(
-            &model_path,
-            &device,
-        )?;
-        Model::Quantized(m::quantized_model::Whisper::load(&vb, config.clone())?)
-    } else {
-        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[model_path], m::DTYPE, &device)? };
-        Model::Normal(m::model::Whisper::load(&vb, config.clone())?)
-    };
-    let seed = 299792458;
-    let mut decoder = model::Decoder::new(
-        model,
-        tokenizer.clone(),
-        seed,
-        &device,
-        /* language_token */ None,
-        Some(model::Task::Transcribe),
-        with_timestamps.unwrap_or(false),
-        verbose.unwrap_or(false),
-    )?;
-
-    let mel_bytes = get_mel_bytes(config.num_mel_bins)?;
-    let mut mel_filters = vec![0f32; mel_bytes.len() / 4];
-    <byteorder::LittleEndian as byteorder::ByteOrder>::read_f32_into(&mel_bytes, &mut mel_filters);
-
-    // Set up audio capture using the abstracted interface
-    let audio_capture_config = AudioCaptureConfig {
-        device: arg_device,
-        is_input,
-        target_sample_rate: 16000,
-        target_channels: 1,
-    };
-
-    let audio_capture = PlatformAudioCapture::new(audio_capture_config)?;
-    let audio_info = audio_capture.get_info();
-    println!("Audio capture info: {:?}", audio_info);
-
-    // Start audio capture
-    let rx = audio_capture.start_capture(cancel_token.child_token())?;
+    caption::engine::run_caption(
+        params,
+        |params| Ok(Box::new(WhisperRecognizer::new(params)?)),
+        result_callback,
+    )
+    .await
+}
 
-    result_callback(_make_status_response(model::WhisperStatus::Ready));
-    println!("Whisper Ready...");
+/// Candle Whisper backend for the caption engine
+pub struct WhisperRecognizer {
+    decoder: model::Decoder,
+    tokenizer: Tokenizer,
+    config: Config,
+    mel_filters: Vec<f32>,
+    device: Device,
+    is_multilingual: bool,
+    language_token_set: bool,
+    language_token_name: Option<String>,
+    inference_timeout: Option<Duration>,
+    max_tokens_per_segment: Option<usize>,
+    temperature: Option<f32>,
+    prompt: WhisperPrompt,
+    /// Some when the language is detected again for every window
+    language_tracker: Option<LanguageTracker>,
+}
 
-    // 处理任务在当前函数中运行
-    let mut buffered_pcm = vec![];
-    let mut history_pcm = Vec::new();
-    let mut last_inference_time = Instant::now();
-    // let mut last_vad_passed_time = Instant::now();
-    let mut first_inference_done = false;
-    let inference_interval = Duration::from_millis(inference_interval_ms.unwrap_or(2000)); // 默认2000毫秒
-    let max_audio_duration: usize = whisper_max_audio_duration.unwrap_or(12) as usize; // 默认12秒
-    let mut language_token_set = false;
-    let mut language_token_name: Option<String> = None;
-    let fixed_temperature = whisper_temperature;
+impl WhisperRecognizer {
+    pub fn new(params: &LaunchCaptionParams) -> anyhow::Result<Self> {
+        let model_path: String = params
+            .models
+            .values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no model file provided"))?
+            .to_string();
+        let device = get_device(params.try_with_cuda)?;
+        let is_multilingual = params.is_multilingual.unwrap_or(false);
+
+        let config: Config = serde_json::from_str(&params.config_data)?;
+        let tokenizer =
+            Tokenizer::from_bytes(&params.tokenizer_data).map_err(anyhow::Error::msg)?;
+
+        // 加载模型前先检查语言参数，避免在推理循环中才报错
+        if let Some(language) = &params.audio_language {
+            if !is_multilingual {
+                anyhow::bail!("a language cannot be set for non-multilingual models")
+            }
+            if model::token_id(&tokenizer, &format!("<|{language}|>")).is_err() {
+                anyhow::bail!("language {language} is not supported")
+            }
+        }
 
-    println!("Check and loading vad model...");
-    let mut vad_model = if let Some(vad_model_path) = vad_model_path {
-        // try_with_gpu: [false] candle-onnx not support gpu
-        let model = onnx_models::vad::new_vad_model(vad_model_path, false);
-        if let Ok(model) = model {
-            Some(model)
+        let language_tracker = if params.whisper_redetect_language.unwrap_or(false) {
+            if !is_multilingual {
+                anyhow::bail!("language detection requires a multilingual model")
+            }
+            if params.audio_language.is_some() {
+                anyhow::bail!("a fixed language cannot be detected again for every window")
+            }
+            Some(LanguageTracker::new(
+                params
+                    .whisper_language_switch_threshold
+                    .unwrap_or(multilingual::DEFAULT_LANGUAGE_SWITCH_THRESHOLD),
+                params
+                    .whisper_language_switch_windows
+                    .unwrap_or(multilingual::DEFAULT_LANGUAGE_SWITCH_WINDOWS),
+            ))
         } else {
-            println!("Failed to load VAD model: {:?}", model.err().unwrap());
             None
-        }
-    } else {
-        None
-    };
+        };
 
-    // 处理循环
-    println!("Starting audio processing loop...");
-    let mut debug_counter = 0;
-    while !cancel_token.is_cancelled() {
-        debug_counter += 1;
-        if debug_counter % 500 == 0 {
-            // 每50秒打印一次调试信息
-            println!(
-                "Audio processing loop iteration {}, buffered_pcm.len(): {}",
-                debug_counter,
-                buffered_pcm.len()
-            );
+        let task = params.whisper_task.unwrap_or(Task::Transcribe);
+        if task == Task::Translate && !is_multilingual {
+            anyhow::bail!("translation requires a multilingual model")
         }
 
-        // 尝试接收音频数据，设置超时以便定期检查取消状态
-        let pcm = rx.recv_timeout(Duration::from_millis(100));
-
-        // 如果接收超时或通道关闭，检查是否应该取消
-        if pcm.is_err() {
-            let err = pcm.unwrap_err();
-            if debug_counter % 1000 == 0 {
-                // 每100秒打印一次超时信息
-                println!(
-                    "Audio recv timeout or error: {:?}, cancel_token cancelled: {}",
-                    err,
-                    cancel_token.is_cancelled()
-                );
-            }
-            if cancel_token.is_cancelled() {
-                break;
-            }
-            continue;
+        // check model path
+        if !std::path::Path::new(&model_path).exists() {
+            anyhow::bail!("model path does not exist: {model_path}");
         }
 
-        // 处理接收到的音频数据
-        let pcm = pcm.unwrap();
-
-        static mut AUDIO_RECEIVED: bool = false;
-        unsafe {
-            if !AUDIO_RECEIVED {
-                println!("First audio data received: {} samples", pcm.len());
-                AUDIO_RECEIVED = true;
-            } else if pcm.len() > 0 && debug_counter % 100 == 0 {
-                println!(
-                    "Audio data: {} samples (debug every 100 iterations)",
-                    pcm.len()
+        let word_timestamps = params.word_timestamps.unwrap_or(false);
+        let mut word_aligner = None;
+        let model = if params.is_quantized {
+            let vb = candle_transformers::quantized_var_builder::VarBuilder::from_gguf(
+                &model_path,
+                &device,
+            )?;
+            if word_timestamps {
+                eprintln!(
+                    "word alignment is not available for quantized models, words are interpolated"
                 );
             }
-        }
-
-        buffered_pcm.extend_from_slice(&pcm);
-
-        if buffered_pcm.len() > 0 && (buffered_pcm.len() % 16000 == 0 || debug_counter % 200 == 0) {
-            println!(
-                "Total buffered_pcm length: {} samples ({:.1}s)",
-                buffered_pcm.len(),
-                buffered_pcm.len() as f32 / 16000.0
-            );
-        }
-
-        // 首次启动时，等待3秒数据
-        if !first_inference_done {
-            if buffered_pcm.len() < 3 * 16000 {
-                continue;
+            Model::Quantized(m::quantized_model::Whisper::load(&vb, config.clone())?)
+        } else {
+            let vb =
+                unsafe { VarBuilder::from_mmaped_safetensors(&[model_path], m::DTYPE, &device)? };
+            if word_timestamps {
+                word_aligner = Some(alignment::WordAligner::load(vb.clone(), &config)?);
             }
-            first_inference_done = true;
+            Model::Normal(m::model::Whisper::load(&vb, config.clone())?)
+        };
+        let seed = 299792458;
+        let mut decoder = model::Decoder::new(
+            model,
+            tokenizer.clone(),
+            seed,
+            &device,
+            /* language_token */ None,
+            Some(task),
+            params.with_timestamps.unwrap_or(false) || word_timestamps,
+            params.verbose.unwrap_or(false),
+        )?;
+        decoder.set_word_timestamps(word_timestamps, word_aligner);
+        if let Some(beam_size) = params.whisper_beam_size {
+            decoder.set_beam_search(Some(BeamSearch::new(
+                beam_size,
+                params.whisper_beam_patience,
+                params.whisper_length_penalty,
+            )));
         }
 
-        // 检查距离上次推理的时间是否小于设定间隔
-        let now = Instant::now();
-        if now.duration_since(last_inference_time) < inference_interval {
-            continue;
-        }
+        let prompt = WhisperPrompt::new(
+            tokenizer.clone(),
+            params.whisper_initial_prompt.as_deref(),
+            params.whisper_previous_text_tokens.unwrap_or(0),
+            config.max_target_positions / 2 - 1,
+        )?;
 
-        // 如果最后有效推理间隔大于设定间隔的两倍，则清空历史音频 （考虑到自然停顿等）
-        // if now.duration_since(last_vad_passed_time) > inference_interval * 2 {
-        //     println!("Clearing buffered_pcm due to long silence");
-        //     history_pcm.clear();
-        // }
+        let mel_bytes = get_mel_bytes(config.num_mel_bins)?;
+        let mut mel_filters = vec![0f32; mel_bytes.len() / 4];
+        <byteorder::LittleEndian as byteorder::ByteOrder>::read_f32_into(
+            &mel_bytes,
+            &mut mel_filters,
+        );
+
+        Ok(Self {
+            decoder,
+            tokenizer,
+            config,
+            mel_filters,
+            device,
+            is_multilingual,
+            language_token_set: false,
+            language_token_name: None,
+            inference_timeout: params.inference_timeout,
+            max_tokens_per_segment: params.max_tokens_per_segment,
+            temperature: params.whisper_temperature,
+            prompt,
+            language_tracker,
+        })
+    }
 
-        // 记录推理开始时间
-        let inference_start = Instant::now();
+    fn pcm_to_mel(&self, pcm: &[f32]) -> anyhow::Result<Tensor> {
+        let mel = audio::pcm_to_mel(&self.config, pcm, &self.mel_filters);
+        let mel_len = mel.len();
+        let num_mel_bins = self.config.num_mel_bins;
+        Ok(Tensor::from_vec(
+            mel,
+            (1, num_mel_bins, mel_len / num_mel_bins),
+            &self.device,
+        )?)
+    }
 
-        if let Some(vad_model) = vad_model.as_mut() {
-            let resampled_pcm = buffered_pcm.clone();
-            let vad_result: Result<onnx_models::vad::VadResult, anyhow::Error> =
-                vad_model.check_vad(resampled_pcm, vad_filters_value);
-            if vad_result.is_err() {
-                println!("VAD error: {:?}", vad_result.err().unwrap());
-            } else {
-                let vad_result = vad_result?;
-                println!(
-                    "VAD prediction: {:?} filtered_count: {:?}",
-                    vad_result.prediction, vad_result.filtered_count
-                );
-                if vad_result.prediction > vad_filters_value.unwrap_or(0.1) {
-                    buffered_pcm = vad_result.pcm_results;
-                    // last_vad_passed_time = Instant::now();
-                } else {
-                    buffered_pcm.clear();
-                    last_inference_time = Instant::now();
-                    continue;
+    fn ensure_language_token(
+        &mut self,
+        mel: &Tensor,
+        language: Option<&str>,
+    ) -> anyhow::Result<()> {
+        if self.language_token_set {
+            return Ok(());
+        }
+        let language_token = match (self.is_multilingual, language) {
+            (true, None) => Some(multilingual::detect_language(
+                self.decoder.model(),
+                &self.tokenizer,
+                mel,
+            )?),
+            (false, None) => None,
+            (true, Some(language)) => {
+                match model::token_id(&self.tokenizer, &format!("<|{language}|>")) {
+                    Ok(token_id) => Some(token_id),
+                    Err(_) => anyhow::bail!("language {language} is not supported"),
                 }
             }
-        }
-
-        // 计算最大样本数（使用16000采样率）
-        let max_samples = max_audio_duration * 16000;
-
-        // 计算总长度
-        let total_len = history_pcm.len() + buffered_pcm.len();
+            (false, Some(_)) => {
+                anyhow::bail!("a language cannot be set for non-multilingual models")
+            }
+        };
+        self.decoder.set_language_token(language_token);
+        self.language_token_set = true;
+        self.language_token_name = match language_token {
+            Some(token) => model::get_token_name_by_id(&self.tokenizer, token),
+            None => None,
+        };
+        eprintln!(
+            "language_token: {:?} language_name: {:?}",
+            language_token, self.language_token_name
+        );
+        Ok(())
+    }
 
-        // 如果总长度超过最大样本限制，调整history_pcm
-        let mut adjusted_history_pcm = history_pcm.clone();
-        if total_len > max_samples {
-            // 计算需要从history_pcm中减去的长度
-            let excess = total_len - max_samples;
-            println!(
-                "history_pcm len: {} buffered_pcm len: {} excess: {}",
-                history_pcm.len(),
-                buffered_pcm.len(),
-                excess
-            );
-            // 如果history_pcm长度大于excess，保留后面部分
-            if history_pcm.len() > excess {
-                adjusted_history_pcm = history_pcm[excess..].to_vec();
-            } else {
-                // 如果history_pcm不够减，则不使用history_pcm
-                adjusted_history_pcm = Vec::new();
+    /// Detect the language of this window and follow it through the tracker, returns the
+    /// distribution reported in the segments
+    fn redetect_language(&mut self, mel: &Tensor) -> anyhow::Result<Vec<LanguageProbability>> {
+        let probs =
+            multilingual::detect_language_probs(self.decoder.model(), &self.tokenizer, mel)?;
+        let language = match self.language_tracker.as_mut() {
+            Some(tracker) => tracker.update(&probs).map(|s| s.to_string()),
+            None => return Ok(vec![]),
+        };
+        if let Some(language) = language {
+            if self.language_token_name.as_deref() != Some(language.as_str()) {
+                let token = model::token_id(&self.tokenizer, &format!("<|{language}|>"))?;
+                self.decoder.set_language_token(Some(token));
+                self.language_token_name = Some(language);
             }
         }
+        Ok(probs)
+    }
+}
 
-        // 合并调整后的历史数据和新数据进行处理
-        let mut combined_pcm = Vec::with_capacity(adjusted_history_pcm.len() + buffered_pcm.len());
-        combined_pcm.extend_from_slice(&adjusted_history_pcm);
-        combined_pcm.extend_from_slice(&buffered_pcm);
-
-        // 更新历史数据为当前的组合数据
-        history_pcm = combined_pcm.clone();
-
-        // 清理缓冲区
-        buffered_pcm.clear();
-
-        let pcm = combined_pcm;
-
-        let mel = audio::pcm_to_mel(&config, &pcm, &mel_filters);
-        let mel_len = mel.len();
-        let mel = candle_core::Tensor::from_vec(
-            mel,
-            (1, config.num_mel_bins, mel_len / config.num_mel_bins),
-            &device,
-        )?;
+impl SpeechRecognizer for WhisperRecognizer {
+    fn name(&self) -> &str {
+        "Whisper"
+    }
 
-        if !language_token_set {
-            let language_token = match (arg_is_multilingual, arg_language.clone()) {
-                (true, None) => Some(multilingual::detect_language(
-                    decoder.model(),
-                    &tokenizer,
-                    &mel,
-                )?),
-                (false, None) => None,
-                (true, Some(language)) => {
-                    match model::token_id(&tokenizer, &format!("<|{language}|>")) {
-                        Ok(token_id) => Some(token_id),
-                        Err(_) => anyhow::bail!("language {language} is not supported"),
-                    }
-                }
-                (false, Some(_)) => {
-                    anyhow::bail!("a language cannot be set for non-multilingual models")
-                }
-            };
-            decoder.set_language_token(language_token);
-            language_token_set = true;
-            language_token_name = match language_token {
-                Some(token) => model::get_token_name_by_id(&tokenizer, token),
-                None => None,
-            };
-            println!(
-                "language_token: {:?} language_name: {:?}",
-                language_token, language_token_name
-            );
+    fn device(&self) -> String {
+        match self.device {
+            Device::Cpu => "cpu",
+            Device::Cuda(_) => "cuda",
+            Device::Metal(_) => "metal",
         }
+        .to_string()
+    }
+
+    fn transcribe(&mut self, pcm: &[f32], language: Option<&str>) -> anyhow::Result<Vec<Segment>> {
+        let mel = self.pcm_to_mel(pcm)?;
+        let language_probs = if self.language_tracker.is_some() && language.is_none() {
+            self.redetect_language(&mel)?
+        } else {
+            self.ensure_language_token(&mel, language)?;
+            vec![]
+        };
+        self.decoder.set_prompt_tokens(self.prompt.tokens());
 
         // 运行解码器并获取结果
-        let mut segments = decoder.run(
+        let segments = self.decoder.run(
             &mel,
             None,
-            inference_timeout.or(Some(inference_interval)),
-            max_tokens_per_segment,
-            fixed_temperature,
-        )?;
-        // 计算推理用时并输出
-        let inference_duration = inference_start.elapsed();
-
-        let audio_duration = (pcm.len() as f32 / 16000.0 * 1000.0) as u128;
-
+            self.inference_timeout,
+            self.max_tokens_per_segment,
+            self.temperature,
+        );
+        self.decoder.reset_kv_cache();
+        let mut segments = segments?;
         for segment in &mut segments {
-            segment.reasoning_duration = Some(inference_duration.as_millis());
-            segment.reasoning_lang = language_token_name.clone();
-            segment.audio_duration = Some(audio_duration);
+            segment.reasoning_lang = self.language_token_name.clone();
+            segment.language_probs = language_probs.clone();
         }
-
-        // 发送结果并更新推理时间
-        result_callback(segments);
-        decoder.reset_kv_cache();
-        last_inference_time = now;
+        Ok(segments)
     }
 
-    println!("Transcription cancelled");
-    result_callback(_make_status_response(model::WhisperStatus::Exit));
+    fn reset(&mut self) {
+        self.language_token_set = false;
+        self.language_token_name = None;
+        self.prompt.reset();
+        if let Some(tracker) = self.language_tracker.as_mut() {
+            tracker.reset();
+        }
+    }
 
-    println!("Whisper Exit");
-    Ok(())
-}
+    fn set_prompt(&mut self, prompt: &str) -> anyhow::Result<()> {
+        self.prompt.set_prompt(prompt)
+    }
 
-fn _make_status_response(status: model::WhisperStatus) -> Vec<Segment> {
-    vec![Segment {
-        start: 0.0,
-        duration: 0.0,
-        dr: model::DecodingResult {
-            tokens: vec![],
-            text: "".to_string(),
-            avg_logprob: 0.0,
-            no_speech_prob: 0.0,
-            temperature: 0.0,
-            compression_ratio: 0.0,
-        },
-        reasoning_duration: None,
-        reasoning_lang: None,
-        audio_duration: None,
-        status,
-    }]
+    fn push_context(&mut self, text: &str) -> anyhow::Result<()> {
+        self.prompt.push_context(text)
+    }
 }
 
 pub fn get_mel_bytes(num_mel_bins: usize) -> anyhow::Result<Vec<u8>> {
@@ -1,11 +1,19 @@
 use candle_core::{Device, IndexOp, Tensor};
-use candle_nn::ops::softmax;
+use candle_nn::ops::{log_softmax, softmax};
 use candle_transformers::models::whisper::{self as m, Config};
 use rand::SeedableRng;
 use rand_distr::Distribution;
+use serde::Serialize;
 use std::time::{Duration, Instant};
 use tokenizers::Tokenizer;
 
+use super::alignment::{self, WordAligner};
+use super::beam_search::{BeamSearch, Beams};
+
+/// 未指定每段 token 上限时的默认值
+const DEFAULT_MAX_DECODE_TOKENS: usize = 256;
+const SOT_PREV_TOKEN: &str = "<|startofprev|>";
+
 pub enum Model {
     Normal(m::model::Whisper),
     Quantized(m::quantized_model::Whisper),
@@ -47,13 +55,13 @@ impl Model {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Task {
     Transcribe,
     Translate,
 }
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Segment {
     pub start: f64,
     pub duration: f64,
@@ -62,11 +70,72 @@ pub struct Segment {
     pub reasoning_lang: Option<String>,
     pub audio_duration: Option<u128>,
     pub status: WhisperStatus,
+    /// Timestamped parts of the window, empty unless timestamps are enabled
+    pub sub_segments: Vec<SubSegment>,
+    /// Monotonic id assigned by the live caption loop (0 for status / offline segments)
+    pub id: u64,
+    /// Stable text that will not be revised any more (see `caption::agreement`)
+    pub committed: bool,
+    /// Language distribution of the window, most likely first, empty unless the language is
+    /// detected for every window
+    pub language_probs: Vec<LanguageProbability>,
+    /// Speaker emotion such as "happy" (SenseVoice only)
+    pub emotion: Option<String>,
+    /// Audio event such as "speech", "bgm" or "applause" (SenseVoice only)
+    pub audio_event: Option<String>,
+}
+
+impl Segment {
+    /// Move the segment (and its sub-segments / words) by `offset` seconds
+    pub fn shift(&mut self, offset: f64) {
+        self.start += offset;
+        for sub_segment in &mut self.sub_segments {
+            sub_segment.start += offset;
+            sub_segment.end += offset;
+            for word in &mut sub_segment.words {
+                word.start += offset;
+                word.end += offset;
+            }
+        }
+    }
+
+    /// Take the language and audio tags of the window this segment was cut from
+    pub fn copy_window_tags(&mut self, window: &Segment) {
+        self.reasoning_lang = window.reasoning_lang.clone();
+        self.language_probs = window.language_probs.clone();
+        self.emotion = window.emotion.clone();
+        self.audio_event = window.audio_event.clone();
+    }
+}
+
+/// Part of a [`Segment`] delimited by Whisper timestamp tokens, times in seconds
+#[derive(Debug, Clone, Serialize)]
+pub struct SubSegment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// Empty unless word timestamps are enabled
+    pub words: Vec<WordTiming>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordTiming {
+    pub start: f64,
+    pub end: f64,
+    pub word: String,
+}
+
+/// Probability of a spoken language, code such as "en"
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageProbability {
+    pub language: String,
+    pub probability: f32,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DecodingResult {
+    #[serde(skip)]
     pub tokens: Vec<u32>,
     pub text: String,
     pub avg_logprob: f64,
@@ -75,7 +144,8 @@ pub struct DecodingResult {
     pub compression_ratio: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WhisperStatus {
     Loading,
     Ready,
@@ -87,18 +157,25 @@ pub enum WhisperStatus {
 pub struct Decoder {
     model: Model,
     rng: rand::rngs::StdRng,
-    task: Option<Task>,
     timestamps: bool,
     verbose: bool,
     tokenizer: Tokenizer,
     suppress_tokens: Tensor,
     sot_token: u32,
-    transcribe_token: u32,
-    translate_token: u32,
+    task_token: u32,
     eot_token: u32,
     no_speech_token: u32,
     no_timestamps_token: u32,
     language_token: Option<u32>,
+    word_timestamps: bool,
+    /// DTW alignment of word times, falls back to interpolation inside sub-segments when None
+    word_aligner: Option<WordAligner>,
+    /// Beam search at temperature 0, greedy decoding when None
+    beam_search: Option<BeamSearch>,
+    /// `<|startofprev|>`, None if the vocabulary has no such token
+    sot_prev_token: Option<u32>,
+    /// Context fed before the sot sequence, see `whisper::prompt`
+    prompt_tokens: Vec<u32>,
 }
 
 impl Decoder {
@@ -129,9 +206,9 @@ impl Decoder {
             .collect();
         let suppress_tokens = Tensor::new(suppress_tokens.as_slice(), device)?;
         let sot_token = token_id(&tokenizer, m::SOT_TOKEN)?;
-        let transcribe_token = token_id(&tokenizer, m::TRANSCRIBE_TOKEN)?;
-        let translate_token = token_id(&tokenizer, m::TRANSLATE_TOKEN)?;
+        let task_token = task_token(&tokenizer, task)?;
         let eot_token = token_id(&tokenizer, m::EOT_TOKEN)?;
+        let sot_prev_token = token_id(&tokenizer, SOT_PREV_TOKEN).ok();
         let no_speech_token = m::NO_SPEECH_TOKENS
             .iter()
             .find_map(|token| token_id(&tokenizer, token).ok());
@@ -143,61 +220,99 @@ impl Decoder {
             model,
             rng: rand::rngs::StdRng::seed_from_u64(seed),
             tokenizer,
-            task,
             timestamps,
             verbose,
             suppress_tokens,
             sot_token,
-            transcribe_token,
-            translate_token,
+            task_token,
             eot_token,
             no_speech_token,
             language_token,
             no_timestamps_token,
+            word_timestamps: false,
+            word_aligner: None,
+            beam_search: None,
+            sot_prev_token,
+            prompt_tokens: vec![],
         })
     }
 
+    /// Enable per-word timings, requires the decoder to run in timestamps mode
+    pub(crate) fn set_word_timestamps(&mut self, enabled: bool, aligner: Option<WordAligner>) {
+        self.word_timestamps = enabled;
+        self.word_aligner = aligner;
+    }
+
+    /// Use beam search instead of greedy decoding at temperature 0
+    pub(crate) fn set_beam_search(&mut self, beam_search: Option<BeamSearch>) {
+        self.beam_search = beam_search.filter(|beam| beam.beam_size > 1);
+    }
+
+    /// Prompt tokens (without `<|startofprev|>`) for the next windows, empty disables it
+    pub(crate) fn set_prompt_tokens(&mut self, tokens: Vec<u32>) {
+        self.prompt_tokens = tokens;
+    }
+
+    fn sot_sequence(&self) -> Vec<u32> {
+        sot_sequence(self.sot_token, self.language_token, self.task_token)
+    }
+
+    /// Decoder input before the first sampled token: `<|startofprev|>` + prompt (when set),
+    /// the sot sequence, `<|notimestamps|>` outside timestamps mode.
+    /// Returns the tokens and the length of the prompt part.
+    fn prefix_tokens(&self) -> (Vec<u32>, usize) {
+        let mut tokens = vec![];
+        if let Some(sot_prev_token) = self.sot_prev_token {
+            if !self.prompt_tokens.is_empty() {
+                let max_prompt_len = self.model.config().max_target_positions / 2 - 1;
+                let from = self.prompt_tokens.len().saturating_sub(max_prompt_len);
+                tokens.push(sot_prev_token);
+                tokens.extend_from_slice(&self.prompt_tokens[from..]);
+            }
+        }
+        let prompt_len = tokens.len();
+        tokens.extend(self.sot_sequence());
+        if !self.timestamps {
+            tokens.push(self.no_timestamps_token);
+        }
+        (tokens, prompt_len)
+    }
+
+    fn timestamp_to_secs(&self, token: u32) -> f64 {
+        // <|0.00|> 紧跟在 <|notimestamps|> 之后，每个时间戳 token 间隔 20ms
+        (token - self.no_timestamps_token - 1) as f64 / 50.
+    }
+
     fn decode(
         &mut self,
-        mel: &Tensor,
+        audio_features: &Tensor,
         t: f64,
         timeout: Option<Duration>,
         max_tokens: Option<usize>,
     ) -> anyhow::Result<DecodingResult> {
+        if t == 0f64 {
+            if let Some(beam_search) = self.beam_search {
+                return self.decode_beam(audio_features, beam_search, timeout, max_tokens);
+            }
+        }
         let start_time = Instant::now();
+        let (mut tokens, prompt_len) = self.prefix_tokens();
+        let sample_begin = tokens.len();
         let model = &mut self.model;
-        let audio_features = model.encoder_forward(mel, true)?;
-        if self.verbose {
-            println!("audio features: {:?}", audio_features.dims());
-        }
 
-        let sample_len = match max_tokens {
-            Some(max) => max.min(model.config().max_target_positions / 2),
-            None => model.config().max_target_positions / 2,
-        };
+        let max_target_positions = model.config().max_target_positions;
+        let sample_len = sample_len(max_tokens, max_target_positions, sample_begin);
 
         let mut sum_logprob = 0f64;
         let mut no_speech_prob = f64::NAN;
-        let mut tokens = vec![self.sot_token];
-        if let Some(language_token) = self.language_token {
-            tokens.push(language_token);
-        }
-        match self.task {
-            None | Some(Task::Transcribe) => tokens.push(self.transcribe_token),
-            Some(Task::Translate) => tokens.push(self.translate_token),
-        }
-        if !self.timestamps {
-            tokens.push(self.no_timestamps_token);
-        }
 
-        let default_max_decode_tokens = 256; // 设置一个默认的token数量上限
-        let max_tokens = max_tokens.unwrap_or(default_max_decode_tokens);
+        let max_tokens = max_tokens.unwrap_or(DEFAULT_MAX_DECODE_TOKENS);
 
         for i in 0..sample_len {
             // 检查是否超时
             if let Some(timeout_duration) = timeout {
                 if start_time.elapsed() >= timeout_duration {
-                    println!(
+                    eprintln!(
                         "Decode timed out after {:?}, returning partial result",
                         start_time.elapsed()
                     );
@@ -206,25 +321,28 @@ impl Decoder {
             }
 
             // 检查是否达到token数量上限
-            if tokens.len() >= max_tokens {
-                println!(
+            if tokens.len() - prompt_len >= max_tokens {
+                eprintln!(
                     "Reached maximum token limit ({} tokens), stopping decoding",
                     max_tokens
                 );
                 break;
             }
 
-            let tokens_t = Tensor::new(tokens.as_slice(), mel.device())?;
+            let tokens_t = Tensor::new(tokens.as_slice(), audio_features.device())?;
 
             // The model expects a batch dim but this inference loop does not handle
             // it so we add it at this point.
             let tokens_t = tokens_t.unsqueeze(0)?;
-            let ys = model.decoder_forward(&tokens_t, &audio_features, i == 0)?;
+            let ys = model.decoder_forward(&tokens_t, audio_features, i == 0)?;
 
             // Extract the no speech probability on the first iteration by looking at the first
             // token logits and the probability for the according token.
             if i == 0 {
-                let logits = model.decoder_final_linear(&ys.i(..1)?)?.i(0)?.i(0)?;
+                let logits = model
+                    .decoder_final_linear(&ys.i(..1)?)?
+                    .i(0)?
+                    .i(prompt_len)?;
                 no_speech_prob = softmax(&logits, 0)?
                     .i(self.no_speech_token as usize)?
                     .to_scalar::<f32>()? as f64;
@@ -236,14 +354,17 @@ impl Decoder {
                 .i(0)?
                 .i(0)?;
 
-            // TODO: Besides suppress tokens, we should apply the heuristics from
-            // ApplyTimestampRules, i.e.:
-            // - Timestamps come in pairs, except before EOT.
-            // - Timestamps should be non-decreasing.
-            // - If the sum of the probabilities of timestamps is higher than any other tokens,
-            //   only consider timestamps when sampling.
-            // https://github.com/openai/whisper/blob/e8622f9afc4eba139bf796c210f5c01081000472/whisper/decoding.py#L439
-            let logits = logits.broadcast_add(&self.suppress_tokens)?;
+            let mut logits = logits.broadcast_add(&self.suppress_tokens)?;
+            if self.timestamps {
+                let mut logits_v: Vec<f32> = logits.to_vec1()?;
+                apply_timestamp_rules(
+                    &mut logits_v,
+                    &tokens[sample_begin..],
+                    self.eot_token,
+                    self.no_timestamps_token + 1,
+                );
+                logits = Tensor::new(logits_v, logits.device())?;
+            }
             let next_token = if t > 0f64 {
                 let prs = softmax(&(&logits / t)?, 0)?;
                 let logits_v: Vec<f32> = prs.to_vec1()?;
@@ -268,6 +389,8 @@ impl Decoder {
             sum_logprob += prob.ln();
         }
 
+        // 提示部分不属于本段的结果
+        tokens.drain(..prompt_len);
         let text = self
             .tokenizer
             .decode(&tokens, true)
@@ -284,9 +407,110 @@ impl Decoder {
         })
     }
 
+    /// Beam search decoding, all beams run through the model as one batch
+    fn decode_beam(
+        &mut self,
+        audio_features: &Tensor,
+        beam_search: BeamSearch,
+        timeout: Option<Duration>,
+        max_tokens: Option<usize>,
+    ) -> anyhow::Result<DecodingResult> {
+        let start_time = Instant::now();
+        let (prefix, prompt_len) = self.prefix_tokens();
+        let sample_begin = prefix.len();
+        let mut beams = Beams::new(beam_search, prefix, self.eot_token);
+        let model = &mut self.model;
+
+        let max_target_positions = model.config().max_target_positions;
+        let sample_len = sample_len(max_tokens, max_target_positions, sample_begin);
+        let max_tokens = max_tokens.unwrap_or(DEFAULT_MAX_DECODE_TOKENS);
+        // 每个 beam 一份 encoder 输出，cross attention 的缓存按批大小建立
+        let audio_features = audio_features.repeat((beam_search.beam_size, 1, 1))?;
+        let mut no_speech_prob = f64::NAN;
+
+        for i in 0..sample_len {
+            if let Some(timeout_duration) = timeout {
+                if start_time.elapsed() >= timeout_duration {
+                    eprintln!(
+                        "Beam search timed out after {:?}, returning partial result",
+                        start_time.elapsed()
+                    );
+                    break;
+                }
+            }
+            if beams.seq_len() - prompt_len >= max_tokens {
+                eprintln!(
+                    "Reached maximum token limit ({} tokens), stopping decoding",
+                    max_tokens
+                );
+                break;
+            }
+
+            let seq_len = beams.seq_len();
+            let tokens_t = Tensor::from_vec(
+                beams.tokens(),
+                (beam_search.beam_size, seq_len),
+                audio_features.device(),
+            )?;
+            let ys = model.decoder_forward(&tokens_t, &audio_features, i == 0)?;
+            if i == 0 {
+                let logits = model
+                    .decoder_final_linear(&ys.i(..1)?)?
+                    .i(0)?
+                    .i(prompt_len)?;
+                no_speech_prob = softmax(&logits, 0)?
+                    .i(self.no_speech_token as usize)?
+                    .to_scalar::<f32>()? as f64;
+            }
+
+            let mut logits = model
+                .decoder_final_linear(&ys.i((.., seq_len - 1..))?)?
+                .squeeze(1)?
+                .broadcast_add(&self.suppress_tokens)?;
+            if self.timestamps {
+                // 与贪心解码相同的时间戳规则，按各 beam 已生成的 token 分别处理
+                let (beam_size, vocab_size) = logits.dims2()?;
+                let mut logits_v: Vec<f32> = logits.flatten_all()?.to_vec1()?;
+                let tokens = beams.tokens();
+                for (row, beam_tokens) in
+                    logits_v.chunks_mut(vocab_size).zip(tokens.chunks(seq_len))
+                {
+                    apply_timestamp_rules(
+                        row,
+                        &beam_tokens[sample_begin..],
+                        self.eot_token,
+                        self.no_timestamps_token + 1,
+                    );
+                }
+                logits = Tensor::from_vec(logits_v, (beam_size, vocab_size), logits.device())?;
+            }
+            let logprobs: Vec<Vec<f32>> =
+                log_softmax(&logits, candle_core::D::Minus1)?.to_vec2()?;
+            beams.update(&logprobs);
+            if beams.is_done() || beams.seq_len() > max_target_positions {
+                break;
+            }
+        }
+
+        let (mut tokens, avg_logprob) = beams.best();
+        tokens.drain(..prompt_len);
+        let text = self
+            .tokenizer
+            .decode(&tokens, true)
+            .map_err(anyhow::Error::msg)?;
+        Ok(DecodingResult {
+            tokens,
+            text,
+            avg_logprob,
+            no_speech_prob,
+            temperature: 0f64,
+            compression_ratio: f64::NAN,
+        })
+    }
+
     fn decode_with_fallback(
         &mut self,
-        segment: &Tensor,
+        audio_features: &Tensor,
         timeout: Option<Duration>,
         max_tokens: Option<usize>,
     ) -> anyhow::Result<DecodingResult> {
@@ -296,7 +520,7 @@ impl Decoder {
             // 检查是否超时
             if let Some(timeout_duration) = timeout {
                 if start_time.elapsed() >= timeout_duration {
-                    println!(
+                    eprintln!(
                         "decode_with_fallback timed out after {:?}, returning error",
                         start_time.elapsed()
                     );
@@ -304,7 +528,8 @@ impl Decoder {
                 }
             }
 
-            let dr: anyhow::Result<DecodingResult> = self.decode(segment, t, timeout, max_tokens);
+            let dr: anyhow::Result<DecodingResult> =
+                self.decode(audio_features, t, timeout, max_tokens);
             if i == m::TEMPERATURES.len() - 1 {
                 return dr;
             }
@@ -318,7 +543,7 @@ impl Decoder {
                     }
                 }
                 Err(err) => {
-                    println!("Error running at {t}: {err}")
+                    eprintln!("Error running at {t}: {err}")
                 }
             }
         }
@@ -342,7 +567,7 @@ impl Decoder {
             // 检查是否超时
             if let Some(timeout_duration) = timeout {
                 if start_time.elapsed() >= timeout_duration {
-                    println!(
+                    eprintln!(
                         "Decoder run timed out after {:?}, returning partial results",
                         start_time.elapsed()
                     );
@@ -355,6 +580,11 @@ impl Decoder {
             let segment_size = usize::min(content_frames - seek, m::N_FRAMES);
             let mel_segment = mel.narrow(2, seek, segment_size)?;
             let segment_duration = (segment_size * m::HOP_LENGTH) as f64 / m::SAMPLE_RATE as f64;
+            // 温度回退时复用同一份 encoder 输出，词级对齐也需要它
+            let audio_features = self.model.encoder_forward(&mel_segment, true)?;
+            if self.verbose {
+                eprintln!("audio features: {:?}", audio_features.dims());
+            }
 
             // 计算本段的超时限制
             let segment_timeout = timeout.map(|t| {
@@ -369,7 +599,12 @@ impl Decoder {
             // 使用固定温度或回退机制
             let dr = if let Some(t) = temperature {
                 // 使用固定温度
-                match self.decode(&mel_segment, t as f64, segment_timeout, max_tokens_per_segment) {
+                match self.decode(
+                    &audio_features,
+                    t as f64,
+                    segment_timeout,
+                    max_tokens_per_segment,
+                ) {
                     Ok(dr) => dr,
                     Err(e) => {
                         if timeout.is_some() && e.to_string().contains("timed out") {
@@ -381,7 +616,7 @@ impl Decoder {
             } else {
                 // 使用原始的温度回退机制
                 match self.decode_with_fallback(
-                    &mel_segment,
+                    &audio_features,
                     segment_timeout,
                     max_tokens_per_segment,
                 ) {
@@ -397,10 +632,10 @@ impl Decoder {
 
             seek += segment_size;
             if dr.no_speech_prob > m::NO_SPEECH_THRESHOLD && dr.avg_logprob < m::LOGPROB_THRESHOLD {
-                println!("no speech detected, skipping {seek} {dr:?}");
+                eprintln!("no speech detected, skipping {seek} {dr:?}");
                 continue;
             }
-            let segment = Segment {
+            let mut segment = Segment {
                 start: time_offset,
                 duration: segment_duration,
                 dr,
@@ -408,52 +643,38 @@ impl Decoder {
                 reasoning_lang: None,
                 audio_duration: None,
                 status: WhisperStatus::Working,
+                sub_segments: vec![],
+                id: 0,
+                committed: false,
+                language_probs: vec![],
+                emotion: None,
+                audio_event: None,
             };
             if self.timestamps {
-                println!(
+                eprintln!(
                     "{:.1}s -- {:.1}s",
                     segment.start,
                     segment.start + segment.duration,
                 );
-                let mut tokens_to_decode = vec![];
-                let mut prev_timestamp_s = 0f32;
-                for &token in segment.dr.tokens.iter() {
-                    if token == self.sot_token || token == self.eot_token {
-                        continue;
-                    }
-                    // The no_timestamp_token is the last before the timestamp ones.
-                    if token > self.no_timestamps_token {
-                        let timestamp_s = (token - self.no_timestamps_token + 1) as f32 / 50.;
-                        if !tokens_to_decode.is_empty() {
-                            let text = self
-                                .tokenizer
-                                .decode(&tokens_to_decode, true)
-                                .map_err(anyhow::Error::msg)?;
-                            println!("  {:.1}s-{:.1}s: {}", prev_timestamp_s, timestamp_s, text);
-                            tokens_to_decode.clear()
-                        }
-                        prev_timestamp_s = timestamp_s;
-                    } else {
-                        tokens_to_decode.push(token)
-                    }
-                }
-                if !tokens_to_decode.is_empty() {
-                    let text = self
-                        .tokenizer
-                        .decode(&tokens_to_decode, true)
-                        .map_err(anyhow::Error::msg)?;
-                    if !text.is_empty() {
-                        println!("  {:.1}s-...: {}", prev_timestamp_s, text);
-                    }
-                    tokens_to_decode.clear()
+                segment.sub_segments = self.sub_segments(
+                    &segment.dr.tokens,
+                    &audio_features,
+                    time_offset,
+                    segment_duration,
+                )?;
+                for sub_segment in &segment.sub_segments {
+                    eprintln!(
+                        "  {:.1}s-{:.1}s: {}",
+                        sub_segment.start, sub_segment.end, sub_segment.text
+                    );
                 }
             } else {
                 match times {
                     Some((start, end)) => {
-                        println!("{:.1}s -- {:.1}s: {}", start, end, segment.dr.text)
+                        eprintln!("{:.1}s -- {:.1}s: {}", start, end, segment.dr.text)
                     }
                     None => {
-                        println!(
+                        eprintln!(
                             "{:.1}s -- {:.1}s: {}",
                             segment.start,
                             segment.start + segment.duration,
@@ -463,13 +684,120 @@ impl Decoder {
                 }
             }
             if self.verbose {
-                println!("{seek}: {segment:?}, in {:?}", segment_start.elapsed());
+                eprintln!("{seek}: {segment:?}, in {:?}", segment_start.elapsed());
             }
             segments.push(segment)
         }
         Ok(segments)
     }
 
+    /// Split the decoded tokens of one window on timestamp tokens, times are offset by `time_offset`.
+    /// Text after the last timestamp token ends at `window_duration`.
+    fn sub_segments(
+        &self,
+        tokens: &[u32],
+        audio_features: &Tensor,
+        time_offset: f64,
+        window_duration: f64,
+    ) -> anyhow::Result<Vec<SubSegment>> {
+        // (start, end, text tokens)
+        let mut ranges: Vec<(f64, f64, Vec<u32>)> = vec![];
+        let mut text_tokens = vec![];
+        let mut prev_timestamp = 0f64;
+        for &token in tokens {
+            if token == self.sot_token || token == self.eot_token {
+                continue;
+            }
+            // The no_timestamp_token is the last before the timestamp ones.
+            if token > self.no_timestamps_token {
+                let timestamp = self.timestamp_to_secs(token);
+                if !text_tokens.is_empty() {
+                    ranges.push((prev_timestamp, timestamp, std::mem::take(&mut text_tokens)));
+                }
+                prev_timestamp = timestamp;
+            } else if token < self.eot_token {
+                text_tokens.push(token)
+            }
+        }
+        if !text_tokens.is_empty() {
+            ranges.push((
+                prev_timestamp,
+                window_duration.max(prev_timestamp),
+                text_tokens,
+            ));
+        }
+
+        let mut sub_segments = ranges
+            .iter()
+            .map(|(start, end, tokens)| {
+                Ok(SubSegment {
+                    start: time_offset + start,
+                    end: time_offset + end,
+                    text: self
+                        .tokenizer
+                        .decode(tokens, true)
+                        .map_err(anyhow::Error::msg)?,
+                    words: vec![],
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if !self.word_timestamps || sub_segments.is_empty() {
+            return Ok(sub_segments);
+        }
+
+        let language = self
+            .language_token
+            .and_then(|token| get_token_name_by_id(&self.tokenizer, token));
+        let all_text_tokens: Vec<u32> = ranges.iter().flat_map(|r| r.2.clone()).collect();
+        let jump_times = match &self.word_aligner {
+            Some(aligner) => match aligner.align(
+                audio_features,
+                &self.sot_sequence(),
+                self.no_timestamps_token,
+                &all_text_tokens,
+                self.eot_token,
+            ) {
+                Ok(times) if times.len() == all_text_tokens.len() + 1 => Some(times),
+                Ok(_) => None,
+                Err(e) => {
+                    eprintln!("word alignment failed, fallback to interpolation: {e}");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut token_index = 0;
+        for (sub_segment, (_, _, tokens)) in sub_segments.iter_mut().zip(ranges.iter()) {
+            let words = alignment::split_words(&self.tokenizer, tokens, language.as_deref())?;
+            match &jump_times {
+                Some(jump_times) => {
+                    for (word, count) in words {
+                        sub_segment.words.push(WordTiming {
+                            start: time_offset + jump_times[token_index],
+                            end: time_offset + jump_times[token_index + count],
+                            word,
+                        });
+                        token_index += count;
+                    }
+                }
+                None => {
+                    // 没有对齐模型时按字符数在子段内线性插值
+                    let total_chars: usize = words.iter().map(|(w, _)| w.chars().count()).sum();
+                    let duration = sub_segment.end - sub_segment.start;
+                    let mut start = sub_segment.start;
+                    for (word, _) in words {
+                        let end = start
+                            + duration * word.chars().count() as f64 / total_chars.max(1) as f64;
+                        sub_segment.words.push(WordTiming { start, end, word });
+                        start = end;
+                    }
+                }
+            }
+        }
+        Ok(sub_segments)
+    }
+
     pub(crate) fn set_language_token(&mut self, language_token: Option<u32>) {
         self.language_token = language_token;
     }
@@ -487,6 +815,101 @@ impl Decoder {
     }
 }
 
+/// Number of decoding steps: at most half the decoder context, and never past the last
+/// position left after the `prefix_len` tokens fed before the first sampled token
+fn sample_len(max_tokens: Option<usize>, max_target_positions: usize, prefix_len: usize) -> usize {
+    max_tokens
+        .unwrap_or(max_target_positions / 2)
+        .min(max_target_positions / 2)
+        .min(max_target_positions.saturating_sub(prefix_len))
+}
+
+/// Whisper's `ApplyTimestampRules` on the logits of the next token, `sampled` are the tokens
+/// generated so far:
+/// - timestamps come in pairs, except right before EOT;
+/// - timestamps are non-decreasing;
+/// - the first token is a timestamp of at most 1 second;
+/// - when timestamps are more likely than any text token, only timestamps are kept.
+///
+/// <https://github.com/openai/whisper/blob/e8622f9afc4eba139bf796c210f5c01081000472/whisper/decoding.py#L439>
+fn apply_timestamp_rules(
+    logits: &mut [f32],
+    sampled: &[u32],
+    eot_token: u32,
+    timestamp_begin: u32,
+) {
+    let eot = eot_token as usize;
+    let begin = timestamp_begin as usize;
+    let is_timestamp = |token: &u32| *token >= timestamp_begin;
+
+    let last_was_timestamp = sampled.last().is_some_and(is_timestamp);
+    let penultimate_was_timestamp = sampled.len() < 2 || is_timestamp(&sampled[sampled.len() - 2]);
+    if last_was_timestamp {
+        if penultimate_was_timestamp {
+            // 成对的时间戳之后必须是文本
+            logits[begin..].fill(f32::NEG_INFINITY);
+        } else {
+            // 单个时间戳之后必须是时间戳或 EOT
+            logits[..eot].fill(f32::NEG_INFINITY);
+        }
+    }
+
+    if let Some(&last_timestamp) = sampled.iter().rev().find(|t| is_timestamp(t)) {
+        // 时间戳不能倒退，未成对时可以重复上一个时间戳来闭合
+        let min_timestamp = if last_was_timestamp && !penultimate_was_timestamp {
+            last_timestamp
+        } else {
+            last_timestamp + 1
+        };
+        let end = (min_timestamp as usize).min(logits.len());
+        logits[begin..end].fill(f32::NEG_INFINITY);
+    }
+
+    if sampled.is_empty() {
+        // 第一个 token 必须是不超过 1 秒的时间戳
+        const MAX_INITIAL_TIMESTAMP_INDEX: usize = 50;
+        logits[..begin].fill(f32::NEG_INFINITY);
+        let last_allowed = begin + MAX_INITIAL_TIMESTAMP_INDEX + 1;
+        if last_allowed < logits.len() {
+            logits[last_allowed..].fill(f32::NEG_INFINITY);
+        }
+    }
+
+    // 时间戳的总概率高于任一文本 token 时只保留时间戳
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max == f32::NEG_INFINITY {
+        return;
+    }
+    let log_sum = |values: &[f32]| values.iter().map(|v| (v - max).exp()).sum::<f32>().ln();
+    let timestamp_logprob = log_sum(&logits[begin..]);
+    let max_text_logprob = logits[..begin]
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max)
+        - max;
+    if timestamp_logprob > max_text_logprob {
+        logits[..begin].fill(f32::NEG_INFINITY);
+    }
+}
+
+/// `<|transcribe|>` or `<|translate|>`, None transcribes
+pub fn task_token(tokenizer: &Tokenizer, task: Option<Task>) -> candle_core::Result<u32> {
+    match task {
+        None | Some(Task::Transcribe) => token_id(tokenizer, m::TRANSCRIBE_TOKEN),
+        Some(Task::Translate) => token_id(tokenizer, m::TRANSLATE_TOKEN),
+    }
+}
+
+/// sot, language and task tokens
+fn sot_sequence(sot_token: u32, language_token: Option<u32>, task_token: u32) -> Vec<u32> {
+    let mut tokens = vec![sot_token];
+    if let Some(language_token) = language_token {
+        tokens.push(language_token);
+    }
+    tokens.push(task_token);
+    tokens
+}
+
 pub fn token_id(tokenizer: &Tokenizer, token: &str) -> candle_core::Result<u32> {
     match tokenizer.token_to_id(token) {
         None => candle_core::bail!("no token-id for {token}"),
@@ -505,3 +928,75 @@ pub(crate) fn get_token_name_by_id(p0: &Tokenizer, p1: u32) -> Option<String> {
     }
     Some(token.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sot_sequence_task_token() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/whisper/base-tokenizer.json"
+        );
+        let tokenizer = Tokenizer::from_file(path).unwrap();
+        let sot_token = token_id(&tokenizer, m::SOT_TOKEN).unwrap();
+        let fr_token = token_id(&tokenizer, "<|fr|>").unwrap();
+        // v3 之前的多语言词表：<|translate|> = 50358, <|transcribe|> = 50359
+        let translate = task_token(&tokenizer, Some(Task::Translate)).unwrap();
+        assert_eq!(
+            sot_sequence(sot_token, Some(fr_token), translate),
+            vec![50258, 50265, 50358]
+        );
+        let transcribe = task_token(&tokenizer, None).unwrap();
+        assert_eq!(
+            sot_sequence(sot_token, None, transcribe),
+            vec![50258, 50359]
+        );
+    }
+
+    #[test]
+    fn test_apply_timestamp_rules() {
+        // 词表：0..4 文本，4 = EOT，5..7 特殊 token，7.. 时间戳
+        let (eot, begin) = (4, 7);
+        let logits = || vec![1.0; 70];
+        let allowed = |logits: &[f32]| -> Vec<usize> {
+            (0..logits.len())
+                .filter(|&i| logits[i] > f32::NEG_INFINITY)
+                .collect()
+        };
+
+        // 第一个 token 只能是 1 秒以内的时间戳
+        let mut first = logits();
+        apply_timestamp_rules(&mut first, &[], eot, begin);
+        assert_eq!(allowed(&first), (7..58).collect::<Vec<_>>());
+
+        // 单个时间戳之后只能是 EOT 或不早于它的时间戳
+        let mut open = logits();
+        open[4] = 10.0;
+        apply_timestamp_rules(&mut open, &[7, 1, 10], eot, begin);
+        let mut expected = vec![4, 5, 6];
+        expected.extend(10..70);
+        assert_eq!(allowed(&open), expected);
+
+        // 成对的时间戳之后只能是文本和特殊 token
+        let mut closed = logits();
+        closed[0] = 10.0;
+        apply_timestamp_rules(&mut closed, &[7, 1, 10, 10], eot, begin);
+        assert_eq!(allowed(&closed), (0..7).collect::<Vec<_>>());
+
+        // 时间戳总概率更高时不再采样文本
+        let mut text = logits();
+        apply_timestamp_rules(&mut text, &[7, 1], eot, begin);
+        assert_eq!(allowed(&text), (8..70).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sample_len_fits_decoder_context() {
+        assert_eq!(sample_len(None, 448, 4), 224);
+        assert_eq!(sample_len(Some(100), 448, 4), 100);
+        // 上下文已被前缀用掉一半以上时，采样步数不能越过最后一个位置
+        assert_eq!(sample_len(None, 448, 228), 220);
+        assert_eq!(sample_len(Some(1000), 448, 448), 0);
+    }
+}
@@ -2,7 +2,7 @@ use candle_core::{IndexOp, Result, Tensor, D};
 use candle_transformers::models::whisper::{self as m};
 use tokenizers::Tokenizer;
 
-use crate::candle_models::whisper::model::{token_id, Model};
+use crate::candle_models::whisper::model::{token_id, LanguageProbability, Model};
 
 const LANGUAGES: [(&str, &str); 99] = [
     ("en", "english"),
@@ -106,8 +106,26 @@ const LANGUAGES: [(&str, &str); 99] = [
     ("su", "sundanese"),
 ];
 
+/// Probability a new language needs to take over from the current one
+pub const DEFAULT_LANGUAGE_SWITCH_THRESHOLD: f32 = 0.6;
+/// Consecutive windows a new language has to win before taking over
+pub const DEFAULT_LANGUAGE_SWITCH_WINDOWS: usize = 2;
+
 /// Returns the token id for the selected language.
 pub fn detect_language(model: &mut Model, tokenizer: &Tokenizer, mel: &Tensor) -> Result<u32> {
+    let probs = detect_language_probs(model, tokenizer, mel)?;
+    for p in probs.iter().take(5) {
+        eprintln!("{}: {}", p.language, p.probability)
+    }
+    token_id(tokenizer, &format!("<|{}|>", probs[0].language))
+}
+
+/// Probabilities of all languages for the first 30 seconds of `mel`, most likely first
+pub fn detect_language_probs(
+    model: &mut Model,
+    tokenizer: &Tokenizer,
+    mel: &Tensor,
+) -> Result<Vec<LanguageProbability>> {
     let (_bsize, _, seq_len) = mel.dims3()?;
     let mel = mel.narrow(
         2,
@@ -128,11 +146,98 @@ pub fn detect_language(model: &mut Model, tokenizer: &Tokenizer, mel: &Tensor) -
     let logits = logits.index_select(&language_token_ids, 0)?;
     let probs = candle_nn::ops::softmax(&logits, D::Minus1)?;
     let probs = probs.to_vec1::<f32>()?;
-    let mut probs = LANGUAGES.iter().zip(probs.iter()).collect::<Vec<_>>();
-    probs.sort_by(|(_, p1), (_, p2)| p2.total_cmp(p1));
-    for ((_, language), p) in probs.iter().take(5) {
-        println!("{language}: {p}")
+    let mut probs = LANGUAGES
+        .iter()
+        .zip(probs)
+        .map(|((language, _), probability)| LanguageProbability {
+            language: language.to_string(),
+            probability,
+        })
+        .collect::<Vec<_>>();
+    probs.sort_by(|p1, p2| p2.probability.total_cmp(&p1.probability));
+    Ok(probs)
+}
+
+/// Follows the spoken language across windows. The first detection is taken as is, another
+/// language then only takes over once it won `switch_windows` consecutive windows with at
+/// least `switch_threshold` probability, so a single uncertain window does not flip it.
+#[derive(Debug, Clone)]
+pub struct LanguageTracker {
+    switch_threshold: f32,
+    switch_windows: usize,
+    current: Option<String>,
+    // 候选语言及其连续胜出的窗口数
+    candidate: Option<(String, usize)>,
+}
+
+impl LanguageTracker {
+    pub fn new(switch_threshold: f32, switch_windows: usize) -> Self {
+        Self {
+            switch_threshold,
+            switch_windows: switch_windows.max(1),
+            current: None,
+            candidate: None,
+        }
+    }
+
+    /// Feed the distribution of a window (most likely first), returns the language to use
+    pub fn update(&mut self, probs: &[LanguageProbability]) -> Option<&str> {
+        let Some(top) = probs.first() else {
+            return self.current.as_deref();
+        };
+        match &self.current {
+            None => self.current = Some(top.language.clone()),
+            Some(current) if *current == top.language => self.candidate = None,
+            Some(_) if top.probability >= self.switch_threshold => {
+                let count = match &self.candidate {
+                    Some((language, count)) if *language == top.language => count + 1,
+                    _ => 1,
+                };
+                if count >= self.switch_windows {
+                    eprintln!(
+                        "language switched: {:?} -> {} ({:.2})",
+                        self.current, top.language, top.probability
+                    );
+                    self.current = Some(top.language.clone());
+                    self.candidate = None;
+                } else {
+                    self.candidate = Some((top.language.clone(), count));
+                }
+            }
+            // 置信度不足的窗口打断候选语言的连续计数
+            Some(_) => self.candidate = None,
+        }
+        self.current.as_deref()
+    }
+
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.candidate = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probs(language: &str, probability: f32) -> Vec<LanguageProbability> {
+        vec![LanguageProbability {
+            language: language.to_string(),
+            probability,
+        }]
+    }
+
+    #[test]
+    fn test_language_tracker_hysteresis() {
+        let mut tracker = LanguageTracker::new(0.6, 2);
+        assert_eq!(tracker.update(&probs("zh", 0.4)), Some("zh"));
+        // 单个窗口或低置信度不会切换
+        assert_eq!(tracker.update(&probs("en", 0.9)), Some("zh"));
+        assert_eq!(tracker.update(&probs("en", 0.5)), Some("zh"));
+        assert_eq!(tracker.update(&probs("en", 0.9)), Some("zh"));
+        assert_eq!(tracker.update(&probs("en", 0.8)), Some("en"));
+        assert_eq!(tracker.update(&[]), Some("en"));
+        tracker.reset();
+        assert_eq!(tracker.update(&probs("ja", 0.3)), Some("ja"));
     }
-    let language = token_id(tokenizer, &format!("<|{}|>", probs[0].0 .0))?;
-    Ok(language)
 }
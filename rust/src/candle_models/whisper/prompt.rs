@@ -0,0 +1,85 @@
+//! Decoding context of Whisper, fed after `<|startofprev|>`: the user prompt (a glossary of
+//! names and jargon) followed by the tail of the text finalized so far.
+
+use anyhow::Result;
+use tokenizers::Tokenizer;
+
+pub struct WhisperPrompt {
+    tokenizer: Tokenizer,
+    initial_prompt: Vec<u32>,
+    prompt: Vec<u32>,
+    context: Vec<u32>,
+    /// Tokens of previous text to keep, 0 disables conditioning on previous text
+    context_max_tokens: usize,
+    /// Model limit, half of the decoder context minus `<|startofprev|>`
+    max_tokens: usize,
+}
+
+impl WhisperPrompt {
+    pub fn new(
+        tokenizer: Tokenizer,
+        initial_prompt: Option<&str>,
+        context_max_tokens: usize,
+        max_tokens: usize,
+    ) -> Result<Self> {
+        let mut prompt = Self {
+            tokenizer,
+            initial_prompt: vec![],
+            prompt: vec![],
+            context: vec![],
+            context_max_tokens,
+            max_tokens,
+        };
+        if let Some(initial_prompt) = initial_prompt {
+            prompt.initial_prompt = prompt.encode(initial_prompt)?;
+            prompt.prompt = prompt.initial_prompt.clone();
+        }
+        Ok(prompt)
+    }
+
+    fn encode(&self, text: &str) -> Result<Vec<u32>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+        // 与 OpenAI 相同，提示文本前加空格
+        let encoding = self
+            .tokenizer
+            .encode(format!(" {text}"), false)
+            .map_err(anyhow::Error::msg)?;
+        Ok(encoding.get_ids().to_vec())
+    }
+
+    /// Replace the prompt until the next [`WhisperPrompt::reset`]
+    pub fn set_prompt(&mut self, prompt: &str) -> Result<()> {
+        self.prompt = self.encode(prompt)?;
+        Ok(())
+    }
+
+    /// Append finalized text, only the last `context_max_tokens` tokens are kept
+    pub fn push_context(&mut self, text: &str) -> Result<()> {
+        if self.context_max_tokens == 0 {
+            return Ok(());
+        }
+        let tokens = self.encode(text)?;
+        self.context.extend(tokens);
+        let excess = self.context.len().saturating_sub(self.context_max_tokens);
+        self.context.drain(..excess);
+        Ok(())
+    }
+
+    /// Back to the initial prompt without previous text
+    pub fn reset(&mut self) {
+        self.prompt = self.initial_prompt.clone();
+        self.context.clear();
+    }
+
+    /// Prompt tokens without `<|startofprev|>`, empty when there is no context.
+    /// Over the model limit the oldest previous text goes first, the prompt is kept.
+    pub fn tokens(&self) -> Vec<u32> {
+        let prompt = &self.prompt[self.prompt.len().saturating_sub(self.max_tokens)..];
+        let context_len = self.max_tokens - prompt.len();
+        let context = &self.context[self.context.len().saturating_sub(context_len)..];
+        [prompt, context].concat()
+    }
+}
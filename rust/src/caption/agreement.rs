@@ -0,0 +1,333 @@
+//! LocalAgreement-n commit policy for live captions.
+//!
+//! The live loop re-transcribes the whole history window on every inference, so the text of
+//! consecutive hypotheses overlaps and keeps changing at the tail. A word is committed once
+//! `n` consecutive hypotheses agree on it; everything after the agreed prefix stays tentative.
+
+use std::collections::VecDeque;
+
+use crate::candle_models::whisper::model::{
+    DecodingResult, Segment, SubSegment, WhisperStatus, WordTiming,
+};
+
+/// 用于与新假设去重的已提交单元数量
+const COMMITTED_TAIL_LEN: usize = 64;
+/// 去重时允许新假设开头有几个识别错误的单元（窗口滑动会截断开头的词）
+const MAX_OVERLAP_SKIP: usize = 3;
+
+/// A word (or CJK character) of a hypothesis, times in seconds on the caption timeline
+#[derive(Debug, Clone)]
+pub struct Unit {
+    /// Text including its leading whitespace, so units can be concatenated
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    /// `end` comes from a Whisper timestamp instead of interpolation,
+    /// so the audio before it can be dropped safely
+    pub timed: bool,
+}
+
+impl Unit {
+    fn key(&self) -> String {
+        let key: String = self
+            .text
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if key.is_empty() {
+            self.text.trim().to_string()
+        } else {
+            key
+        }
+    }
+}
+
+pub struct LocalAgreement {
+    n: usize,
+    /// 最近提交的单元，用于去除新假设中已提交的部分
+    committed_tail: Vec<Unit>,
+    committed_end: Option<f64>,
+    /// 最近 n-1 次（去掉已提交部分后的）假设
+    hypotheses: VecDeque<Vec<Unit>>,
+}
+
+impl LocalAgreement {
+    /// `n` consecutive hypotheses must agree before text is committed (at least 2)
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(2),
+            committed_tail: vec![],
+            committed_end: None,
+            hypotheses: VecDeque::new(),
+        }
+    }
+
+    /// Insert the hypothesis of a new window, returns `(newly committed, tentative)` units
+    pub fn insert(&mut self, hypothesis: Vec<Unit>) -> (Vec<Unit>, Vec<Unit>) {
+        let mut hypothesis = hypothesis;
+        let skip = self.committed_overlap(&hypothesis);
+        hypothesis.drain(..skip);
+
+        self.hypotheses.push_back(hypothesis);
+        if self.hypotheses.len() < self.n {
+            let tentative = self.hypotheses.back().cloned().unwrap_or_default();
+            return (vec![], tentative);
+        }
+
+        let current = self.hypotheses.back().unwrap();
+        let agreed = (0..current.len())
+            .take_while(|&i| {
+                let key = current[i].key();
+                self.hypotheses
+                    .iter()
+                    .all(|h| h.get(i).is_some_and(|u| u.key() == key))
+            })
+            .count();
+
+        let committed: Vec<Unit> = current[..agreed].to_vec();
+        for hypothesis in self.hypotheses.iter_mut() {
+            hypothesis.drain(..agreed);
+        }
+        self.hypotheses.pop_front();
+        let tentative = self.hypotheses.back().cloned().unwrap_or_default();
+        self.commit(&committed);
+        (committed, tentative)
+    }
+
+    /// Commit the last (tentative) hypothesis, used when the audio source ends
+    pub fn flush(&mut self) -> Vec<Unit> {
+        let units = self.hypotheses.pop_back().unwrap_or_default();
+        self.hypotheses.clear();
+        self.commit(&units);
+        units
+    }
+
+    /// End of the last committed unit with a reliable time, audio before it is no longer needed
+    pub fn committed_end(&self) -> Option<f64> {
+        self.committed_end
+    }
+
+    fn commit(&mut self, units: &[Unit]) {
+        if let Some(last) = units.last() {
+            if last.timed {
+                self.committed_end = Some(last.end);
+            }
+        }
+        self.committed_tail.extend_from_slice(units);
+        let excess = self.committed_tail.len().saturating_sub(COMMITTED_TAIL_LEN);
+        self.committed_tail.drain(..excess);
+    }
+
+    /// Number of leading units of `hypothesis` that repeat already committed text
+    fn committed_overlap(&self, hypothesis: &[Unit]) -> usize {
+        let tail: Vec<String> = self.committed_tail.iter().map(Unit::key).collect();
+        let keys: Vec<String> = hypothesis.iter().map(Unit::key).collect();
+        let mut best = 0;
+        for skip in 0..=MAX_OVERLAP_SKIP.min(keys.len()) {
+            let max_k = tail.len().min(keys.len() - skip);
+            // 跳过开头的单元时至少要匹配两个，避免误删新内容
+            let min_k = if skip == 0 { 1 } else { 2 };
+            for k in (min_k..=max_k).rev() {
+                if tail[tail.len() - k..] == keys[skip..skip + k] {
+                    best = best.max(skip + k);
+                    break;
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Flatten the working segments of a window into units, `offset` is the window start in seconds
+pub fn hypothesis_units(segments: &[Segment], offset: f64) -> Vec<Unit> {
+    let mut units = vec![];
+    for segment in segments
+        .iter()
+        .filter(|s| matches!(s.status, WhisperStatus::Working))
+    {
+        if segment.sub_segments.is_empty() {
+            let start = offset + segment.start;
+            units.extend(split_units(
+                &segment.dr.text,
+                start,
+                start + segment.duration,
+                false,
+            ));
+            continue;
+        }
+        for sub_segment in &segment.sub_segments {
+            if sub_segment.words.is_empty() {
+                units.extend(split_units(
+                    &sub_segment.text,
+                    offset + sub_segment.start,
+                    offset + sub_segment.end,
+                    true,
+                ));
+            } else {
+                units.extend(sub_segment.words.iter().map(|word| Unit {
+                    text: word.word.clone(),
+                    start: offset + word.start,
+                    end: offset + word.end,
+                    timed: true,
+                }));
+            }
+        }
+    }
+    units
+}
+
+/// Split text into words (each CJK character is its own unit) with times interpolated
+/// by character count. Only the last unit is `timed`, and only if `end_timed` is set.
+pub fn split_units(text: &str, start: f64, end: f64, end_timed: bool) -> Vec<Unit> {
+    let mut words: Vec<String> = vec![];
+    let mut current = String::new();
+    for c in text.chars() {
+        // 中日文标点跟随前一个字
+        if current.is_empty() && !c.is_alphanumeric() && !c.is_whitespace() {
+            if let Some(last) = words.last_mut() {
+                last.push(c);
+                continue;
+            }
+        }
+        let starts_unit = is_cjk(c) || (c.is_whitespace() && !current.trim().is_empty());
+        if starts_unit && !current.trim().is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+        if is_cjk(c) {
+            words.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        words.push(current);
+    } else if let Some(last) = words.last_mut() {
+        last.push_str(&current);
+    }
+
+    let total: usize = words.iter().map(|w| w.trim().chars().count()).sum();
+    let step = (end - start).max(0.0) / total.max(1) as f64;
+    let mut position = start;
+    let count = words.len();
+    words
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let unit_start = position;
+            position += step * text.trim().chars().count() as f64;
+            Unit {
+                text,
+                start: unit_start,
+                end: if i + 1 == count { end } else { position },
+                timed: end_timed && i + 1 == count,
+            }
+        })
+        .collect()
+}
+
+/// Build the segment sent to the UI for committed or tentative units
+pub fn units_to_segment(
+    units: &[Unit],
+    id: u64,
+    committed: bool,
+    template: Option<&DecodingResult>,
+) -> Segment {
+    let text: String = units.iter().map(|u| u.text.as_str()).collect();
+    let text = text.trim().to_string();
+    let start = units.first().map(|u| u.start).unwrap_or(0.0);
+    let end = units.last().map(|u| u.end).unwrap_or(start);
+    let sub_segments = if !units.is_empty() && units.iter().all(|u| u.timed) {
+        vec![SubSegment {
+            start,
+            end,
+            text: text.clone(),
+            words: units
+                .iter()
+                .map(|u| WordTiming {
+                    start: u.start,
+                    end: u.end,
+                    word: u.text.clone(),
+                })
+                .collect(),
+        }]
+    } else {
+        vec![]
+    };
+    Segment {
+        start,
+        duration: end - start,
+        dr: DecodingResult {
+            tokens: vec![],
+            text,
+            avg_logprob: template.map(|dr| dr.avg_logprob).unwrap_or(f64::NAN),
+            no_speech_prob: template.map(|dr| dr.no_speech_prob).unwrap_or(0.0),
+            temperature: template.map(|dr| dr.temperature).unwrap_or(0.0),
+            compression_ratio: template.map(|dr| dr.compression_ratio).unwrap_or(1.0),
+        },
+        reasoning_duration: None,
+        reasoning_lang: None,
+        audio_duration: None,
+        status: WhisperStatus::Working,
+        sub_segments,
+        id,
+        committed,
+        language_probs: vec![],
+        emotion: None,
+        audio_event: None,
+    }
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}' // 平假名 / 片假名
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}')
+}
+
+/// Segment of `text` spread evenly over `start..end`, shared by the caption output tests
+#[cfg(test)]
+pub(crate) fn test_segment(text: &str, start: f64, end: f64, id: u64, committed: bool) -> Segment {
+    units_to_segment(&split_units(text, start, end, false), id, committed, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hypothesis(text: &str) -> Vec<Unit> {
+        split_units(text, 0.0, 1.0, false)
+    }
+
+    fn join(units: &[Unit]) -> String {
+        units.iter().map(|u| u.text.as_str()).collect::<String>()
+    }
+
+    #[test]
+    fn test_split_units() {
+        let units = split_units(" Hello world, 你好。", 0.0, 1.0, true);
+        let texts: Vec<_> = units.iter().map(|u| u.text.as_str()).collect();
+        assert_eq!(texts, vec![" Hello", " world,", " 你", "好。"]);
+        assert!(units.last().unwrap().timed);
+        assert_eq!(units.last().unwrap().end, 1.0);
+    }
+
+    #[test]
+    fn test_local_agreement_commits_stable_prefix() {
+        let mut agreement = LocalAgreement::new(2);
+        let (committed, tentative) = agreement.insert(hypothesis(" one two three"));
+        assert!(committed.is_empty());
+        assert_eq!(join(&tentative), " one two three");
+
+        let (committed, tentative) = agreement.insert(hypothesis(" one two three four five"));
+        assert_eq!(join(&committed), " one two three");
+        assert_eq!(join(&tentative), " four five");
+
+        // 窗口仍包含已提交的文本，且开头的词被截断
+        let (committed, tentative) = agreement.insert(hypothesis(" ne two three four five six"));
+        assert_eq!(join(&committed), " four five");
+        assert_eq!(join(&tentative), " six");
+
+        assert_eq!(join(&agreement.flush()), " six");
+    }
+}
@@ -0,0 +1,262 @@
+//! WebSocket broadcast of caption events, for browser overlays, OBS browser sources and
+//! other tools that can't use the Flutter `StreamSink`.
+//!
+//! Every published [`CaptionEvent`] is sent to all subscribers as the same JSON that
+//! `serde_json` produces for it (`{"event": "segments", ...}`). On connect a subscriber
+//! first receives the last `ready` event, a `history` message with the recent committed
+//! segments and the latest `segments` event, so late joiners start with context.
+//! Messages from subscribers are ignored.
+
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::{Message, Utf8Bytes};
+use tokio_util::sync::CancellationToken;
+
+use crate::candle_models::whisper::model::Segment;
+use crate::caption::CaptionEvent;
+
+/// 新连接收到的已提交字幕段数
+const HISTORY_SEGMENTS: usize = 50;
+/// 每个订阅者最多积压的消息数，超出后丢弃最旧的
+const CHANNEL_CAPACITY: usize = 256;
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Parse `host:port`, a bare port binds to localhost
+pub fn parse_addr(addr: &str) -> Option<SocketAddr> {
+    match addr.parse::<u16>() {
+        Ok(port) => Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)),
+        Err(_) => addr.parse().ok(),
+    }
+}
+
+#[derive(Default)]
+struct State {
+    ready: Option<Utf8Bytes>,
+    committed: VecDeque<Segment>,
+    latest: Option<Utf8Bytes>,
+}
+
+impl State {
+    /// Messages sent to a new subscriber before the live events
+    fn greeting(&self) -> Vec<Utf8Bytes> {
+        let history = serde_json::json!({
+            "event": "history",
+            "segments": self.committed,
+        });
+        let mut messages: Vec<Utf8Bytes> = self.ready.iter().cloned().collect();
+        messages.push(history.to_string().into());
+        messages.extend(self.latest.iter().cloned());
+        messages
+    }
+}
+
+struct Shared {
+    state: Mutex<State>,
+    tx: broadcast::Sender<Utf8Bytes>,
+}
+
+/// Embeddable broadcast server, runs on its own thread and stops when dropped
+pub struct BroadcastServer {
+    shared: Arc<Shared>,
+    local_addr: SocketAddr,
+    cancel_token: CancellationToken,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BroadcastServer {
+    /// Listen on `addr` (port 0 picks a free port, see [`BroadcastServer::local_addr`])
+    pub fn start(addr: SocketAddr) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("failed to listen on {addr}"))?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        // 独立线程和运行时，不依赖调用方的 tokio 运行时（字幕主循环会阻塞所在线程）
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            tx,
+        });
+        let cancel_token = CancellationToken::new();
+        let thread = {
+            let shared = shared.clone();
+            let cancel_token = cancel_token.clone();
+            std::thread::Builder::new()
+                .name("caption-broadcast".to_string())
+                .spawn(move || {
+                    runtime.block_on(async move {
+                        match TcpListener::from_std(listener) {
+                            Ok(listener) => accept_loop(listener, shared, cancel_token).await,
+                            Err(e) => eprintln!("Broadcast server error: {:?}", e),
+                        }
+                    })
+                })?
+        };
+        eprintln!("Caption broadcast listening on ws://{local_addr}");
+        Ok(Self {
+            shared,
+            local_addr,
+            cancel_token,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.tx.receiver_count()
+    }
+
+    /// Send `event` to all subscribers and remember what late joiners need
+    pub fn publish(&self, event: &CaptionEvent) {
+        let json: Utf8Bytes = match serde_json::to_string(event) {
+            Ok(json) => json.into(),
+            Err(e) => {
+                eprintln!("Failed to serialize caption event: {:?}", e);
+                return;
+            }
+        };
+        // 持锁发送，保证新订阅者的历史与实时消息之间不重不漏
+        let mut state = self.shared.state.lock().unwrap();
+        match event {
+            CaptionEvent::Ready { .. } => state.ready = Some(json.clone()),
+            CaptionEvent::Segments { segments } => {
+                for segment in segments {
+                    if segment.committed && !segment.dr.text.trim().is_empty() {
+                        state.committed.push_back(segment.clone());
+                    }
+                }
+                let excess = state.committed.len().saturating_sub(HISTORY_SEGMENTS);
+                state.committed.drain(..excess);
+                state.latest = Some(json.clone());
+            }
+            _ => {}
+        }
+        // 没有订阅者时发送失败，忽略
+        let _ = self.shared.tx.send(json);
+    }
+}
+
+impl Drop for BroadcastServer {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+async fn accept_loop(listener: TcpListener, shared: Arc<Shared>, cancel_token: CancellationToken) {
+    let mut clients = tokio::task::JoinSet::new();
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    let shared = shared.clone();
+                    let cancel_token = cancel_token.clone();
+                    clients.spawn(async move {
+                        if let Err(e) = serve_client(stream, shared, cancel_token).await {
+                            eprintln!("Broadcast subscriber {peer} error: {:?}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Broadcast accept error: {:?}", e),
+            },
+        }
+    }
+    // 等待所有连接发送关闭帧，卡住的连接超时后随 JoinSet 一起中止
+    let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+        while clients.join_next().await.is_some() {}
+    })
+    .await;
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    shared: Arc<Shared>,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let mut ws = tokio_tungstenite::accept_async(stream).await?;
+    let (mut rx, greeting) = {
+        let state = shared.state.lock().unwrap();
+        (shared.tx.subscribe(), state.greeting())
+    };
+    for message in greeting {
+        ws.send(Message::Text(message)).await?;
+    }
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => {
+                // 先发完积压的消息（如最后的 exit 事件）再关闭
+                while let Ok(message) = rx.try_recv() {
+                    ws.send(Message::Text(message)).await?;
+                }
+                let _ = ws.close(None).await;
+                break;
+            }
+            message = rx.recv() => match message {
+                Ok(message) => ws.send(Message::Text(message)).await?,
+                Err(RecvError::Lagged(count)) => {
+                    eprintln!("Broadcast subscriber lagged, {count} messages dropped");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = ws.next() => match incoming {
+                None | Some(Ok(Message::Close(_))) => break,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+            },
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::agreement::test_segment;
+
+    #[tokio::test]
+    async fn test_broadcast_late_joiner_receives_history() {
+        let server = BroadcastServer::start(parse_addr("0").unwrap()).unwrap();
+        server.publish(&CaptionEvent::Ready {
+            backend: "Test".to_string(),
+            device: "cpu".to_string(),
+            providers: vec![],
+        });
+        server.publish(&CaptionEvent::Segments {
+            segments: vec![test_segment(" hello world", 0.0, 1.0, 1, true)],
+        });
+
+        let url = format!("ws://{}", server.local_addr());
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        let mut next = async || -> serde_json::Value {
+            let message = client.next().await.unwrap().unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        };
+        assert_eq!(next().await["event"], "ready");
+        let history = next().await;
+        assert_eq!(history["event"], "history");
+        assert_eq!(history["segments"][0]["dr"]["text"], "hello world");
+        assert_eq!(next().await["event"], "segments");
+
+        server.publish(&CaptionEvent::Warning {
+            msg: "live".to_string(),
+        });
+        assert_eq!(next().await["msg"], "live");
+    }
+}
@@ -0,0 +1,527 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::candle_models::whisper::model::Task;
+use crate::candle_models::whisper::LaunchCaptionParams;
+use crate::caption::subtitles::SubtitleFormat;
+use crate::caption::{broadcast, obs, recorder};
+
+/// Whisper 的音频窗口为 30 秒，更长的音频会被截断
+pub const MAX_AUDIO_DURATION_SECS: u32 = 30;
+
+/// `vad_threshold` 的默认值
+pub const DEFAULT_VAD_THRESHOLD: f32 = 0.5;
+
+/// Model types decoded as the audio arrives, they end and commit each utterance themselves
+/// (`endpoint_silence_ms` without a VAD model)
+pub const STREAMING_MODEL_TYPES: [&str; 1] = ["zipformer_onnx"];
+
+/// Settings of a live caption session.
+///
+/// Serializable as JSON or TOML so a config can be saved, shared and loaded by hosts
+/// other than the Flutter app. Missing fields take their default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptionConfig {
+    /// Device id from `list_audio_devices`, None for the system default device
+    pub audio_device: Option<String>,
+    /// Capture an input device (microphone), `false` captures what an output device plays.
+    /// Default `true`
+    pub audio_device_is_input: bool,
+    /// Spoken language such as "en", None lets multilingual models detect it
+    pub language: Option<String>,
+    pub with_timestamps: bool,
+    /// Word-level timestamps in the sub-segments (candle Whisper only)
+    pub word_timestamps: bool,
+    pub verbose: bool,
+    pub try_with_cuda: bool,
+    /// Audio context fed to the model in seconds, `1..=30`, default 12
+    pub max_audio_duration_secs: u32,
+    /// Time between two inferences in milliseconds, default 2000
+    pub inference_interval_ms: u64,
+    /// Abort a decode running longer than this, default 2000, None disables the limit
+    pub inference_timeout_ms: Option<u64>,
+    /// Token limit per segment against hallucination loops, None uses the model limit
+    pub max_tokens_per_segment: Option<u32>,
+    /// Sampling temperature, None uses the decoder's temperature fallback
+    pub temperature: Option<f32>,
+    /// Silero VAD model file, None disables VAD
+    pub vad_model_path: Option<String>,
+    /// Speech probability threshold in `0.0..=1.0`, default 0.5
+    pub vad_threshold: Option<f32>,
+    /// Commit text once n (>= 2) consecutive inferences agree, None disables it
+    pub local_agreement: Option<u32>,
+    /// End an utterance after this much silence (requires `vad_model_path` except for streaming
+    /// models), None runs inference every `inference_interval_ms`
+    pub endpoint_silence_ms: Option<u32>,
+    /// Write the committed captions to this `.srt`, `.vtt` or `.ttml` file while captioning
+    /// (requires `local_agreement`, `endpoint_silence_ms` or a streaming model), None disables it
+    pub subtitle_path: Option<String>,
+    /// Archive the captured audio to this `.wav` or `.flac` file and write a JSONL transcript
+    /// of every segment next to it (same name, `.jsonl`), None disables recording
+    pub record_path: Option<String>,
+    /// Broadcast the caption events as JSON over WebSocket on this `host:port`,
+    /// a bare port listens on localhost only, None disables it
+    pub broadcast_addr: Option<String>,
+    /// Keep the last `obs_text_lines` caption lines in this text file for an OBS Text source,
+    /// None disables it
+    pub obs_text_path: Option<String>,
+    /// Lines kept in `obs_text_path`, default 3
+    pub obs_text_lines: u32,
+    /// Send the captions to OBS as native closed captions over obs-websocket v5,
+    /// e.g. "ws://127.0.0.1:4455", None disables it
+    pub obs_websocket_url: Option<String>,
+    /// obs-websocket server password, None if authentication is disabled
+    pub obs_websocket_password: Option<String>,
+    /// Beam search width at temperature 0 (candle Whisper only), None or 1 decodes greedily
+    pub beam_size: Option<u32>,
+    /// Beam search stops once `beam_size * patience` sequences finished, None means 1.0
+    pub beam_patience: Option<f32>,
+    /// Length penalty alpha in `0.0..=1.0` for ranking beams, None ranks by average log
+    /// probability
+    pub length_penalty: Option<f32>,
+    /// Text fed to Whisper as previous context, such as names and jargon to spell right
+    pub initial_prompt: Option<String>,
+    /// Condition each window on up to this many tokens of the committed text (requires
+    /// `local_agreement`, `endpoint_silence_ms` or a streaming model), None disables it
+    pub condition_on_previous_text: Option<u32>,
+    /// Translate the speech to English instead of transcribing it (multilingual Whisper only)
+    pub translate: bool,
+    /// Detect the language again for every window so speakers can switch languages
+    /// (multilingual Whisper, `language` unset), segments then report the distribution
+    pub redetect_language: bool,
+    /// Probability in `0.0..=1.0` another language needs to take over, default 0.6
+    pub language_switch_threshold: Option<f32>,
+    /// Consecutive windows another language has to win before taking over, default 2
+    pub language_switch_windows: Option<u32>,
+    /// Put the emotion and audio event emoji into the caption text (SenseVoice only), the
+    /// tags are reported in the segment fields either way
+    pub inline_emoji: bool,
+    /// Inverse text normalization (SenseVoice only): written-form numbers and punctuation,
+    /// `false` keeps the spoken form. Default `false`
+    pub with_itn: bool,
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        Self {
+            audio_device: None,
+            audio_device_is_input: true,
+            language: None,
+            with_timestamps: false,
+            word_timestamps: false,
+            verbose: false,
+            try_with_cuda: false,
+            max_audio_duration_secs: 12,
+            inference_interval_ms: 2000,
+            inference_timeout_ms: Some(2000),
+            max_tokens_per_segment: None,
+            temperature: None,
+            vad_model_path: None,
+            vad_threshold: Some(DEFAULT_VAD_THRESHOLD),
+            local_agreement: None,
+            endpoint_silence_ms: None,
+            subtitle_path: None,
+            record_path: None,
+            broadcast_addr: None,
+            obs_text_path: None,
+            obs_text_lines: 3,
+            obs_websocket_url: None,
+            obs_websocket_password: None,
+            beam_size: None,
+            beam_patience: None,
+            length_penalty: None,
+            initial_prompt: None,
+            condition_on_previous_text: None,
+            translate: false,
+            redetect_language: false,
+            language_switch_threshold: None,
+            language_switch_windows: None,
+            inline_emoji: false,
+            with_itn: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// A language was set for a model that only knows one language
+    LanguageOnMonolingualModel(String),
+    VadThresholdOutOfRange(f32),
+    MaxAudioDurationOutOfRange(u32),
+    ZeroInferenceInterval,
+    ZeroInferenceTimeout,
+    ZeroMaxTokens,
+    InvalidTemperature(f32),
+    /// LocalAgreement needs at least two hypotheses to compare
+    LocalAgreementTooSmall(u32),
+    EndpointingWithoutVad,
+    UnknownSubtitleFormat(String),
+    /// Only committed text is written to subtitles, the default loop never commits
+    SubtitlesWithoutCommitPolicy,
+    UnknownRecordingFormat(String),
+    InvalidBroadcastAddr(String),
+    ZeroObsTextLines,
+    InvalidObsWebsocketUrl(String),
+    ZeroBeamSize,
+    InvalidBeamPatience(f32),
+    LengthPenaltyOutOfRange(f32),
+    ZeroPreviousTextTokens,
+    /// Only committed text is used as context, the default loop never commits
+    PreviousTextWithoutCommitPolicy,
+    /// Only multilingual models know the translate task
+    TranslateOnMonolingualModel,
+    RedetectOnMonolingualModel,
+    /// A fixed language is never detected
+    RedetectWithFixedLanguage(String),
+    LanguageSwitchThresholdOutOfRange(f32),
+    ZeroLanguageSwitchWindows,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::LanguageOnMonolingualModel(language) => write!(
+                f,
+                "language {language} cannot be set for non-multilingual models"
+            ),
+            ConfigError::VadThresholdOutOfRange(v) => {
+                write!(f, "VAD threshold {v} is outside 0.0..=1.0")
+            }
+            ConfigError::MaxAudioDurationOutOfRange(v) => write!(
+                f,
+                "max audio duration {v}s is outside 1..={MAX_AUDIO_DURATION_SECS}s"
+            ),
+            ConfigError::ZeroInferenceInterval => write!(f, "inference interval must be > 0"),
+            ConfigError::ZeroInferenceTimeout => {
+                write!(f, "inference timeout must be > 0, use None to disable it")
+            }
+            ConfigError::ZeroMaxTokens => write!(f, "max tokens per segment must be > 0"),
+            ConfigError::InvalidTemperature(v) => {
+                write!(f, "temperature {v} must be a finite value >= 0")
+            }
+            ConfigError::LocalAgreementTooSmall(n) => write!(
+                f,
+                "local agreement needs n >= 2 (got {n}), use None to disable it"
+            ),
+            ConfigError::EndpointingWithoutVad => {
+                write!(f, "VAD endpointing requires a VAD model path")
+            }
+            ConfigError::UnknownSubtitleFormat(path) => write!(
+                f,
+                "unknown subtitle format of {path}, use .srt, .vtt or .ttml"
+            ),
+            ConfigError::SubtitlesWithoutCommitPolicy => {
+                write!(f, "subtitles require local agreement or VAD endpointing")
+            }
+            ConfigError::UnknownRecordingFormat(path) => {
+                write!(f, "unknown audio format of {path}, use .wav or .flac")
+            }
+            ConfigError::InvalidBroadcastAddr(addr) => {
+                write!(
+                    f,
+                    "invalid broadcast address {addr}, use host:port or a port"
+                )
+            }
+            ConfigError::ZeroObsTextLines => write!(f, "OBS text lines must be > 0"),
+            ConfigError::InvalidObsWebsocketUrl(url) => {
+                write!(f, "invalid obs-websocket url {url}, use ws://host:port")
+            }
+            ConfigError::ZeroBeamSize => write!(f, "beam size must be > 0"),
+            ConfigError::InvalidBeamPatience(v) => {
+                write!(f, "beam patience {v} must be a finite value > 0")
+            }
+            ConfigError::LengthPenaltyOutOfRange(v) => {
+                write!(f, "length penalty {v} is outside 0.0..=1.0")
+            }
+            ConfigError::ZeroPreviousTextTokens => write!(
+                f,
+                "previous text tokens must be > 0, use None to disable it"
+            ),
+            ConfigError::PreviousTextWithoutCommitPolicy => write!(
+                f,
+                "conditioning on previous text requires local agreement or VAD endpointing"
+            ),
+            ConfigError::TranslateOnMonolingualModel => {
+                write!(f, "translation requires a multilingual model")
+            }
+            ConfigError::RedetectOnMonolingualModel => {
+                write!(f, "language detection requires a multilingual model")
+            }
+            ConfigError::RedetectWithFixedLanguage(language) => write!(
+                f,
+                "language {language} is fixed, unset it to detect the language of every window"
+            ),
+            ConfigError::LanguageSwitchThresholdOutOfRange(v) => {
+                write!(f, "language switch threshold {v} is outside 0.0..=1.0")
+            }
+            ConfigError::ZeroLanguageSwitchWindows => {
+                write!(f, "language switch windows must be > 0")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl CaptionConfig {
+    /// Check the values against each other and the model, returns the first problem found
+    pub fn validate(&self, model_type: &str, is_multilingual: bool) -> Result<(), ConfigError> {
+        // 流式模型自己断句并提交每句话
+        let streaming = STREAMING_MODEL_TYPES.contains(&model_type);
+        let commits =
+            streaming || self.local_agreement.is_some() || self.endpoint_silence_ms.is_some();
+        if let Some(language) = &self.language {
+            if !is_multilingual {
+                return Err(ConfigError::LanguageOnMonolingualModel(language.clone()));
+            }
+        }
+        if self.translate && !is_multilingual {
+            return Err(ConfigError::TranslateOnMonolingualModel);
+        }
+        if self.redetect_language {
+            if !is_multilingual {
+                return Err(ConfigError::RedetectOnMonolingualModel);
+            }
+            if let Some(language) = &self.language {
+                return Err(ConfigError::RedetectWithFixedLanguage(language.clone()));
+            }
+        }
+        if let Some(threshold) = self.language_switch_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(ConfigError::LanguageSwitchThresholdOutOfRange(threshold));
+            }
+        }
+        if self.language_switch_windows == Some(0) {
+            return Err(ConfigError::ZeroLanguageSwitchWindows);
+        }
+        if let Some(threshold) = self.vad_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(ConfigError::VadThresholdOutOfRange(threshold));
+            }
+        }
+        if !(1..=MAX_AUDIO_DURATION_SECS).contains(&self.max_audio_duration_secs) {
+            return Err(ConfigError::MaxAudioDurationOutOfRange(
+                self.max_audio_duration_secs,
+            ));
+        }
+        if self.inference_interval_ms == 0 {
+            return Err(ConfigError::ZeroInferenceInterval);
+        }
+        if self.inference_timeout_ms == Some(0) {
+            return Err(ConfigError::ZeroInferenceTimeout);
+        }
+        if self.max_tokens_per_segment == Some(0) {
+            return Err(ConfigError::ZeroMaxTokens);
+        }
+        if let Some(temperature) = self.temperature {
+            if !temperature.is_finite() || temperature < 0.0 {
+                return Err(ConfigError::InvalidTemperature(temperature));
+            }
+        }
+        if let Some(n) = self.local_agreement {
+            if n < 2 {
+                return Err(ConfigError::LocalAgreementTooSmall(n));
+            }
+        }
+        if self.endpoint_silence_ms.is_some() && self.vad_model_path.is_none() && !streaming {
+            return Err(ConfigError::EndpointingWithoutVad);
+        }
+        if let Some(path) = &self.subtitle_path {
+            if SubtitleFormat::from_path(path).is_none() {
+                return Err(ConfigError::UnknownSubtitleFormat(path.clone()));
+            }
+            if !commits {
+                return Err(ConfigError::SubtitlesWithoutCommitPolicy);
+            }
+        }
+        if let Some(path) = &self.record_path {
+            if !recorder::is_supported(path) {
+                return Err(ConfigError::UnknownRecordingFormat(path.clone()));
+            }
+        }
+        if let Some(addr) = &self.broadcast_addr {
+            if broadcast::parse_addr(addr).is_none() {
+                return Err(ConfigError::InvalidBroadcastAddr(addr.clone()));
+            }
+        }
+        if self.obs_text_path.is_some() && self.obs_text_lines == 0 {
+            return Err(ConfigError::ZeroObsTextLines);
+        }
+        if let Some(url) = &self.obs_websocket_url {
+            if !obs::is_valid_url(url) {
+                return Err(ConfigError::InvalidObsWebsocketUrl(url.clone()));
+            }
+        }
+        if self.beam_size == Some(0) {
+            return Err(ConfigError::ZeroBeamSize);
+        }
+        if let Some(patience) = self.beam_patience {
+            if !patience.is_finite() || patience <= 0.0 {
+                return Err(ConfigError::InvalidBeamPatience(patience));
+            }
+        }
+        if let Some(length_penalty) = self.length_penalty {
+            if !(0.0..=1.0).contains(&length_penalty) {
+                return Err(ConfigError::LengthPenaltyOutOfRange(length_penalty));
+            }
+        }
+        if let Some(tokens) = self.condition_on_previous_text {
+            if tokens == 0 {
+                return Err(ConfigError::ZeroPreviousTextTokens);
+            }
+            if !commits {
+                return Err(ConfigError::PreviousTextWithoutCommitPolicy);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_json(s: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Caption parameters without the model fields, those are filled in by the caller
+    pub fn into_params(self, cancel_token: CancellationToken) -> LaunchCaptionParams {
+        LaunchCaptionParams {
+            audio_device: self.audio_device,
+            audio_device_is_input: Some(self.audio_device_is_input),
+            audio_language: self.language,
+            cancel_token,
+            // LocalAgreement 需要时间戳来裁剪已提交的音频
+            with_timestamps: Some(self.with_timestamps || self.local_agreement.is_some()),
+            word_timestamps: Some(self.word_timestamps),
+            verbose: Some(self.verbose),
+            try_with_cuda: self.try_with_cuda,
+            inference_timeout: self.inference_timeout_ms.map(Duration::from_millis),
+            max_tokens_per_segment: self.max_tokens_per_segment.map(|n| n as usize),
+            whisper_max_audio_duration: Some(self.max_audio_duration_secs),
+            inference_interval_ms: Some(self.inference_interval_ms),
+            whisper_temperature: self.temperature,
+            vad_model_path: self.vad_model_path,
+            // 没有阈值时 check_vad 不保留任何音频
+            vad_filters_value: self.vad_threshold.or(Some(DEFAULT_VAD_THRESHOLD)),
+            local_agreement: self.local_agreement.map(|n| n as usize),
+            endpoint_silence_ms: self.endpoint_silence_ms,
+            subtitle_path: self.subtitle_path,
+            record_path: self.record_path,
+            broadcast_addr: self.broadcast_addr,
+            obs_text_path: self.obs_text_path,
+            obs_text_lines: Some(self.obs_text_lines),
+            obs_websocket_url: self.obs_websocket_url,
+            obs_websocket_password: self.obs_websocket_password,
+            whisper_beam_size: self.beam_size.map(|n| n as usize),
+            whisper_beam_patience: self.beam_patience,
+            whisper_length_penalty: self.length_penalty,
+            whisper_initial_prompt: self.initial_prompt,
+            whisper_previous_text_tokens: self.condition_on_previous_text.map(|n| n as usize),
+            whisper_task: self.translate.then_some(Task::Translate),
+            whisper_redetect_language: Some(self.redetect_language),
+            whisper_language_switch_threshold: self.language_switch_threshold,
+            whisper_language_switch_windows: self.language_switch_windows.map(|n| n as usize),
+            sense_voice_inline_emoji: Some(self.inline_emoji),
+            sense_voice_use_itn: Some(self.with_itn),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_agreement_enables_timestamps() {
+        let config = CaptionConfig {
+            local_agreement: Some(2),
+            ..Default::default()
+        };
+        let params = config.into_params(CancellationToken::new());
+        assert_eq!(params.with_timestamps, Some(true));
+        assert_eq!(params.local_agreement, Some(2));
+
+        let params = CaptionConfig::default().into_params(CancellationToken::new());
+        assert_eq!(params.with_timestamps, Some(false));
+    }
+
+    #[test]
+    fn test_vad_threshold_defaults_to_half() {
+        let config = CaptionConfig {
+            vad_model_path: Some("silero.onnx".to_string()),
+            vad_threshold: None,
+            ..Default::default()
+        };
+        let params = config.into_params(CancellationToken::new());
+        assert_eq!(params.vad_filters_value, Some(DEFAULT_VAD_THRESHOLD));
+        assert_eq!(CaptionConfig::default().vad_threshold, Some(0.5));
+    }
+
+    #[test]
+    fn test_caption_config_toml_and_validation() {
+        // 缺省字段取默认值
+        let config = CaptionConfig::from_toml("language = \"en\"\nvad_threshold = 0.6\n").unwrap();
+        assert_eq!(config.language.as_deref(), Some("en"));
+        assert_eq!(config.inference_interval_ms, 2000);
+        assert_eq!(
+            CaptionConfig::from_toml(&config.to_toml().unwrap()).unwrap(),
+            config
+        );
+        assert_eq!(
+            CaptionConfig::from_json(&config.to_json().unwrap()).unwrap(),
+            config
+        );
+
+        assert_eq!(config.validate("whisper", true), Ok(()));
+        assert_eq!(
+            config.validate("whisper", false),
+            Err(ConfigError::LanguageOnMonolingualModel("en".to_string()))
+        );
+        let config = CaptionConfig {
+            vad_threshold: Some(1.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate("whisper", true),
+            Err(ConfigError::VadThresholdOutOfRange(1.5))
+        );
+    }
+
+    #[test]
+    fn test_streaming_models_commit_utterances() {
+        let config = CaptionConfig {
+            subtitle_path: Some("live.srt".to_string()),
+            endpoint_silence_ms: Some(800),
+            ..Default::default()
+        };
+        assert_eq!(config.validate("zipformer_onnx", false), Ok(()));
+        assert_eq!(
+            config.validate("whisper", true),
+            Err(ConfigError::EndpointingWithoutVad)
+        );
+
+        let config = CaptionConfig {
+            subtitle_path: Some("live.srt".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.validate("zipformer_onnx", false), Ok(()));
+        assert_eq!(
+            config.validate("sense-voice_onnx", true),
+            Err(ConfigError::SubtitlesWithoutCommitPolicy)
+        );
+    }
+}
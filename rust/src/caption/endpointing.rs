@@ -0,0 +1,396 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::caption::{agreement, engine, CaptionErrorKind, CaptionEvent, SpeechRecognizer};
+use crate::onnx_models::vad::{StreamingVad, VadEvent};
+
+const SAMPLE_RATE: usize = 16000;
+/// 静音时保留的音频，用于容纳 VAD 回溯的语音起点
+const PRE_ROLL_SAMPLES: usize = SAMPLE_RATE;
+/// 太短的语音不送去识别
+const MIN_DECODE_SAMPLES: usize = SAMPLE_RATE / 10;
+
+/// Source of speech start/end events for the [`Endpointer`]
+pub(crate) trait VadEventSource {
+    /// Consume new 16kHz mono samples, returns the events of the completed frames
+    fn accept(&mut self, pcm: &[f32]) -> anyhow::Result<Vec<VadEvent>>;
+    /// End the current speech (if any) when the audio source is finished
+    fn flush(&mut self) -> Option<VadEvent>;
+    /// Speech probability of the last scored frame
+    fn last_probability(&self) -> f32;
+}
+
+impl VadEventSource for StreamingVad {
+    fn accept(&mut self, pcm: &[f32]) -> anyhow::Result<Vec<VadEvent>> {
+        StreamingVad::accept(self, pcm)
+    }
+
+    fn flush(&mut self) -> Option<VadEvent> {
+        StreamingVad::flush(self)
+    }
+
+    fn last_probability(&self) -> f32 {
+        StreamingVad::last_probability(self)
+    }
+}
+
+/// VAD-driven caption loop: audio is accumulated while the VAD reports speech, an interim decode
+/// runs every `inference_interval` and a final decode runs when the utterance ends.
+///
+/// All segments of an utterance share its id; interim results have `committed == false`
+/// and are replaced by the final (committed) result. Times are relative to the capture start.
+pub(crate) struct Endpointer {
+    vad: Box<dyn VadEventSource>,
+    inference_interval: Duration,
+    max_utterance_samples: usize,
+    /// 从 buffer_start 开始的音频
+    buffer: Vec<f32>,
+    buffer_start: usize,
+    utterance_start: Option<usize>,
+    utterance_id: u64,
+    last_interim: Instant,
+}
+
+impl Endpointer {
+    pub(crate) fn new(
+        vad: Box<dyn VadEventSource>,
+        inference_interval: Duration,
+        max_utterance_samples: usize,
+    ) -> Self {
+        Self {
+            vad,
+            inference_interval,
+            max_utterance_samples,
+            buffer: vec![],
+            buffer_start: 0,
+            utterance_start: None,
+            utterance_id: 1,
+            last_interim: Instant::now(),
+        }
+    }
+
+    pub(crate) fn run<F>(
+        &mut self,
+        rx: Receiver<Vec<f32>>,
+        recognizer: &mut dyn SpeechRecognizer,
+        cancel_token: &CancellationToken,
+        language: Option<&str>,
+        result_callback: &mut F,
+    ) where
+        F: FnMut(CaptionEvent),
+    {
+        let name = recognizer.name().to_string();
+        eprintln!("Starting {name} VAD endpointing loop...");
+
+        while !cancel_token.is_cancelled() {
+            let (pcm, finished) = match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(pcm) => (pcm, false),
+                Err(RecvTimeoutError::Disconnected) => {
+                    eprintln!("{name} audio capture finished");
+                    (vec![], true)
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+            };
+
+            self.buffer.extend_from_slice(&pcm);
+            let mut events = self.vad.accept(&pcm).unwrap_or_else(|e| {
+                eprintln!("VAD error: {:?}", e);
+                result_callback(CaptionEvent::error(CaptionErrorKind::Vad, &e));
+                vec![]
+            });
+            if finished {
+                events.extend(self.vad.flush());
+            }
+
+            for event in events {
+                match event {
+                    VadEvent::SpeechStart { sample } => {
+                        eprintln!("{name} speech start at {:.2}s", seconds(sample));
+                        result_callback(CaptionEvent::VadActivity {
+                            speaking: true,
+                            probability: self.vad.last_probability(),
+                        });
+                        self.utterance_start = Some(sample.max(self.buffer_start));
+                        self.last_interim = Instant::now();
+                    }
+                    VadEvent::SpeechEnd { sample } => {
+                        eprintln!("{name} speech end at {:.2}s", seconds(sample));
+                        result_callback(CaptionEvent::VadActivity {
+                            speaking: false,
+                            probability: self.vad.last_probability(),
+                        });
+                        if let Some(start) = self.utterance_start.take() {
+                            self.decode(start, sample, true, recognizer, language, result_callback);
+                            self.drop_before(sample);
+                        }
+                    }
+                }
+            }
+
+            let end = self.buffer_start + self.buffer.len();
+            match self.utterance_start {
+                // 超长语音强制切分，避免超出模型的音频窗口
+                Some(start) if end - start >= self.max_utterance_samples => {
+                    self.decode(start, end, true, recognizer, language, result_callback);
+                    self.utterance_start = Some(end);
+                    self.drop_before(end);
+                }
+                Some(start) if self.last_interim.elapsed() >= self.inference_interval => {
+                    self.decode(start, end, false, recognizer, language, result_callback);
+                    self.last_interim = Instant::now();
+                }
+                Some(_) => {}
+                // 静音时只保留回溯所需的音频
+                None => self.drop_before(end.saturating_sub(PRE_ROLL_SAMPLES)),
+            }
+
+            if finished {
+                break;
+            }
+        }
+    }
+
+    fn decode<F>(
+        &mut self,
+        start: usize,
+        end: usize,
+        is_final: bool,
+        recognizer: &mut dyn SpeechRecognizer,
+        language: Option<&str>,
+        result_callback: &mut F,
+    ) where
+        F: FnMut(CaptionEvent),
+    {
+        let id = self.utterance_id;
+        if is_final {
+            self.utterance_id += 1;
+        }
+        let from = start
+            .saturating_sub(self.buffer_start)
+            .min(self.buffer.len());
+        let to = end.saturating_sub(self.buffer_start).min(self.buffer.len());
+        let pcm = &self.buffer[from..to];
+        if pcm.len() < MIN_DECODE_SAMPLES {
+            return;
+        }
+
+        let inference_start = Instant::now();
+        recognizer.set_window_start(self.buffer_start + from);
+        match recognizer.transcribe(pcm, language) {
+            Ok(mut segments) => {
+                let inference_duration = inference_start.elapsed();
+                let audio_duration = (pcm.len() as f32 / 16000.0 * 1000.0) as u128;
+                if segments.is_empty() && is_final {
+                    // 让界面用空结果替换掉之前的临时结果
+                    segments.push(agreement::units_to_segment(&[], id, true, None));
+                }
+                for segment in &mut segments {
+                    segment.shift(seconds(self.buffer_start + from));
+                    segment.reasoning_duration = Some(inference_duration.as_millis());
+                    segment.audio_duration = Some(audio_duration);
+                    if segment.reasoning_lang.is_none() {
+                        segment.reasoning_lang = language.map(|s| s.to_string());
+                    }
+                    segment.id = id;
+                    segment.committed = is_final;
+                }
+                engine::push_committed_context(recognizer, &segments);
+                result_callback(CaptionEvent::Segments { segments });
+            }
+            Err(e) => {
+                eprintln!("{} inference error: {:?}", recognizer.name(), e);
+                result_callback(CaptionEvent::error(CaptionErrorKind::Inference, &e));
+            }
+        }
+    }
+
+    /// Drop buffered audio before stream sample `sample`
+    fn drop_before(&mut self, sample: usize) {
+        let count = sample
+            .saturating_sub(self.buffer_start)
+            .min(self.buffer.len());
+        self.buffer.drain(..count);
+        self.buffer_start += count;
+    }
+}
+
+fn seconds(sample: usize) -> f64 {
+    sample as f64 / SAMPLE_RATE as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::candle_models::whisper::model::Segment;
+
+    const CHUNK: usize = SAMPLE_RATE / 10;
+
+    /// Reports the scripted events, one batch per accepted chunk
+    struct ScriptedVad {
+        events: VecDeque<Vec<VadEvent>>,
+    }
+
+    impl VadEventSource for ScriptedVad {
+        fn accept(&mut self, _pcm: &[f32]) -> anyhow::Result<Vec<VadEvent>> {
+            Ok(self.events.pop_front().unwrap_or_default())
+        }
+
+        fn flush(&mut self) -> Option<VadEvent> {
+            None
+        }
+
+        fn last_probability(&self) -> f32 {
+            0.5
+        }
+    }
+
+    /// Records the decoded windows, the text is the window length
+    #[derive(Default)]
+    struct LengthRecognizer {
+        windows: Vec<(usize, usize)>,
+        window_start: usize,
+        silent: bool,
+    }
+
+    impl SpeechRecognizer for LengthRecognizer {
+        fn name(&self) -> &str {
+            "Length"
+        }
+
+        fn set_window_start(&mut self, sample: usize) {
+            self.window_start = sample;
+        }
+
+        fn transcribe(&mut self, pcm: &[f32], _: Option<&str>) -> anyhow::Result<Vec<Segment>> {
+            self.windows.push((self.window_start, pcm.len()));
+            if self.silent {
+                return Ok(vec![]);
+            }
+            let mut segment = agreement::units_to_segment(&[], 0, false, None);
+            segment.dr.text = pcm.len().to_string();
+            segment.duration = seconds(pcm.len());
+            Ok(vec![segment])
+        }
+    }
+
+    /// Feed `chunks` chunks of audio and collect the reported segments
+    fn run(
+        endpointer: &mut Endpointer,
+        recognizer: &mut LengthRecognizer,
+        chunks: usize,
+    ) -> Vec<Segment> {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..chunks {
+            tx.send(vec![0.0; CHUNK]).unwrap();
+        }
+        drop(tx);
+        let mut segments = vec![];
+        endpointer.run(
+            rx,
+            recognizer,
+            &CancellationToken::new(),
+            None,
+            &mut |event| {
+                if let CaptionEvent::Segments { segments: batch } = event {
+                    segments.extend(batch);
+                }
+            },
+        );
+        segments
+    }
+
+    /// `events[i]` is reported for chunk `i`
+    fn scripted_endpointer(
+        events: Vec<Vec<VadEvent>>,
+        inference_interval: Duration,
+        max_utterance_samples: usize,
+    ) -> Endpointer {
+        let vad = ScriptedVad {
+            events: events.into(),
+        };
+        Endpointer::new(Box::new(vad), inference_interval, max_utterance_samples)
+    }
+
+    #[test]
+    fn test_interim_decode_every_interval() {
+        let events = vec![vec![VadEvent::SpeechStart { sample: 0 }]];
+        let mut endpointer = scripted_endpointer(events, Duration::ZERO, usize::MAX);
+        let mut recognizer = LengthRecognizer::default();
+        let segments = run(&mut endpointer, &mut recognizer, 3);
+
+        // 每个 chunk 之后一次，音频结束时再一次
+        let lengths: Vec<usize> = recognizer.windows.iter().map(|w| w.1).collect();
+        assert_eq!(lengths, vec![CHUNK, 2 * CHUNK, 3 * CHUNK, 3 * CHUNK]);
+        assert!(segments.iter().all(|s| s.id == 1 && !s.committed));
+
+        // 间隔未到时不做临时识别
+        let events = vec![vec![VadEvent::SpeechStart { sample: 0 }]];
+        let mut endpointer = scripted_endpointer(events, Duration::from_secs(3600), usize::MAX);
+        let mut recognizer = LengthRecognizer::default();
+        run(&mut endpointer, &mut recognizer, 3);
+        assert!(recognizer.windows.is_empty());
+    }
+
+    #[test]
+    fn test_final_decode_on_speech_end() {
+        let events = vec![
+            vec![VadEvent::SpeechStart { sample: 0 }],
+            vec![],
+            vec![],
+            vec![VadEvent::SpeechEnd { sample: 3 * CHUNK }],
+        ];
+        let mut endpointer = scripted_endpointer(events, Duration::from_secs(3600), usize::MAX);
+        let mut recognizer = LengthRecognizer::default();
+        let segments = run(&mut endpointer, &mut recognizer, 5);
+
+        assert_eq!(recognizer.windows, vec![(0, 3 * CHUNK)]);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].id, 1);
+        assert!(segments[0].committed);
+        assert_eq!(segments[0].dr.text, (3 * CHUNK).to_string());
+        // 已识别的音频被丢弃，之后的静音只保留回溯所需部分
+        assert_eq!(endpointer.buffer_start, 3 * CHUNK);
+        assert_eq!(endpointer.utterance_id, 2);
+    }
+
+    #[test]
+    fn test_long_utterance_is_split() {
+        let events = vec![vec![VadEvent::SpeechStart { sample: 0 }]];
+        let mut endpointer = scripted_endpointer(events, Duration::from_secs(3600), 2 * CHUNK);
+        let mut recognizer = LengthRecognizer::default();
+        let segments = run(&mut endpointer, &mut recognizer, 4);
+
+        assert_eq!(
+            recognizer.windows,
+            vec![(0, 2 * CHUNK), (2 * CHUNK, 2 * CHUNK)]
+        );
+        let ids: Vec<u64> = segments.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(segments.iter().all(|s| s.committed));
+        assert_eq!(segments[1].start, seconds(2 * CHUNK));
+    }
+
+    #[test]
+    fn test_empty_final_replaces_interim() {
+        let events = vec![
+            vec![VadEvent::SpeechStart { sample: 0 }],
+            vec![VadEvent::SpeechEnd { sample: 2 * CHUNK }],
+        ];
+        let mut endpointer = scripted_endpointer(events, Duration::from_secs(3600), usize::MAX);
+        let mut recognizer = LengthRecognizer {
+            silent: true,
+            ..Default::default()
+        };
+        let segments = run(&mut endpointer, &mut recognizer, 2);
+
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].id, 1);
+        assert!(segments[0].committed);
+        assert!(segments[0].dr.text.is_empty());
+    }
+}
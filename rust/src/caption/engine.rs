@@ -0,0 +1,772 @@
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio_capture::{self, AudioCapture, AudioCaptureConfig};
+use crate::candle_models::whisper::model::{DecodingResult, Segment};
+use crate::candle_models::whisper::LaunchCaptionParams;
+use crate::caption::agreement::{self, LocalAgreement};
+use crate::caption::broadcast::{self, BroadcastServer};
+use crate::caption::endpointing::Endpointer;
+use crate::caption::obs::{ObsCaptionSink, TextFileSink};
+use crate::caption::recorder::{self, AudioRecorder, TranscriptRecorder};
+use crate::caption::streaming;
+use crate::caption::subtitles::{SubtitleOptions, SubtitleWriter};
+use crate::caption::{CaptionErrorKind, CaptionEvent, CaptionExitReason, SpeechRecognizer};
+use crate::onnx_models::vad::{self, StreamingVad, VadOptions};
+
+/// Run the live caption loop: capture audio, buffer it, gate it with VAD,
+/// manage the history window and feed it to the recognizer created by `load_recognizer`.
+///
+/// Fatal errors are reported as [`CaptionEvent::Error`] followed by [`CaptionEvent::Exit`]
+/// before they are returned.
+pub async fn run_caption<F, L>(
+    params: LaunchCaptionParams,
+    load_recognizer: L,
+    mut result_callback: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(CaptionEvent) + Send + 'static,
+    L: FnOnce(&LaunchCaptionParams) -> anyhow::Result<Box<dyn SpeechRecognizer>>,
+{
+    // 设置音频捕获配置
+    let audio_capture_config = AudioCaptureConfig {
+        device: params.audio_device.clone(),
+        is_input: params.audio_device_is_input.unwrap_or(true),
+        target_sample_rate: 16000,
+        target_channels: 1,
+    };
+    let audio_capture = audio_capture::new_audio_capture(audio_capture_config)
+        .map_err(|e| report_fatal(&mut result_callback, CaptionErrorKind::AudioCapture, e))?;
+    run_caption_with_capture(params, audio_capture, load_recognizer, result_callback).await
+}
+
+/// Same as [`run_caption`] but with a caller provided audio capture.
+/// When the capture closes its channel (e.g. end of a replayed file) the remaining audio
+/// is transcribed and the loop exits.
+pub async fn run_caption_with_capture<F, L>(
+    mut params: LaunchCaptionParams,
+    audio_capture: Box<dyn AudioCapture>,
+    load_recognizer: L,
+    mut result_callback: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(CaptionEvent) + Send + 'static,
+    L: FnOnce(&LaunchCaptionParams) -> anyhow::Result<Box<dyn SpeechRecognizer>>,
+{
+    let local_agreement = params.local_agreement.filter(|n| *n >= 2);
+    // 提交策略需要时间戳来裁剪已提交的音频，即使调用方关闭了时间戳
+    if local_agreement.is_some() {
+        params.with_timestamps = Some(true);
+    }
+
+    // 已提交的字幕边识别边追加到字幕文件
+    let mut subtitle_writer = None;
+    if let Some(path) = params.subtitle_path.as_deref() {
+        let options = SubtitleOptions {
+            language: params.audio_language.clone(),
+            ..Default::default()
+        };
+        match SubtitleWriter::create(path, options) {
+            Ok(writer) => subtitle_writer = Some(writer),
+            Err(e) => {
+                eprintln!("Failed to create subtitle file: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to create subtitle file, subtitles are disabled: {e:#}"),
+                });
+            }
+        }
+    }
+
+    // 录制：音频存档 + JSONL 转写记录
+    let mut audio_recorder = None;
+    let mut transcript_recorder = None;
+    if let Some(path) = params.record_path.as_deref() {
+        let recorders = AudioRecorder::create(path).and_then(|audio| {
+            let transcript = TranscriptRecorder::create(
+                recorder::transcript_path(path),
+                path,
+                params.audio_language.clone(),
+            )?;
+            Ok((audio, transcript))
+        });
+        match recorders {
+            Ok((audio, transcript)) => {
+                audio_recorder = Some(audio);
+                transcript_recorder = Some(transcript);
+            }
+            Err(e) => {
+                eprintln!("Failed to create recording: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to create recording, recording is disabled: {e:#}"),
+                });
+            }
+        }
+    }
+
+    // WebSocket 广播，会话结束时随回调一起释放
+    let mut broadcast_server = None;
+    if let Some(addr) = params.broadcast_addr.as_deref() {
+        let server = broadcast::parse_addr(addr)
+            .with_context(|| format!("invalid broadcast address {addr}"))
+            .and_then(BroadcastServer::start);
+        match server {
+            Ok(server) => broadcast_server = Some(server),
+            Err(e) => {
+                eprintln!("Failed to start broadcast server: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to start broadcast server: {e:#}"),
+                });
+            }
+        }
+    }
+
+    // OBS 输出：文本源文件和 obs-websocket 原生字幕
+    let mut obs_text_sink = None;
+    if let Some(path) = params.obs_text_path.as_deref() {
+        let lines = params.obs_text_lines.unwrap_or(3) as usize;
+        match TextFileSink::create(path, lines) {
+            Ok(sink) => obs_text_sink = Some(sink),
+            Err(e) => {
+                eprintln!("Failed to create OBS text file: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to create OBS text file: {e:#}"),
+                });
+            }
+        }
+    }
+    let mut obs_caption_sink = None;
+    if let Some(url) = params.obs_websocket_url.as_deref() {
+        match ObsCaptionSink::start(url, params.obs_websocket_password.clone()) {
+            Ok(sink) => obs_caption_sink = Some(sink),
+            Err(e) => {
+                eprintln!("Failed to start OBS caption output: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to start OBS caption output: {e:#}"),
+                });
+            }
+        }
+    }
+
+    let mut result_callback = move |event: CaptionEvent| {
+        if let Some(server) = broadcast_server.as_ref() {
+            server.publish(&event);
+        }
+        if let Some(sink) = obs_caption_sink.as_ref() {
+            sink.push_event(&event);
+        }
+        if let Some(sink) = obs_text_sink.as_mut() {
+            if let Err(e) = sink.push_event(&event) {
+                eprintln!("Failed to write OBS text file: {:?}", e);
+            }
+        }
+        if let Some(writer) = subtitle_writer.as_mut() {
+            if let Err(e) = writer.push_event(&event) {
+                eprintln!("Failed to write subtitles: {:?}", e);
+            }
+        }
+        if let Some(transcript) = transcript_recorder.as_mut() {
+            if let Err(e) = transcript.push_event(&event) {
+                eprintln!("Failed to write transcript: {:?}", e);
+            }
+        }
+        result_callback(event);
+    };
+
+    result_callback(CaptionEvent::Loading { progress: None });
+    let mut recognizer = load_recognizer(&params)
+        .map_err(|e| report_fatal(&mut result_callback, CaptionErrorKind::ModelLoad, e))?;
+    let name = recognizer.name().to_string();
+
+    let LaunchCaptionParams {
+        audio_language,
+        cancel_token,
+        whisper_max_audio_duration,
+        inference_interval_ms,
+        vad_model_path,
+        vad_filters_value,
+        endpoint_silence_ms,
+        ..
+    } = params;
+
+    let audio_info = audio_capture.get_info();
+    eprintln!("{name} Audio capture info: {:?}", audio_info);
+
+    // 开始音频捕获
+    let rx = audio_capture
+        .start_capture(cancel_token.child_token())
+        .map_err(|e| report_fatal(&mut result_callback, CaptionErrorKind::AudioCapture, e))?;
+    let (rx, recording) = match audio_recorder {
+        Some(audio_recorder) => {
+            let (rx, handle) = recorder::record_audio(rx, audio_recorder);
+            (rx, Some(handle))
+        }
+        None => (rx, None),
+    };
+
+    result_callback(CaptionEvent::Ready {
+        backend: name.clone(),
+        device: recognizer.device(),
+        providers: recognizer.providers(),
+    });
+    eprintln!("{name} Ready...");
+
+    // 初始化音频处理状态
+    let mut buffered_pcm = vec![];
+    let mut history_pcm = Vec::new();
+    let mut last_inference_time = Instant::now();
+    let mut first_inference_done = false;
+    let mut audio_received = false;
+    let mut capture_finished = false;
+    let inference_interval = Duration::from_millis(inference_interval_ms.unwrap_or(2000)); // 默认2000毫秒
+    let max_audio_duration: usize = whisper_max_audio_duration.unwrap_or(12) as usize; // 默认12秒
+    let language = audio_language.as_deref();
+    let mut agreement = local_agreement.map(LocalAgreement::new);
+    let mut next_segment_id: u64 = 1;
+    // 最近一次推理的暂定结果，结束时提交的文本沿用它的语言、置信度和标签
+    let mut last_tentative: Option<Segment> = None;
+    let mut timeline_samples: usize = 0; // 已收到的采样数（含 VAD 丢弃的静音），用于换算绝对时间
+
+    // 流式模型逐块解码并保留编码器缓存，不需要 VAD 和历史窗口
+    if let Some(streaming) = recognizer.as_streaming() {
+        streaming::run_streaming(
+            &name,
+            streaming,
+            rx,
+            &cancel_token,
+            language,
+            &mut result_callback,
+        );
+        eprintln!("{name} transcription stopped");
+        finish_recording(recording);
+        result_callback(CaptionEvent::Exit {
+            reason: exit_reason(&cancel_token),
+        });
+        eprintln!("{name} Exit");
+        return Ok(());
+    }
+
+    eprintln!("Check and loading VAD model...");
+    let mut vad_model = if let Some(vad_model_path) = vad_model_path {
+        // try_with_gpu: [false] vad model is small enough to run on cpu
+        match vad::new_vad_model(vad_model_path, false) {
+            Ok(model) => Some(model),
+            Err(e) => {
+                eprintln!("Failed to load VAD model: {:?}", e);
+                result_callback(CaptionEvent::Warning {
+                    msg: format!("Failed to load VAD model, VAD is disabled: {e:#}"),
+                });
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // VAD 断句模式：按语音起止推理，不再按固定间隔重复识别历史音频
+    if let Some(silence_ms) = endpoint_silence_ms {
+        match vad_model.take() {
+            Some(vad_model) => {
+                let vad = StreamingVad::new(
+                    vad_model,
+                    VadOptions {
+                        threshold: vad_filters_value.unwrap_or(0.5),
+                        min_silence_duration_ms: silence_ms,
+                        speech_pad_ms: 200, // 给识别留出前后余量
+                        ..Default::default()
+                    },
+                );
+                Endpointer::new(
+                    Box::new(vad),
+                    inference_interval,
+                    max_audio_duration * 16000,
+                )
+                .run(
+                    rx,
+                    recognizer.as_mut(),
+                    &cancel_token,
+                    language,
+                    &mut result_callback,
+                );
+                eprintln!("{name} transcription stopped");
+                finish_recording(recording);
+                result_callback(CaptionEvent::Exit {
+                    reason: exit_reason(&cancel_token),
+                });
+                eprintln!("{name} Exit");
+                return Ok(());
+            }
+            None => {
+                eprintln!("VAD endpointing requires a VAD model, using inference interval");
+                result_callback(CaptionEvent::Warning {
+                    msg: "VAD endpointing requires a VAD model, using inference interval"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    // 音频处理主循环
+    eprintln!("Starting {name} audio processing loop...");
+    let mut debug_counter = 0;
+
+    while !cancel_token.is_cancelled() {
+        debug_counter += 1;
+        if debug_counter % 500 == 0 {
+            eprintln!(
+                "{name} audio processing loop iteration {}, buffered_pcm.len(): {}",
+                debug_counter,
+                buffered_pcm.len()
+            );
+        }
+
+        // 尝试接收音频数据，设置超时以便定期检查取消状态
+        let pcm = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(pcm) => pcm,
+            Err(RecvTimeoutError::Disconnected) => {
+                // 音频源已结束（如文件回放完毕），处理剩余音频后退出
+                eprintln!("{name} audio capture finished");
+                capture_finished = true;
+                vec![]
+            }
+            Err(err) => {
+                if debug_counter % 1000 == 0 {
+                    eprintln!(
+                        "Audio recv timeout or error: {:?}, cancel_token cancelled: {}",
+                        err,
+                        cancel_token.is_cancelled()
+                    );
+                }
+                if cancel_token.is_cancelled() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if !audio_received && !pcm.is_empty() {
+            eprintln!("{name} first audio data received: {} samples", pcm.len());
+            audio_received = true;
+        } else if !pcm.is_empty() && debug_counter % 100 == 0 {
+            eprintln!(
+                "{name} audio data: {} samples (debug every 100 iterations)",
+                pcm.len()
+            );
+        }
+
+        timeline_samples += pcm.len();
+        buffered_pcm.extend_from_slice(&pcm);
+
+        if !buffered_pcm.is_empty() && (buffered_pcm.len() % 16000 == 0 || debug_counter % 200 == 0)
+        {
+            eprintln!(
+                "Total buffered_pcm length: {} samples ({:.1}s)",
+                buffered_pcm.len(),
+                buffered_pcm.len() as f32 / 16000.0
+            );
+        }
+
+        let now = Instant::now();
+        if capture_finished {
+            if buffered_pcm.is_empty() {
+                break;
+            }
+        } else {
+            // 首次启动时，等待3秒数据
+            if !first_inference_done {
+                if buffered_pcm.len() < 3 * 16000 {
+                    continue;
+                }
+                first_inference_done = true;
+            }
+
+            // 检查距离上次推理的时间是否小于设定间隔
+            if now.duration_since(last_inference_time) < inference_interval {
+                continue;
+            }
+        }
+
+        // 记录推理开始时间
+        let inference_start = Instant::now();
+
+        // VAD检测
+        if let Some(vad_model) = vad_model.as_mut() {
+            match vad_model.check_vad(buffered_pcm.clone(), vad_filters_value) {
+                Err(e) => {
+                    eprintln!("VAD error: {:?}", e);
+                    result_callback(CaptionEvent::error(CaptionErrorKind::Vad, &e));
+                }
+                Ok(vad_result) => {
+                    eprintln!(
+                        "{name} VAD prediction: {:?} filtered_count: {:?}",
+                        vad_result.prediction, vad_result.filtered_count
+                    );
+                    let speaking = vad_result.prediction > vad_filters_value.unwrap_or(0.1);
+                    result_callback(CaptionEvent::VadActivity {
+                        speaking,
+                        probability: vad_result.prediction,
+                    });
+                    if speaking {
+                        buffered_pcm = vad_result.pcm_results;
+                    } else {
+                        buffered_pcm.clear();
+                        last_inference_time = Instant::now();
+                        if capture_finished {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+            }
+        }
+
+        // 合并历史数据和新数据，超出最大长度时丢弃最旧的历史数据
+        let pcm = merge_history(&history_pcm, &buffered_pcm, max_audio_duration * 16000);
+        let window_start_sample = window_start_sample(timeline_samples, pcm.len());
+        history_pcm = pcm.clone();
+        buffered_pcm.clear();
+
+        recognizer.set_window_start(window_start_sample);
+        match recognizer.transcribe(&pcm, language) {
+            Ok(segments) => {
+                let mut segments = match agreement.as_mut() {
+                    None => segments,
+                    Some(agreement) => {
+                        let offset = window_start_sample as f64 / 16000.0;
+                        let window = segments.last().cloned();
+                        let template = window.as_ref().map(|s| s.dr.clone());
+                        let (committed, tentative) =
+                            agreement.insert(agreement::hypothesis_units(&segments, offset));
+                        // 已提交的音频不再需要重新识别
+                        if let Some(end) = agreement.committed_end() {
+                            let keep_from = ((end * 16000.0) as usize)
+                                .saturating_sub(window_start_sample)
+                                .min(history_pcm.len());
+                            history_pcm.drain(..keep_from);
+                        }
+                        let mut segments = agreement_segments(
+                            &committed,
+                            &tentative,
+                            &mut next_segment_id,
+                            template.as_ref(),
+                        );
+                        // 保留本窗口检测到的语言和标签
+                        if let Some(window) = &window {
+                            for segment in &mut segments {
+                                segment.copy_window_tags(window);
+                            }
+                        }
+                        segments
+                    }
+                };
+
+                // 计算推理用时
+                let inference_duration = inference_start.elapsed();
+                let audio_duration = (pcm.len() as f32 / 16000.0 * 1000.0) as u128;
+
+                for segment in &mut segments {
+                    segment.reasoning_duration = Some(inference_duration.as_millis());
+                    segment.audio_duration = Some(audio_duration);
+                    if segment.reasoning_lang.is_none() {
+                        segment.reasoning_lang = language.map(|s| s.to_string());
+                    }
+                }
+
+                if agreement.is_some() {
+                    last_tentative = segments.last().cloned();
+                }
+                push_committed_context(recognizer.as_mut(), &segments);
+                result_callback(CaptionEvent::Segments { segments });
+            }
+            Err(e) => {
+                eprintln!("{name} inference error: {:?}", e);
+                result_callback(CaptionEvent::error(CaptionErrorKind::Inference, &e));
+            }
+        }
+
+        last_inference_time = now;
+        if capture_finished {
+            break;
+        }
+    }
+
+    eprintln!("{name} transcription stopped");
+    // 结束时提交剩余的暂定文本
+    if let Some(agreement) = agreement.as_mut() {
+        let units = agreement.flush();
+        if !units.is_empty() {
+            result_callback(CaptionEvent::Segments {
+                segments: vec![flushed_segment(
+                    &units,
+                    next_segment_id,
+                    last_tentative.as_ref(),
+                )],
+            });
+        }
+    }
+    drop(rx);
+    finish_recording(recording);
+    result_callback(CaptionEvent::Exit {
+        reason: exit_reason(&cancel_token),
+    });
+    eprintln!("{name} Exit");
+    Ok(())
+}
+
+/// Stream position of the first sample of a window that ends with the newest audio.
+/// `received_samples` includes the silence dropped by the VAD, so the newest speech keeps
+/// its place in the stream after a pause.
+fn window_start_sample(received_samples: usize, window_len: usize) -> usize {
+    received_samples.saturating_sub(window_len)
+}
+
+/// Feed committed text to the recognizer as context for the following windows
+pub(crate) fn push_committed_context(recognizer: &mut dyn SpeechRecognizer, segments: &[Segment]) {
+    for segment in segments.iter().filter(|s| s.committed) {
+        if let Err(e) = recognizer.push_context(&segment.dr.text) {
+            eprintln!("{} context error: {:?}", recognizer.name(), e);
+        }
+    }
+}
+
+/// Wait until the recorder stage has finalized the audio archive
+fn finish_recording(recording: Option<std::thread::JoinHandle<()>>) {
+    if let Some(handle) = recording {
+        if handle.join().is_err() {
+            eprintln!("Audio recorder thread panicked");
+        }
+    }
+}
+
+/// Append `buffered_pcm` to `history_pcm`, dropping the oldest history samples
+/// so the result does not exceed `max_samples` (new audio is always kept).
+fn merge_history(history_pcm: &[f32], buffered_pcm: &[f32], max_samples: usize) -> Vec<f32> {
+    let total_len = history_pcm.len() + buffered_pcm.len();
+    let excess = total_len.saturating_sub(max_samples);
+    if excess > 0 {
+        eprintln!(
+            "history_pcm len: {} buffered_pcm len: {} excess: {}",
+            history_pcm.len(),
+            buffered_pcm.len(),
+            excess
+        );
+    }
+    // 如果history_pcm不够减，则不使用history_pcm
+    let history_pcm = history_pcm.get(excess..).unwrap_or_default();
+
+    let mut combined_pcm = Vec::with_capacity(history_pcm.len() + buffered_pcm.len());
+    combined_pcm.extend_from_slice(history_pcm);
+    combined_pcm.extend_from_slice(buffered_pcm);
+    combined_pcm
+}
+
+/// Segments for one inference in LocalAgreement mode: the newly committed text (if any) gets a
+/// new id, the tentative tail carries the id the next committed segment will use.
+fn agreement_segments(
+    committed: &[agreement::Unit],
+    tentative: &[agreement::Unit],
+    next_segment_id: &mut u64,
+    template: Option<&DecodingResult>,
+) -> Vec<Segment> {
+    let mut segments = vec![];
+    if !committed.is_empty() {
+        segments.push(agreement::units_to_segment(
+            committed,
+            *next_segment_id,
+            true,
+            template,
+        ));
+        *next_segment_id += 1;
+    }
+    segments.push(agreement::units_to_segment(
+        tentative,
+        *next_segment_id,
+        false,
+        template,
+    ));
+    segments
+}
+
+/// Committed segment of the text left at the end of the session, it keeps the tags and
+/// timings of the last window like the segments committed during the session
+fn flushed_segment(units: &[agreement::Unit], id: u64, last: Option<&Segment>) -> Segment {
+    let mut segment = agreement::units_to_segment(units, id, true, last.map(|s| &s.dr));
+    if let Some(last) = last {
+        segment.copy_window_tags(last);
+        segment.reasoning_duration = last.reasoning_duration;
+        segment.audio_duration = last.audio_duration;
+    }
+    segment
+}
+
+/// Report a fatal error as `Error` + `Exit` events, returns the error for `?`
+fn report_fatal<F>(
+    result_callback: &mut F,
+    kind: CaptionErrorKind,
+    err: anyhow::Error,
+) -> anyhow::Error
+where
+    F: FnMut(CaptionEvent),
+{
+    eprintln!("Caption error ({:?}): {:?}", kind, err);
+    result_callback(CaptionEvent::error(kind, &err));
+    result_callback(CaptionEvent::Exit {
+        reason: CaptionExitReason::Error,
+    });
+    err
+}
+
+fn exit_reason(cancel_token: &CancellationToken) -> CaptionExitReason {
+    if cancel_token.is_cancelled() {
+        CaptionExitReason::Cancelled
+    } else {
+        CaptionExitReason::EndOfStream
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_capture::{SyntheticAudioCapture, SyntheticSignal};
+    use std::sync::{Arc, Mutex};
+
+    /// Returns the number of received samples as text
+    struct SampleCountRecognizer;
+
+    impl SpeechRecognizer for SampleCountRecognizer {
+        fn name(&self) -> &str {
+            "SampleCount"
+        }
+
+        fn transcribe(&mut self, pcm: &[f32], _: Option<&str>) -> anyhow::Result<Vec<Segment>> {
+            let units = agreement::split_units(
+                &pcm.len().to_string(),
+                0.0,
+                pcm.len() as f64 / 16000.0,
+                false,
+            );
+            Ok(vec![agreement::units_to_segment(&units, 0, false, None)])
+        }
+    }
+
+    #[test]
+    fn test_flushed_segment_keeps_window_tags() {
+        let units = agreement::split_units("see you", 1.0, 2.0, false);
+        let mut last = agreement::units_to_segment(&units, 3, false, None);
+        last.dr.avg_logprob = -0.25;
+        last.reasoning_lang = Some("en".to_string());
+        last.emotion = Some("happy".to_string());
+        last.reasoning_duration = Some(120);
+        last.audio_duration = Some(4000);
+
+        let segment = flushed_segment(&units, 3, Some(&last));
+        assert!(segment.committed);
+        assert_eq!(segment.dr.text, "see you");
+        assert_eq!(segment.dr.avg_logprob, -0.25);
+        assert_eq!(segment.reasoning_lang.as_deref(), Some("en"));
+        assert_eq!(segment.emotion.as_deref(), Some("happy"));
+        assert_eq!(segment.reasoning_duration, Some(120));
+        assert_eq!(segment.audio_duration, Some(4000));
+    }
+
+    #[test]
+    fn test_window_times_count_dropped_silence() {
+        let mut agreement = LocalAgreement::new(2);
+        let mut timeline_samples = 0;
+
+        // 3 秒语音，两次推理得到相同的假设
+        timeline_samples += 3 * 16000;
+        let history = merge_history(&[], &[0.0; 3 * 16000], 12 * 16000);
+        let start = window_start_sample(timeline_samples, history.len());
+        assert_eq!(start, 0);
+        let hello = agreement::units_to_segment(
+            &agreement::split_units("hello", 0.0, 3.0, true),
+            0,
+            false,
+            None,
+        );
+        for _ in 0..2 {
+            let offset = start as f64 / 16000.0;
+            agreement.insert(agreement::hypothesis_units(
+                std::slice::from_ref(&hello),
+                offset,
+            ));
+        }
+
+        // VAD 丢弃 2 秒静音，之后又有 1 秒语音
+        timeline_samples += 2 * 16000;
+        timeline_samples += 16000;
+        let window = merge_history(&history, &[0.0; 16000], 12 * 16000);
+        let start = window_start_sample(timeline_samples, window.len());
+        assert_eq!(start, 2 * 16000);
+
+        // 新的语音在窗口末尾，对应流中的 5..6 秒
+        let world = agreement::units_to_segment(
+            &agreement::split_units("world", 3.0, 4.0, true),
+            0,
+            false,
+            None,
+        );
+        let offset = start as f64 / 16000.0;
+        let (_, tentative) = agreement.insert(agreement::hypothesis_units(&[world], offset));
+        assert_eq!(tentative.len(), 1);
+        assert_eq!(tentative[0].text.trim(), "world");
+        assert_eq!((tentative[0].start, tentative[0].end), (5.0, 6.0));
+    }
+
+    #[tokio::test]
+    async fn test_run_caption_with_synthetic_capture() {
+        let capture = SyntheticAudioCapture::with_options(
+            AudioCaptureConfig::default(),
+            SyntheticSignal::Tone {
+                frequency: 440.0,
+                amplitude: 0.5,
+            },
+            Some(Duration::from_secs(4)),
+            0.0,
+        )
+        .unwrap();
+
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let results_clone = results.clone();
+        run_caption_with_capture(
+            LaunchCaptionParams::default(),
+            Box::new(capture),
+            |_| Ok(Box::new(SampleCountRecognizer)),
+            move |event| results_clone.lock().unwrap().push(event),
+        )
+        .await
+        .unwrap();
+
+        let results = results.lock().unwrap();
+        assert!(matches!(
+            results.first().unwrap(),
+            CaptionEvent::Loading { .. }
+        ));
+        assert!(matches!(
+            &results[1],
+            CaptionEvent::Ready { backend, .. } if backend == "SampleCount"
+        ));
+        assert!(matches!(
+            results.last().unwrap(),
+            CaptionEvent::Exit {
+                reason: CaptionExitReason::EndOfStream
+            }
+        ));
+
+        // 合成音频结束后，剩余的 4 秒音频应在一次推理中处理完
+        let working: Vec<_> = results
+            .iter()
+            .flat_map(|event| match event {
+                CaptionEvent::Segments { segments } => segments.iter().collect(),
+                _ => vec![],
+            })
+            .collect();
+        assert_eq!(working.len(), 1);
+        assert_eq!(working[0].dr.text, "64000");
+        assert_eq!(working[0].audio_duration, Some(4000));
+    }
+}
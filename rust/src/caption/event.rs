@@ -0,0 +1,74 @@
+use serde::Serialize;
+
+use crate::candle_models::whisper::model::Segment;
+
+/// Event streamed by the live caption loop to the UI
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CaptionEvent {
+    /// The recognizer is being loaded, `progress` is in `0.0..=1.0` when known
+    Loading {
+        progress: Option<f32>,
+    },
+    /// Model loaded and audio capture started
+    Ready {
+        backend: String,
+        /// Compute device, e.g. "cpu", "cuda", "metal"
+        device: String,
+        /// ONNX Runtime execution providers that were registered (empty for candle)
+        providers: Vec<String>,
+    },
+    /// Transcription result of one inference
+    Segments {
+        segments: Vec<Segment>,
+    },
+    /// Speech probability of the latest VAD check
+    VadActivity {
+        speaking: bool,
+        probability: f32,
+    },
+    /// Something went wrong but the caption loop keeps running
+    Warning {
+        msg: String,
+    },
+    Error {
+        kind: CaptionErrorKind,
+        msg: String,
+    },
+    /// Always the last event of a caption session
+    Exit {
+        reason: CaptionExitReason,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionErrorKind {
+    ModelLoad,
+    AudioCapture,
+    Vad,
+    Inference,
+    Internal,
+    /// The caption config failed validation
+    Config,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionExitReason {
+    /// The cancel token was cancelled
+    Cancelled,
+    /// The audio source was finished (e.g. file replay)
+    EndOfStream,
+    /// A fatal error was reported before
+    Error,
+}
+
+impl CaptionEvent {
+    pub(crate) fn error(kind: CaptionErrorKind, err: &anyhow::Error) -> Self {
+        CaptionEvent::Error {
+            kind,
+            msg: format!("{err:#}"),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::Result;
+
+use crate::audio_capture::decoder;
+use crate::candle_models::whisper::model::Segment;
+use crate::candle_models::whisper::LaunchCaptionParams;
+use crate::caption::{self, SpeechRecognizer};
+
+const SAMPLE_RATE: usize = 16000;
+/// 离线转写时每次送入模型的音频窗口长度（秒），与 Whisper 的 30 秒输入窗口一致
+const WINDOW_SECS: usize = 30;
+/// 短于该长度的尾部窗口直接丢弃（0.1 秒）
+const MIN_WINDOW_SAMPLES: usize = SAMPLE_RATE / 10;
+/// 结束时间离窗口末尾不足该时长的片段视为被截断（秒）
+const COMPLETE_MARGIN_SECS: f64 = 0.01;
+
+/// Decode an audio file and transcribe it with the backend selected by `params.model_type`.
+/// Segment timestamps are relative to the start of the file.
+pub fn transcribe_file(
+    params: &LaunchCaptionParams,
+    path: impl AsRef<Path>,
+) -> Result<Vec<Segment>> {
+    let audio = decoder::decode_audio_file(path.as_ref(), SAMPLE_RATE as u32)?;
+    eprintln!(
+        "Transcribe file: {} ({:.1}s, {}Hz, {} channels)",
+        path.as_ref().display(),
+        audio.duration_secs(),
+        audio.source_sample_rate,
+        audio.source_channels
+    );
+
+    let mut recognizer = caption::new_recognizer(params)?;
+    transcribe_pcm(
+        recognizer.as_mut(),
+        &audio.pcm,
+        params.audio_language.as_deref(),
+    )
+}
+
+/// Transcribe a whole pcm buffer (f32, mono, 16kHz) window by window.
+/// When the recognizer returns timed sub-segments, the next window starts at the end of the
+/// last complete one (like Whisper's seek), so words at the window boundary are not cut.
+pub fn transcribe_pcm(
+    recognizer: &mut dyn SpeechRecognizer,
+    pcm: &[f32],
+    language: Option<&str>,
+) -> Result<Vec<Segment>> {
+    let window_samples = WINDOW_SECS * SAMPLE_RATE;
+    let mut segments = vec![];
+
+    let mut seek = 0;
+    while pcm.len() - seek >= MIN_WINDOW_SAMPLES {
+        let window = &pcm[seek..pcm.len().min(seek + window_samples)];
+        let is_last = seek + window.len() == pcm.len();
+        let offset = seek as f64 / SAMPLE_RATE as f64;
+        let inference_start = Instant::now();
+        let mut window_segments = recognizer.transcribe(window, language)?;
+        let inference_duration = inference_start.elapsed().as_millis();
+        let audio_duration = (window.len() * 1000 / SAMPLE_RATE) as u128;
+
+        let window_secs = window.len() as f64 / SAMPLE_RATE as f64;
+        seek += match (is_last, last_complete_end(&window_segments, window_secs)) {
+            // 丢弃被窗口截断的尾部，从最后一个完整片段的结尾继续
+            (false, Some(end)) => {
+                truncate_segments(&mut window_segments, end);
+                (end * SAMPLE_RATE as f64) as usize
+            }
+            _ => window.len(),
+        };
+
+        for mut segment in window_segments {
+            if segment.dr.text.trim().is_empty() {
+                continue;
+            }
+            // 文件转写的每个窗口都是最终结果
+            recognizer.push_context(&segment.dr.text)?;
+            segment.shift(offset);
+            segment.reasoning_duration = Some(inference_duration);
+            segment.audio_duration = Some(audio_duration);
+            if segment.reasoning_lang.is_none() {
+                segment.reasoning_lang = language.map(|s| s.to_string());
+            }
+            segments.push(segment);
+        }
+    }
+
+    Ok(segments)
+}
+
+/// End (seconds into the window) of the last sub-segment closed by a timestamp. Text after
+/// the last timestamp runs to the end of the window and is not complete.
+/// None when the segments are not timed or nothing is complete.
+fn last_complete_end(segments: &[Segment], window_secs: f64) -> Option<f64> {
+    if segments.is_empty() || segments.iter().any(|s| s.sub_segments.is_empty()) {
+        return None;
+    }
+    segments
+        .iter()
+        .flat_map(|s| &s.sub_segments)
+        .map(|sub_segment| sub_segment.end)
+        .filter(|&end| end > 0.0 && end < window_secs - COMPLETE_MARGIN_SECS)
+        .max_by(f64::total_cmp)
+}
+
+/// Keep the sub-segments that end by `end`, the text of the segments follows them
+fn truncate_segments(segments: &mut Vec<Segment>, end: f64) {
+    for segment in segments.iter_mut() {
+        let count = segment.sub_segments.len();
+        segment
+            .sub_segments
+            .retain(|sub_segment| sub_segment.end <= end);
+        if segment.sub_segments.len() != count {
+            segment.dr.text = segment
+                .sub_segments
+                .iter()
+                .map(|sub_segment| sub_segment.text.as_str())
+                .collect();
+            segment.duration = (end - segment.start).max(0.0);
+        }
+    }
+    segments.retain(|segment| !segment.sub_segments.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candle_models::whisper::model::SubSegment;
+    use crate::caption::agreement;
+
+    /// Timestamps every second of the window, the last second is cut by the window end
+    struct SecondsRecognizer {
+        window_lengths: Vec<usize>,
+    }
+
+    impl SpeechRecognizer for SecondsRecognizer {
+        fn name(&self) -> &str {
+            "Seconds"
+        }
+
+        fn transcribe(&mut self, pcm: &[f32], _: Option<&str>) -> Result<Vec<Segment>> {
+            self.window_lengths.push(pcm.len());
+            let secs = pcm.len() as f64 / SAMPLE_RATE as f64;
+            let mut segment = agreement::units_to_segment(&[], 0, false, None);
+            segment.duration = secs;
+            segment.sub_segments = (0..secs.ceil() as usize)
+                .map(|i| SubSegment {
+                    start: i as f64,
+                    end: (i as f64 + 1.0).min(secs),
+                    text: format!(" {i}"),
+                    words: vec![],
+                })
+                .collect();
+            segment.dr.text = segment
+                .sub_segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect();
+            Ok(vec![segment])
+        }
+    }
+
+    #[test]
+    fn test_transcribe_pcm_seeks_to_last_complete_sub_segment() {
+        let mut recognizer = SecondsRecognizer {
+            window_lengths: vec![],
+        };
+        // 45.5 秒：第一个窗口的最后一秒未闭合，第二个窗口从 29 秒开始
+        let pcm = vec![0.0; SAMPLE_RATE * 91 / 2];
+        let segments = transcribe_pcm(&mut recognizer, &pcm, None).unwrap();
+
+        assert_eq!(
+            recognizer.window_lengths,
+            vec![WINDOW_SECS * SAMPLE_RATE, SAMPLE_RATE * 33 / 2]
+        );
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].sub_segments.len(), 29);
+        assert_eq!(segments[0].sub_segments.last().unwrap().end, 29.0);
+        assert_eq!(segments[1].start, 29.0);
+        assert_eq!(segments[1].sub_segments.last().unwrap().end, 45.5);
+    }
+}
@@ -0,0 +1,33 @@
+pub mod agreement;
+pub mod broadcast;
+pub mod config;
+pub mod endpointing;
+pub mod engine;
+pub mod event;
+pub mod file;
+pub mod model_files;
+pub mod obs;
+pub mod recorder;
+pub mod streaming;
+pub mod subtitles;
+pub mod traits;
+pub mod transcription_server;
+
+pub use config::*;
+pub use event::*;
+pub use traits::*;
+
+use crate::candle_models;
+use crate::candle_models::whisper::LaunchCaptionParams;
+use crate::onnx_models;
+
+/// Create the recognizer for `params.model_type` ("*_onnx" types go to onnx runtime, others to candle)
+pub fn new_recognizer(params: &LaunchCaptionParams) -> anyhow::Result<Box<dyn SpeechRecognizer>> {
+    if params.model_type.ends_with("_onnx") {
+        onnx_models::new_recognizer(params)
+    } else {
+        Ok(Box::new(candle_models::whisper::WhisperRecognizer::new(
+            params,
+        )?))
+    }
+}
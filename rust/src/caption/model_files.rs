@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::candle_models::whisper::LaunchCaptionParams;
+
+/// 多语言 Whisper 的词表包含语言 token，纯英文模型为 51864
+const MULTILINGUAL_VOCAB_SIZE: u64 = 51865;
+
+/// Model inputs of a caption session, the same data the Flutter app passes as `WhisperClient`
+#[derive(Debug, Clone, Default)]
+pub struct ModelFiles {
+    pub models: HashMap<String, String>,
+    pub config_data: String,
+    pub tokenizer_data: Vec<u8>,
+    pub is_multilingual: bool,
+    pub is_quantized: bool,
+    pub model_type: String,
+}
+
+impl ModelFiles {
+    /// Load the model files of `model_type` from `dir`:
+    /// - `whisper`: `model.safetensors` or a `*.gguf` file, `config.json` and `tokenizer.json`
+    /// - `sense-voice_onnx`: a `*.onnx` file and `tokens.txt`
+    /// - `whisper-olive_onnx`: a `*.onnx` file, `tokenizer.json` (optional, for prompting)
+    /// - `zipformer_onnx`: `encoder*.onnx`, `decoder*.onnx`, `joiner*.onnx` and `tokens.txt`
+    pub fn from_dir(dir: impl AsRef<Path>, model_type: &str) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut files = Self {
+            model_type: model_type.to_string(),
+            ..Default::default()
+        };
+        let model_path = match model_type {
+            "whisper" => {
+                let safetensors = dir.join("model.safetensors");
+                let model_path = if safetensors.exists() {
+                    safetensors
+                } else {
+                    find_file(dir, "gguf")?.with_context(|| {
+                        format!("no model.safetensors or *.gguf in {}", dir.display())
+                    })?
+                };
+                files.is_quantized = model_path.extension().is_some_and(|ext| ext == "gguf");
+                files.config_data = read_to_string(&dir.join("config.json"))?;
+                files.tokenizer_data = read(&dir.join("tokenizer.json"))?;
+                let config: serde_json::Value = serde_json::from_str(&files.config_data)?;
+                files.is_multilingual =
+                    config["vocab_size"].as_u64().unwrap_or(0) >= MULTILINGUAL_VOCAB_SIZE;
+                model_path
+            }
+            "sense-voice_onnx" | "whisper-olive_onnx" => {
+                if model_type == "sense-voice_onnx" {
+                    files.tokenizer_data = read(&dir.join("tokens.txt"))?;
+                } else if dir.join("tokenizer.json").exists() {
+                    files.tokenizer_data = read(&dir.join("tokenizer.json"))?;
+                }
+                files.is_multilingual = true;
+                files.is_quantized = true;
+                find_file(dir, "onnx")?
+                    .with_context(|| format!("no *.onnx model in {}", dir.display()))?
+            }
+            "zipformer_onnx" => {
+                files.tokenizer_data = read(&dir.join("tokens.txt"))?;
+                let onnx_files = find_files(dir, "onnx")?;
+                for part in ["encoder", "decoder", "joiner"] {
+                    // 按文件名排序，同时存在时 *.int8.onnx 排在前面
+                    let path = onnx_files
+                        .iter()
+                        .find(|path| file_name(path).contains(part))
+                        .with_context(|| format!("no {part}*.onnx in {}", dir.display()))?;
+                    files.is_quantized |= file_name(path).contains("int8");
+                    files
+                        .models
+                        .insert(file_name(path), path.to_string_lossy().to_string());
+                }
+                return Ok(files);
+            }
+            _ => bail!("Unsupported model type: {model_type}"),
+        };
+
+        files.models.insert(
+            file_name(&model_path),
+            model_path.to_string_lossy().to_string(),
+        );
+        Ok(files)
+    }
+
+    /// Fill the model fields of `params`
+    pub fn into_params(self, params: LaunchCaptionParams) -> LaunchCaptionParams {
+        LaunchCaptionParams {
+            models: self.models,
+            config_data: self.config_data,
+            model_type: self.model_type,
+            is_quantized: self.is_quantized,
+            tokenizer_data: self.tokenizer_data,
+            is_multilingual: Some(self.is_multilingual),
+            ..params
+        }
+    }
+}
+
+/// First file in `dir` (by name) with the given extension
+fn find_file(dir: &Path, extension: &str) -> Result<Option<PathBuf>> {
+    Ok(find_files(dir, extension)?.into_iter().next())
+}
+
+/// Files in `dir` with the given extension, sorted by name
+fn find_files(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read model dir {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == extension))
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn read_to_string(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
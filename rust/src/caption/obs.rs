@@ -0,0 +1,403 @@
+//! Outputs for OBS and other streaming tools.
+//!
+//! - [`TextFileSink`] keeps the last N caption lines in a text file for OBS Text
+//!   (GDI+ / FreeType 2) sources, replaced atomically so OBS never reads a partial file.
+//! - [`ObsCaptionSink`] sends the final caption text to the native closed-caption track of
+//!   the stream through obs-websocket v5 (`SendStreamCaption`).
+//!
+//! Final text is the committed segments (LocalAgreement / VAD endpointing); without a commit
+//! policy every inference is final.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::candle_models::whisper::model::Segment;
+use crate::caption::CaptionEvent;
+
+/// obs-websocket 默认地址
+pub const DEFAULT_OBS_WEBSOCKET_URL: &str = "ws://127.0.0.1:4455";
+const RPC_VERSION: u64 = 1;
+/// 连接 OBS 失败后的重试间隔
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Only plain `ws://` is supported, obs-websocket doesn't serve TLS itself
+pub fn is_valid_url(url: &str) -> bool {
+    url.strip_prefix("ws://")
+        .is_some_and(|rest| !rest.is_empty())
+}
+
+/// Text of the segments that will not change any more, None if there is none
+fn final_text(segments: &[Segment]) -> Option<String> {
+    // id 为 0 表示没有提交策略，每次推理的结果就是最终结果
+    let no_commit_policy = segments.iter().all(|s| s.id == 0 && !s.committed);
+    let text = segments
+        .iter()
+        .filter(|s| s.committed || no_commit_policy)
+        .map(|s| s.dr.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Rolling "last N lines" caption file
+pub struct TextFileSink {
+    path: PathBuf,
+    max_lines: usize,
+    lines: VecDeque<String>,
+    /// Text that may still change, shown after the committed lines
+    tentative: String,
+    written: Option<String>,
+}
+
+impl TextFileSink {
+    /// Create (or clear) the file at `path`, keeping at most `max_lines` lines (at least 1)
+    pub fn create(path: impl AsRef<Path>, max_lines: usize) -> Result<Self> {
+        let mut sink = Self {
+            path: path.as_ref().to_path_buf(),
+            max_lines: max_lines.max(1),
+            lines: VecDeque::new(),
+            tentative: String::new(),
+            written: None,
+        };
+        sink.write()?;
+        Ok(sink)
+    }
+
+    pub fn push_event(&mut self, event: &CaptionEvent) -> Result<()> {
+        let CaptionEvent::Segments { segments } = event else {
+            return Ok(());
+        };
+        // 空结果（如 VAD 断句的空临时结果）无法判断提交模式，保持当前内容
+        if segments.is_empty() {
+            return Ok(());
+        }
+        let no_commit_policy = segments.iter().all(|s| s.id == 0 && !s.committed);
+        if no_commit_policy {
+            // 每次推理都重新识别整个窗口，只显示最新结果
+            self.lines.clear();
+            self.tentative.clear();
+        }
+        if let Some(text) = final_text(segments) {
+            self.lines.push_back(text);
+        }
+        if !no_commit_policy {
+            self.tentative = segments
+                .iter()
+                .filter(|s| !s.committed)
+                .map(|s| s.dr.text.trim())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        self.write()
+    }
+
+    fn content(&self) -> String {
+        let mut lines: Vec<&str> = self.lines.iter().map(String::as_str).collect();
+        if !self.tentative.trim().is_empty() {
+            lines.push(self.tentative.trim());
+        }
+        let skip = lines.len().saturating_sub(self.max_lines);
+        lines[skip..].join("\n")
+    }
+
+    /// Write to a temporary file and rename it over the target
+    fn write(&mut self) -> Result<()> {
+        let excess = self.lines.len().saturating_sub(self.max_lines);
+        self.lines.drain(..excess);
+        let content = self.content();
+        if self.written.as_ref() == Some(&content) {
+            return Ok(());
+        }
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, &content)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))?;
+        self.written = Some(content);
+        Ok(())
+    }
+}
+
+/// Sends final caption text to OBS with `SendStreamCaption`.
+///
+/// Runs on its own thread, connects (and reconnects) in the background so captioning is not
+/// blocked while OBS is closed. Captions produced while disconnected are dropped.
+/// OBS only forwards them to the stream output while streaming.
+pub struct ObsCaptionSink {
+    tx: Option<mpsc::UnboundedSender<String>>,
+    cancel_token: CancellationToken,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ObsCaptionSink {
+    /// `url` is the obs-websocket address such as [`DEFAULT_OBS_WEBSOCKET_URL`],
+    /// `password` is required when authentication is enabled in OBS
+    pub fn start(url: &str, password: Option<String>) -> Result<Self> {
+        if !is_valid_url(url) {
+            bail!("unsupported obs-websocket url {url}, use ws://host:port");
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let cancel_token = CancellationToken::new();
+        let thread = {
+            let url = url.to_string();
+            let cancel_token = cancel_token.clone();
+            std::thread::Builder::new()
+                .name("obs-caption".to_string())
+                .spawn(move || runtime.block_on(run_obs_client(url, password, rx, cancel_token)))?
+        };
+        Ok(Self {
+            tx: Some(tx),
+            cancel_token,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn push_event(&self, event: &CaptionEvent) {
+        if let CaptionEvent::Segments { segments } = event {
+            if let Some(text) = final_text(segments) {
+                self.send_caption(text);
+            }
+        }
+    }
+
+    pub fn send_caption(&self, text: String) {
+        if let Some(tx) = self.tx.as_ref() {
+            let _ = tx.send(text);
+        }
+    }
+}
+
+impl Drop for ObsCaptionSink {
+    fn drop(&mut self) {
+        // 关闭通道，已连接时发完剩余字幕后退出；取消只中断连接和重连等待
+        self.tx.take();
+        self.cancel_token.cancel();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+type ObsSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn run_obs_client(
+    url: String,
+    password: Option<String>,
+    mut rx: mpsc::UnboundedReceiver<String>,
+    cancel_token: CancellationToken,
+) {
+    loop {
+        let connected = tokio::select! {
+            _ = cancel_token.cancelled() => return,
+            connected = connect_obs(&url, password.as_deref()) => connected,
+        };
+        match connected {
+            Ok(mut ws) => {
+                eprintln!("Connected to obs-websocket at {url}");
+                // 连接之前积压的字幕已经过时，丢弃
+                while rx.try_recv().is_ok() {}
+                match send_captions(&mut ws, &mut rx).await {
+                    Ok(()) => {
+                        let _ = ws.close(None).await;
+                        return;
+                    }
+                    Err(e) => eprintln!("obs-websocket connection lost: {:?}", e),
+                }
+            }
+            Err(e) => eprintln!("Failed to connect to obs-websocket at {url}: {:?}", e),
+        }
+        tokio::select! {
+            _ = cancel_token.cancelled() => return,
+            _ = tokio::time::sleep(RECONNECT_INTERVAL) => {}
+        }
+    }
+}
+
+/// Connect and identify, see the obs-websocket v5 protocol (Hello / Identify / Identified)
+async fn connect_obs(url: &str, password: Option<&str>) -> Result<ObsSocket> {
+    let (mut ws, _) = tokio_tungstenite::connect_async(url).await?;
+    let hello = read_op(&mut ws, 0).await?;
+    let mut identify = json!({
+        "rpcVersion": RPC_VERSION,
+        "eventSubscriptions": 0,
+    });
+    if let Some(auth) = hello.get("authentication") {
+        let Some(password) = password else {
+            bail!("obs-websocket requires a password");
+        };
+        let challenge = auth["challenge"].as_str().unwrap_or_default();
+        let salt = auth["salt"].as_str().unwrap_or_default();
+        identify["authentication"] = auth_response(password, salt, challenge).into();
+    }
+    let identify = json!({ "op": 1, "d": identify });
+    ws.send(Message::text(identify.to_string())).await?;
+    read_op(&mut ws, 2).await?;
+    Ok(ws)
+}
+
+/// base64(sha256(base64(sha256(password + salt)) + challenge))
+fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let secret = base64.encode(Sha256::digest(format!("{password}{salt}")));
+    base64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+/// Read messages until one with opcode `op` arrives, returns its `d` field
+async fn read_op(ws: &mut ObsSocket, op: u64) -> Result<Value> {
+    while let Some(message) = ws.next().await {
+        let message = message?;
+        if let Message::Close(frame) = &message {
+            // 鉴权失败时 OBS 以 4009 关闭连接
+            bail!("obs-websocket closed the connection: {:?}", frame);
+        }
+        let Ok(text) = message.to_text() else {
+            continue;
+        };
+        let value: Value = serde_json::from_str(text)?;
+        if value["op"].as_u64() == Some(op) {
+            return Ok(value["d"].clone());
+        }
+    }
+    bail!("obs-websocket closed the connection")
+}
+
+/// Forward captions until the channel is closed, returns an error if the connection drops
+async fn send_captions(ws: &mut ObsSocket, rx: &mut mpsc::UnboundedReceiver<String>) -> Result<()> {
+    loop {
+        tokio::select! {
+            text = rx.recv() => {
+                let Some(text) = text else {
+                    return Ok(());
+                };
+                let request = json!({
+                    "op": 6,
+                    "d": {
+                        "requestType": "SendStreamCaption",
+                        "requestId": uuid::Uuid::new_v4().to_string(),
+                        "requestData": { "captionText": text },
+                    },
+                });
+                ws.send(Message::text(request.to_string())).await?;
+            }
+            message = ws.next() => {
+                let Some(message) = message else {
+                    bail!("obs-websocket closed the connection");
+                };
+                if let Message::Text(text) = message? {
+                    // 请求失败（如未在推流）只打印，不断开
+                    let value: Value = serde_json::from_str(&text).unwrap_or_default();
+                    let status = &value["d"]["requestStatus"];
+                    if value["op"].as_u64() == Some(7) && status["result"] == false {
+                        eprintln!("SendStreamCaption failed: {}", status);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::agreement::test_segment;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_text_file_sink_keeps_last_lines() {
+        let path = std::env::temp_dir().join(format!("fl_caption_obs_{}.txt", std::process::id()));
+        let mut sink = TextFileSink::create(&path, 2).unwrap();
+        for (i, text) in ["one", "two", "three"].iter().enumerate() {
+            let event = CaptionEvent::Segments {
+                segments: vec![
+                    test_segment(text, 0.0, 1.0, i as u64 + 1, true),
+                    test_segment("four", 0.0, 1.0, i as u64 + 2, false),
+                ],
+            };
+            sink.push_event(&event).unwrap();
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "three\nfour");
+    }
+
+    #[test]
+    fn test_text_file_sink_ignores_empty_segments() {
+        let path =
+            std::env::temp_dir().join(format!("fl_caption_obs_empty_{}.txt", std::process::id()));
+        let mut sink = TextFileSink::create(&path, 2).unwrap();
+        let committed = CaptionEvent::Segments {
+            segments: vec![test_segment("one", 0.0, 1.0, 1, true)],
+        };
+        sink.push_event(&committed).unwrap();
+        sink.push_event(&CaptionEvent::Segments { segments: vec![] })
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "one");
+    }
+
+    /// Stand-in obs-websocket server with authentication
+    #[tokio::test]
+    async fn test_obs_caption_sink_sends_stream_caption() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let sink = ObsCaptionSink::start(&url, Some("secret".to_string())).unwrap();
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let hello = json!({
+            "op": 0,
+            "d": {
+                "obsWebSocketVersion": "5.5.0",
+                "rpcVersion": 1,
+                "authentication": { "challenge": "challenge", "salt": "salt" },
+            },
+        });
+        ws.send(Message::text(hello.to_string())).await.unwrap();
+        let identify: Value =
+            serde_json::from_str(ws.next().await.unwrap().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(identify["op"], 1);
+        assert_eq!(
+            identify["d"]["authentication"],
+            auth_response("secret", "salt", "challenge")
+        );
+        let identified = json!({ "op": 2, "d": { "negotiatedRpcVersion": 1 } });
+        ws.send(Message::text(identified.to_string()))
+            .await
+            .unwrap();
+
+        // 连接建立前推送的字幕会被丢弃，等客户端进入发送循环
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        sink.push_event(&CaptionEvent::Segments {
+            segments: vec![
+                test_segment(" hello obs", 0.0, 1.0, 1, true),
+                test_segment(" tentative", 0.0, 1.0, 2, false),
+            ],
+        });
+        let request: Value =
+            serde_json::from_str(ws.next().await.unwrap().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(request["op"], 6);
+        assert_eq!(request["d"]["requestType"], "SendStreamCaption");
+        assert_eq!(request["d"]["requestData"]["captionText"], "hello obs");
+        drop(ws);
+        drop(sink);
+    }
+}
@@ -0,0 +1,297 @@
+//! Minimal streaming FLAC encoder for the audio archive: 16-bit mono, fixed block size,
+//! FIXED linear predictors (order 0-4) with a single Rice partition per subframe.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use anyhow::Result;
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+/// 4 位 Rice 参数中 15 为转义码，可用的最大参数为 14
+const MAX_RICE_PARAM: u32 = 14;
+/// "fLaC" + 元数据块头之后就是 STREAMINFO
+const STREAMINFO_OFFSET: u64 = 8;
+
+pub struct FlacWriter<W: Write + Seek> {
+    inner: W,
+    sample_rate: u32,
+    pending: Vec<i32>,
+    frame_number: u32,
+    total_samples: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+}
+
+impl<W: Write + Seek> FlacWriter<W> {
+    pub fn new(mut inner: W, sample_rate: u32) -> Result<Self> {
+        inner.write_all(b"fLaC")?;
+        // 最后一个元数据块，类型 0 (STREAMINFO)，长度 34
+        inner.write_all(&[0x80, 0, 0, 34])?;
+        let mut writer = Self {
+            inner,
+            sample_rate,
+            pending: Vec::with_capacity(BLOCK_SIZE),
+            frame_number: 0,
+            total_samples: 0,
+            min_frame_size: 0,
+            max_frame_size: 0,
+        };
+        let streaminfo = writer.streaminfo();
+        writer.inner.write_all(&streaminfo)?;
+        Ok(writer)
+    }
+
+    /// Queue 16-bit samples, every full block is encoded and written right away
+    pub fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        for &sample in samples {
+            self.pending.push(sample as i32);
+            if self.pending.len() == BLOCK_SIZE {
+                self.write_frame()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Encode the last partial block and fill in the stream length and frame sizes
+    pub fn finalize(mut self) -> Result<W> {
+        if !self.pending.is_empty() {
+            self.write_frame()?;
+        }
+        let streaminfo = self.streaminfo();
+        self.inner.seek(SeekFrom::Start(STREAMINFO_OFFSET))?;
+        self.inner.write_all(&streaminfo)?;
+        self.inner.seek(SeekFrom::End(0))?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn streaminfo(&self) -> Vec<u8> {
+        let mut w = BitWriter::default();
+        w.write(BLOCK_SIZE as u64, 16); // min block size
+        w.write(BLOCK_SIZE as u64, 16); // max block size
+        w.write(self.min_frame_size as u64, 24);
+        w.write(self.max_frame_size as u64, 24);
+        w.write(self.sample_rate as u64, 20);
+        w.write(0, 3); // channels - 1
+        w.write((BITS_PER_SAMPLE - 1) as u64, 5);
+        w.write(self.total_samples, 36); // 0 表示未知，录制中断时文件依然可读
+        w.write(0, 64); // MD5 未计算
+        w.write(0, 64);
+        w.bytes
+    }
+
+    fn write_frame(&mut self) -> Result<()> {
+        let block = std::mem::take(&mut self.pending);
+        let mut w = BitWriter::default();
+        w.write(0b11111111111110, 14); // sync code
+        w.write(0, 1);
+        w.write(0, 1); // fixed block size
+        w.write(0b0111, 4); // block size - 1 follows as 16 bits
+        w.write(0b0000, 4); // sample rate from STREAMINFO
+        w.write(0b0000, 4); // mono
+        w.write(0b100, 3); // 16 bits per sample
+        w.write(0, 1);
+        w.write_utf8(self.frame_number);
+        w.write(block.len() as u64 - 1, 16);
+        let crc = crc8(&w.bytes);
+        w.write(crc as u64, 8);
+
+        write_fixed_subframe(&mut w, &block);
+        w.align();
+        let crc = crc16(&w.bytes);
+        w.write(crc as u64, 16);
+
+        self.inner.write_all(&w.bytes)?;
+        let frame_size = w.bytes.len() as u32;
+        self.min_frame_size = if self.frame_number == 0 {
+            frame_size
+        } else {
+            self.min_frame_size.min(frame_size)
+        };
+        self.max_frame_size = self.max_frame_size.max(frame_size);
+        self.frame_number += 1;
+        self.total_samples += block.len() as u64;
+        self.pending = block;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// FIXED subframe with the predictor order that gives the smallest residual
+fn write_fixed_subframe(w: &mut BitWriter, block: &[i32]) {
+    let (order, residual) = (0..=MAX_FIXED_ORDER.min(block.len()))
+        .map(|order| (order, fixed_residual(block, order)))
+        .min_by_key(|(_, residual)| {
+            residual
+                .iter()
+                .map(|r| r.unsigned_abs() as u64)
+                .sum::<u64>()
+        })
+        .unwrap_or_default();
+
+    w.write(0, 1);
+    w.write(0b001000 | order as u64, 6);
+    w.write(0, 1); // no wasted bits
+    for &sample in &block[..order] {
+        w.write_signed(sample, BITS_PER_SAMPLE);
+    }
+
+    // Rice 编码，4 位参数，分区阶数 0
+    let folded: Vec<u64> = residual
+        .iter()
+        .map(|&r| ((r << 1) ^ (r >> 31)) as u32 as u64)
+        .collect();
+    let param = (0..=MAX_RICE_PARAM)
+        .min_by_key(|&k| folded.iter().map(|&u| (u >> k) + 1 + k as u64).sum::<u64>())
+        .unwrap_or(0);
+    w.write(0b00, 2);
+    w.write(0, 4);
+    w.write(param as u64, 4);
+    for u in folded {
+        w.write_unary(u >> param);
+        w.write(u & ((1 << param) - 1), param);
+    }
+}
+
+fn fixed_residual(block: &[i32], order: usize) -> Vec<i32> {
+    (order..block.len())
+        .map(|i| {
+            let x = |back: usize| block[i - back];
+            match order {
+                0 => x(0),
+                1 => x(0) - x(1),
+                2 => x(0) - 2 * x(1) + x(2),
+                3 => x(0) - 3 * x(1) + 3 * x(2) - x(3),
+                _ => x(0) - 4 * x(1) + 6 * x(2) - 4 * x(3) + x(4),
+            }
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u8,
+    used: u32,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        self.current = (self.current << 1) | bit as u8;
+        self.used += 1;
+        if self.used == 8 {
+            self.bytes.push(self.current);
+            self.current = 0;
+            self.used = 0;
+        }
+    }
+
+    /// Write the low `bits` bits of `value`, most significant first
+    fn write(&mut self, value: u64, bits: u32) {
+        for i in (0..bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(value as u64 & ((1 << bits) - 1), bits);
+    }
+
+    fn write_unary(&mut self, zeros: u64) {
+        for _ in 0..zeros {
+            self.write_bit(false);
+        }
+        self.write_bit(true);
+    }
+
+    /// Frame numbers use the UTF-8 style variable length code
+    fn write_utf8(&mut self, value: u32) {
+        if value < 0x80 {
+            self.write(value as u64, 8);
+            return;
+        }
+        let len = match value {
+            0x80..0x800 => 2,
+            0x800..0x10000 => 3,
+            0x10000..0x200000 => 4,
+            0x200000..0x4000000 => 5,
+            _ => 6,
+        };
+        let prefix = (0xff00u32 >> len) as u8;
+        self.write((prefix | (value >> (6 * (len - 1))) as u8) as u64, 8);
+        for i in (0..len - 1).rev() {
+            self.write((0x80 | ((value >> (6 * i)) & 0x3f)) as u64, 8);
+        }
+    }
+
+    fn align(&mut self) {
+        while self.used != 0 {
+            self.write_bit(false);
+        }
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_capture::decoder;
+
+    #[test]
+    fn test_flac_writer_round_trip() {
+        // 两个多块长度的正弦波 + 一个不完整的尾块
+        let samples: Vec<i16> = (0..BLOCK_SIZE * 2 + 1000)
+            .map(|i| ((i as f32 * 0.05).sin() * 12000.0) as i16)
+            .collect();
+        let path = std::env::temp_dir().join(format!("fl_caption_{}.flac", std::process::id()));
+        let file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        let mut writer = FlacWriter::new(file, 16000).unwrap();
+        writer.write_samples(&samples[..5000]).unwrap();
+        writer.write_samples(&samples[5000..]).unwrap();
+        writer.finalize().unwrap();
+
+        let audio = decoder::decode_audio_file(&path, 16000).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(audio.pcm.len(), samples.len());
+        for (decoded, sample) in audio.pcm.iter().zip(&samples) {
+            assert!((decoded * 32768.0 - *sample as f32).abs() < 1.0);
+        }
+        assert!(size < samples.len() as u64 * 2);
+    }
+}
@@ -0,0 +1,288 @@
+//! Session recorder: archives the captured audio (WAV or FLAC) and writes a JSONL transcript
+//! of every emitted segment, so a session can be audited or re-transcribed later.
+//!
+//! The transcript starts with a `session` line (wall-clock start, backend, audio file),
+//! followed by one `segment` line per non-empty segment and an `exit` line.
+//! Segment `start` / `end` are the times reported by the caption loop in seconds.
+//! `confidence` / `avg_logprob` are null for backends that do not score their output.
+
+pub mod flac;
+
+use std::fs::File;
+use std::io::{BufWriter, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::json;
+
+use crate::caption::CaptionEvent;
+use flac::FlacWriter;
+
+const SAMPLE_RATE: u32 = 16000;
+/// 每录制这么多采样刷新一次文件头，进程异常退出时已录制的音频仍可读取
+const FLUSH_INTERVAL_SAMPLES: usize = SAMPLE_RATE as usize;
+
+/// Whether `path` has an extension the audio recorder can write (`.wav` or `.flac`)
+pub fn is_supported(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav") || ext.eq_ignore_ascii_case("flac"))
+}
+
+/// The transcript is written next to the audio archive with a `.jsonl` extension
+pub fn transcript_path(audio_path: impl AsRef<Path>) -> PathBuf {
+    audio_path.as_ref().with_extension("jsonl")
+}
+
+/// 16 kHz mono archive of the captured audio
+pub enum AudioRecorder {
+    Wav(hound::WavWriter<BufWriter<File>>),
+    Flac(FlacWriter<BufWriter<File>>),
+}
+
+impl AudioRecorder {
+    /// Create (or truncate) `path`, the format follows its extension
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !is_supported(path) {
+            bail!(
+                "unknown audio format of {}, use .wav or .flac",
+                path.display()
+            );
+        }
+        let is_flac = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("flac"));
+        let recorder = if is_flac {
+            let file = File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            AudioRecorder::Flac(FlacWriter::new(BufWriter::new(file), SAMPLE_RATE)?)
+        } else {
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate: SAMPLE_RATE,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            AudioRecorder::Wav(
+                hound::WavWriter::create(path, spec)
+                    .with_context(|| format!("failed to create {}", path.display()))?,
+            )
+        };
+        Ok(recorder)
+    }
+
+    /// Append pcm (f32, mono, 16kHz)
+    pub fn write(&mut self, pcm: &[f32]) -> Result<()> {
+        let samples: Vec<i16> = pcm
+            .iter()
+            .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16)
+            .collect();
+        match self {
+            AudioRecorder::Wav(writer) => {
+                for sample in samples {
+                    writer.write_sample(sample)?;
+                }
+            }
+            AudioRecorder::Flac(writer) => writer.write_samples(&samples)?,
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        match self {
+            AudioRecorder::Wav(writer) => writer.flush()?,
+            AudioRecorder::Flac(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    pub fn finalize(self) -> Result<()> {
+        match self {
+            AudioRecorder::Wav(writer) => writer.finalize()?,
+            AudioRecorder::Flac(writer) => {
+                writer.finalize()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recorder stage between the audio capture and the caption loop: forwards every chunk
+/// and archives it. The thread finalizes the file once the capture channel closes,
+/// join the handle before reporting the end of the session.
+pub fn record_audio(
+    rx: Receiver<Vec<f32>>,
+    mut recorder: AudioRecorder,
+) -> (Receiver<Vec<f32>>, JoinHandle<()>) {
+    let (tx, forwarded_rx) = mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        let mut failed = false;
+        let mut unflushed = 0;
+        for pcm in rx {
+            // 写入失败只停止录制，不影响字幕
+            if !failed {
+                let result = recorder.write(&pcm).and_then(|_| {
+                    unflushed += pcm.len();
+                    if unflushed >= FLUSH_INTERVAL_SAMPLES {
+                        unflushed = 0;
+                        recorder.flush()?;
+                    }
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    eprintln!("Failed to record audio, recording stopped: {:?}", e);
+                    failed = true;
+                }
+            }
+            if tx.send(pcm).is_err() {
+                break;
+            }
+        }
+        if let Err(e) = recorder.finalize() {
+            eprintln!("Failed to finalize audio recording: {:?}", e);
+        }
+    });
+    (forwarded_rx, handle)
+}
+
+/// JSONL transcript of a caption session
+pub struct TranscriptRecorder {
+    file: LineWriter<File>,
+    audio_path: String,
+    language: Option<String>,
+    backend: String,
+    device: String,
+}
+
+impl TranscriptRecorder {
+    /// `language` is recorded for segments that don't report their own language
+    pub fn create(
+        path: impl AsRef<Path>,
+        audio_path: impl AsRef<Path>,
+        language: Option<String>,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        Ok(Self {
+            file: LineWriter::new(file),
+            audio_path: audio_path.as_ref().to_string_lossy().to_string(),
+            language,
+            backend: String::new(),
+            device: String::new(),
+        })
+    }
+
+    pub fn push_event(&mut self, event: &CaptionEvent) -> Result<()> {
+        let time = wall_clock();
+        match event {
+            CaptionEvent::Ready {
+                backend, device, ..
+            } => {
+                self.backend = backend.clone();
+                self.device = device.clone();
+                self.write_line(json!({
+                    "type": "session",
+                    "time": time,
+                    "audio": self.audio_path,
+                    "sample_rate": SAMPLE_RATE,
+                    "backend": backend,
+                    "device": device,
+                    "language": self.language,
+                }))?;
+            }
+            CaptionEvent::Segments { segments } => {
+                for segment in segments {
+                    let text = segment.dr.text.trim();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let language = segment.reasoning_lang.as_ref().or(self.language.as_ref());
+                    self.write_line(json!({
+                        "type": "segment",
+                        "time": time,
+                        "id": segment.id,
+                        "committed": segment.committed,
+                        "start": segment.start,
+                        "end": segment.start + segment.duration,
+                        "text": text,
+                        "backend": self.backend,
+                        "language": language,
+                        // 平均对数概率换算成 0~1 的置信度，后端没有给出分数（NaN）时写 null
+                        "confidence": finite(segment.dr.avg_logprob)
+                            .map(|logprob| logprob.exp().clamp(0.0, 1.0)),
+                        "avg_logprob": finite(segment.dr.avg_logprob),
+                        "no_speech_prob": segment.dr.no_speech_prob,
+                    }))?;
+                }
+            }
+            CaptionEvent::Exit { reason } => {
+                self.write_line(json!({ "type": "exit", "time": time, "reason": reason }))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, value: serde_json::Value) -> Result<()> {
+        writeln!(self.file, "{value}")?;
+        Ok(())
+    }
+}
+
+/// `None` for the NaN / infinite scores of backends that do not compute them
+fn finite(value: f64) -> Option<f64> {
+    value.is_finite().then_some(value)
+}
+
+/// RFC 3339 UTC time with milliseconds
+fn wall_clock() -> String {
+    DateTime::<Utc>::from(SystemTime::now()).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::agreement::{units_to_segment, Unit};
+
+    #[test]
+    fn test_transcript_confidence_is_null_without_score() {
+        let unit = |text: &str| Unit {
+            text: text.to_string(),
+            start: 0.0,
+            end: 1.0,
+            timed: false,
+        };
+        // 没有模板时 avg_logprob 为 NaN（ONNX 后端同样不给分数）
+        let unscored = units_to_segment(&[unit(" hello")], 0, true, None);
+        let mut scored = units_to_segment(&[unit(" world")], 1, true, None);
+        scored.dr.avg_logprob = 0.0;
+
+        let path = std::env::temp_dir().join(format!("fl_caption_{}.jsonl", std::process::id()));
+        let mut recorder = TranscriptRecorder::create(&path, "session.wav", None).unwrap();
+        recorder
+            .push_event(&CaptionEvent::Segments {
+                segments: vec![unscored, scored],
+            })
+            .unwrap();
+        drop(recorder);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]["confidence"].is_null());
+        assert!(lines[0]["avg_logprob"].is_null());
+        assert_eq!(lines[1]["confidence"], 1.0);
+        assert_eq!(lines[1]["avg_logprob"], 0.0);
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::candle_models::whisper::model::Segment;
+use crate::caption::{CaptionErrorKind, CaptionEvent, StreamingRecognizer};
+
+/// Caption loop of streaming recognizers: every captured chunk is decoded as it arrives,
+/// the current utterance is reported whenever its text changes and once more when the
+/// recognizer detects its end.
+///
+/// All segments of an utterance share its id; partial results have `committed == false`
+/// and are replaced by the final (committed) result. Times are relative to the capture start.
+pub(crate) fn run_streaming<F>(
+    name: &str,
+    recognizer: &mut dyn StreamingRecognizer,
+    rx: Receiver<Vec<f32>>,
+    cancel_token: &CancellationToken,
+    language: Option<&str>,
+    result_callback: &mut F,
+) where
+    F: FnMut(CaptionEvent),
+{
+    eprintln!("Starting {name} streaming loop...");
+    let mut utterance_id = 1;
+    // 本句是否已发送过临时结果，最终结果为空时也要发送以替换它
+    let mut partial_sent = false;
+
+    while !cancel_token.is_cancelled() {
+        let (pcm, finished) = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(pcm) => (pcm, false),
+            Err(RecvTimeoutError::Disconnected) => {
+                eprintln!("{name} audio capture finished");
+                (vec![], true)
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+        };
+
+        let inference_start = Instant::now();
+        match recognizer.accept_waveform(&pcm) {
+            Ok(Some(mut segment)) => {
+                fill_segment(&mut segment, utterance_id, false, inference_start, language);
+                partial_sent = true;
+                result_callback(CaptionEvent::Segments {
+                    segments: vec![segment],
+                });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{name} inference error: {:?}", e);
+                result_callback(CaptionEvent::error(CaptionErrorKind::Inference, &e));
+            }
+        }
+
+        if finished || recognizer.is_endpoint() {
+            match recognizer.finish_utterance(finished) {
+                Ok(mut segment) if partial_sent || !segment.dr.text.is_empty() => {
+                    fill_segment(&mut segment, utterance_id, true, inference_start, language);
+                    result_callback(CaptionEvent::Segments {
+                        segments: vec![segment],
+                    });
+                    utterance_id += 1;
+                }
+                // 没有识别出文字的静音，不产生字幕
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("{name} inference error: {:?}", e);
+                    result_callback(CaptionEvent::error(CaptionErrorKind::Inference, &e));
+                }
+            }
+            partial_sent = false;
+        }
+
+        if finished {
+            break;
+        }
+    }
+}
+
+fn fill_segment(
+    segment: &mut Segment,
+    id: u64,
+    committed: bool,
+    inference_start: Instant,
+    language: Option<&str>,
+) {
+    segment.id = id;
+    segment.committed = committed;
+    segment.reasoning_duration = Some(inference_start.elapsed().as_millis());
+    segment.audio_duration = Some((segment.duration * 1000.0) as u128);
+    if segment.reasoning_lang.is_none() {
+        segment.reasoning_lang = language.map(|s| s.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::caption::agreement::test_segment;
+
+    /// Replays a partial result and an endpoint flag per chunk, and the final results
+    struct ScriptedStream {
+        chunks: VecDeque<(Option<&'static str>, bool)>,
+        finals: VecDeque<&'static str>,
+        endpoint: bool,
+        finish_calls: Vec<bool>,
+    }
+
+    fn segment(text: &str) -> Segment {
+        test_segment(text, 0.0, 1.0, 0, false)
+    }
+
+    impl StreamingRecognizer for ScriptedStream {
+        fn accept_waveform(&mut self, _pcm: &[f32]) -> anyhow::Result<Option<Segment>> {
+            let (partial, endpoint) = self.chunks.pop_front().unwrap_or((None, false));
+            self.endpoint = endpoint;
+            Ok(partial.map(segment))
+        }
+
+        fn is_endpoint(&self) -> bool {
+            self.endpoint
+        }
+
+        fn finish_utterance(&mut self, input_finished: bool) -> anyhow::Result<Segment> {
+            self.finish_calls.push(input_finished);
+            Ok(segment(self.finals.pop_front().unwrap_or_default()))
+        }
+    }
+
+    #[test]
+    fn test_run_streaming_partial_and_final_results() {
+        let mut recognizer = ScriptedStream {
+            chunks: VecDeque::from([
+                (Some("he"), false),
+                (Some("hello"), true),
+                // 没有文字的静音断句，不产生字幕
+                (None, true),
+                (Some("wor"), false),
+                // 最终结果为空，但要替换已发送的临时结果
+                (None, true),
+            ]),
+            finals: VecDeque::from(["hello", "", "", ""]),
+            endpoint: false,
+            finish_calls: vec![],
+        };
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..5 {
+            tx.send(vec![0.0; 1600]).unwrap();
+        }
+        drop(tx);
+
+        let mut segments = vec![];
+        run_streaming(
+            "Scripted",
+            &mut recognizer,
+            rx,
+            &CancellationToken::new(),
+            Some("en"),
+            &mut |event| {
+                if let CaptionEvent::Segments { segments: batch } = event {
+                    segments.extend(batch);
+                }
+            },
+        );
+
+        let results: Vec<(&str, u64, bool)> = segments
+            .iter()
+            .map(|s| (s.dr.text.as_str(), s.id, s.committed))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("he", 1, false),
+                ("hello", 1, false),
+                ("hello", 1, true),
+                ("wor", 2, false),
+                ("", 2, true),
+            ]
+        );
+        assert!(segments
+            .iter()
+            .all(|s| s.reasoning_lang.as_deref() == Some("en")));
+        // 音频结束时冲刷剩余音频
+        assert_eq!(recognizer.finish_calls, vec![false, false, false, true]);
+    }
+}
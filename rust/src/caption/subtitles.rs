@@ -0,0 +1,438 @@
+//! Subtitle export (SRT, WebVTT, TTML).
+//!
+//! Segments are flattened into timed words (Whisper word timings when available, otherwise
+//! times interpolated by character count) and regrouped into cues no longer than
+//! [`SubtitleOptions::max_cue_duration_secs`] and [`SubtitleOptions::max_cue_chars`].
+//! A complete list of segments is rendered with [`render`] / [`write_file`], a live session
+//! appends its committed segments with a [`SubtitleWriter`].
+
+use std::fmt;
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::candle_models::whisper::model::Segment;
+use crate::caption::agreement::{self, Unit};
+use crate::caption::CaptionEvent;
+
+/// 时间戳相同或倒序的词合并成的字幕至少显示这么久
+const MIN_CUE_DURATION_SECS: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+    Ttml,
+}
+
+impl SubtitleFormat {
+    /// Format matching the extension of `path` (`.srt`, `.vtt`, `.ttml` / `.dfxp` / `.xml`)
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::WebVtt),
+            "ttml" | "dfxp" | "xml" => Some(Self::Ttml),
+            _ => None,
+        }
+    }
+}
+
+/// WebVTT cue settings, appended after the timings of every cue (e.g. `line:85% align:center`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VttCueSettings {
+    /// `line:` value, e.g. "85%" or "-2"
+    pub line: Option<String>,
+    /// `position:` value, e.g. "50%"
+    pub position: Option<String>,
+    /// `size:` value, e.g. "80%"
+    pub size: Option<String>,
+    /// `align:` value: start, center, end, left or right
+    pub align: Option<String>,
+}
+
+impl fmt::Display for VttCueSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let settings = [
+            ("line", &self.line),
+            ("position", &self.position),
+            ("size", &self.size),
+            ("align", &self.align),
+        ];
+        let mut first = true;
+        for (name, value) in settings {
+            if let Some(value) = value {
+                if !first {
+                    write!(f, " ")?;
+                }
+                write!(f, "{name}:{value}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleOptions {
+    /// Split cues longer than this, default 7 seconds
+    pub max_cue_duration_secs: f64,
+    /// Split cues with more characters than this, default 84 (two lines)
+    pub max_cue_chars: usize,
+    /// Wrap the text of a cue at this many characters per line, default 42
+    pub max_line_chars: usize,
+    /// WebVTT only
+    pub vtt_settings: VttCueSettings,
+    /// `xml:lang` of the TTML document
+    pub language: Option<String>,
+}
+
+impl Default for SubtitleOptions {
+    fn default() -> Self {
+        Self {
+            max_cue_duration_secs: 7.0,
+            max_cue_chars: 84,
+            max_line_chars: 42,
+            vtt_settings: VttCueSettings::default(),
+            language: None,
+        }
+    }
+}
+
+/// One subtitle, times in seconds, lines separated by `\n`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Group the text of `segments` into cues, segments with empty text are skipped
+pub fn segments_to_cues(segments: &[Segment], options: &SubtitleOptions) -> Vec<Cue> {
+    let mut cues = vec![];
+    for segment in segments {
+        if segment.dr.text.trim().is_empty() {
+            continue;
+        }
+        let units = agreement::hypothesis_units(std::slice::from_ref(segment), 0.0);
+        cues.extend(split_cues(&units, options));
+    }
+    // 字幕不能重叠
+    let mut previous_end = 0.0_f64;
+    for cue in &mut cues {
+        cue.start = cue.start.max(previous_end);
+        cue.end = cue.end.max(cue.start + MIN_CUE_DURATION_SECS);
+        previous_end = cue.end;
+    }
+    cues
+}
+
+/// Greedily pack units into cues within the duration and character limits
+fn split_cues(units: &[Unit], options: &SubtitleOptions) -> Vec<Cue> {
+    let mut cues = vec![];
+    let mut current: Vec<&Unit> = vec![];
+    for unit in units {
+        if let Some(first) = current.first() {
+            let chars = text_of(&current).chars().count() + unit.text.chars().count();
+            let too_long = chars > options.max_cue_chars;
+            let too_slow = unit.end - first.start > options.max_cue_duration_secs;
+            if too_long || too_slow {
+                cues.push(cue_of(&current, options));
+                current.clear();
+            }
+        }
+        current.push(unit);
+    }
+    if !current.is_empty() {
+        cues.push(cue_of(&current, options));
+    }
+    cues
+}
+
+fn text_of(units: &[&Unit]) -> String {
+    units.iter().map(|u| u.text.as_str()).collect::<String>()
+}
+
+fn cue_of(units: &[&Unit], options: &SubtitleOptions) -> Cue {
+    // 按行宽折行，单位之间自带空格（中日文没有空格，直接拼接）
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    for unit in units {
+        let fits =
+            line.chars().count() + unit.text.trim_end().chars().count() <= options.max_line_chars;
+        if !line.is_empty() && !fits {
+            lines.push(std::mem::take(&mut line));
+        }
+        if line.is_empty() {
+            line.push_str(unit.text.trim_start());
+        } else {
+            line.push_str(&unit.text);
+        }
+    }
+    lines.push(line);
+    let text = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Cue {
+        start: units.first().map(|u| u.start).unwrap_or(0.0),
+        end: units.last().map(|u| u.end).unwrap_or(0.0),
+        text,
+    }
+}
+
+/// Render a complete subtitle document
+pub fn render(format: SubtitleFormat, cues: &[Cue], options: &SubtitleOptions) -> String {
+    let mut out = header(format, options);
+    for (index, cue) in cues.iter().enumerate() {
+        out.push_str(&render_cue(format, index + 1, cue, options));
+    }
+    out.push_str(footer(format));
+    out
+}
+
+/// Render `segments` into a subtitle file, the format follows the extension of `path`
+pub fn write_file(
+    path: impl AsRef<Path>,
+    segments: &[Segment],
+    options: &SubtitleOptions,
+) -> Result<()> {
+    let path = path.as_ref();
+    let format = format_of(path)?;
+    let cues = segments_to_cues(segments, options);
+    std::fs::write(path, render(format, &cues, options))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn format_of(path: &Path) -> Result<SubtitleFormat> {
+    match SubtitleFormat::from_path(path) {
+        Some(format) => Ok(format),
+        None => bail!(
+            "unknown subtitle format of {}, use .srt, .vtt or .ttml",
+            path.display()
+        ),
+    }
+}
+
+fn header(format: SubtitleFormat, options: &SubtitleOptions) -> String {
+    match format {
+        SubtitleFormat::Srt => String::new(),
+        SubtitleFormat::WebVtt => "WEBVTT\n\n".to_string(),
+        SubtitleFormat::Ttml => {
+            let lang = options
+                .language
+                .as_deref()
+                .map(|lang| format!(" xml:lang=\"{}\"", escape_xml(lang)))
+                .unwrap_or_default();
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <tt xmlns=\"http://www.w3.org/ns/ttml\"{lang}>\n  <body>\n    <div>\n"
+            )
+        }
+    }
+}
+
+fn footer(format: SubtitleFormat) -> &'static str {
+    match format {
+        SubtitleFormat::Srt | SubtitleFormat::WebVtt => "",
+        SubtitleFormat::Ttml => "    </div>\n  </body>\n</tt>\n",
+    }
+}
+
+fn render_cue(
+    format: SubtitleFormat,
+    index: usize,
+    cue: &Cue,
+    options: &SubtitleOptions,
+) -> String {
+    match format {
+        SubtitleFormat::Srt => format!(
+            "{index}\n{} --> {}\n{}\n\n",
+            timestamp(cue.start, ','),
+            timestamp(cue.end, ','),
+            cue.text
+        ),
+        SubtitleFormat::WebVtt => {
+            let settings = options.vtt_settings.to_string();
+            let settings = if settings.is_empty() {
+                settings
+            } else {
+                format!(" {settings}")
+            };
+            format!(
+                "{index}\n{} --> {}{settings}\n{}\n\n",
+                timestamp(cue.start, '.'),
+                timestamp(cue.end, '.'),
+                escape_vtt(&cue.text)
+            )
+        }
+        SubtitleFormat::Ttml => format!(
+            "      <p begin=\"{}\" end=\"{}\">{}</p>\n",
+            timestamp(cue.start, '.'),
+            timestamp(cue.end, '.'),
+            escape_xml(&cue.text).replace('\n', "<br/>")
+        ),
+    }
+}
+
+/// hh:mm:ss followed by `separator` and milliseconds
+fn timestamp(seconds: f64, separator: char) -> String {
+    let ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_xml(text: &str) -> String {
+    escape_vtt(text).replace('"', "&quot;")
+}
+
+/// Appends the committed segments of a live session to a subtitle file.
+///
+/// The file is a complete document after every append (for TTML the closing tags are
+/// rewritten each time), so it can be opened while the session is still running.
+/// Only committed segments are written, which needs LocalAgreement or VAD endpointing.
+pub struct SubtitleWriter {
+    file: File,
+    format: SubtitleFormat,
+    options: SubtitleOptions,
+    cue_count: usize,
+    last_end: f64,
+}
+
+impl SubtitleWriter {
+    /// Create (or truncate) `path`, the format follows its extension
+    pub fn create(path: impl AsRef<Path>, options: SubtitleOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let format = format_of(path)?;
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        let mut writer = Self {
+            file,
+            format,
+            options,
+            cue_count: 0,
+            last_end: 0.0,
+        };
+        let header = header(format, &writer.options);
+        writer.write_and_close(&header)?;
+        Ok(writer)
+    }
+
+    /// Append the committed segments, returns the number of cues written
+    pub fn append(&mut self, segments: &[Segment]) -> Result<usize> {
+        let committed: Vec<Segment> = segments.iter().filter(|s| s.committed).cloned().collect();
+        let mut cues = segments_to_cues(&committed, &self.options);
+        let mut out = String::new();
+        for cue in &mut cues {
+            cue.start = cue.start.max(self.last_end);
+            cue.end = cue.end.max(cue.start + MIN_CUE_DURATION_SECS);
+            self.last_end = cue.end;
+            self.cue_count += 1;
+            out.push_str(&render_cue(self.format, self.cue_count, cue, &self.options));
+        }
+        if !out.is_empty() {
+            self.write_and_close(&out)?;
+        }
+        Ok(cues.len())
+    }
+
+    /// Append the segments of a [`CaptionEvent::Segments`], other events are ignored
+    pub fn push_event(&mut self, event: &CaptionEvent) -> Result<usize> {
+        match event {
+            CaptionEvent::Segments { segments } => self.append(segments),
+            _ => Ok(0),
+        }
+    }
+
+    /// Write `text` then the footer, and move back before the footer for the next append
+    fn write_and_close(&mut self, text: &str) -> Result<()> {
+        let footer = footer(self.format);
+        self.file.write_all(text.as_bytes())?;
+        self.file.write_all(footer.as_bytes())?;
+        self.file.flush()?;
+        self.file.seek(SeekFrom::Current(-(footer.len() as i64)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::agreement::test_segment;
+
+    #[test]
+    fn test_subtitles_split_and_render() {
+        let options = SubtitleOptions {
+            max_cue_duration_secs: 3.0,
+            ..Default::default()
+        };
+        let segments = vec![
+            test_segment(" one two three four five six", 0.0, 6.0, 1, true),
+            test_segment(" ", 6.0, 7.0, 2, true),
+            test_segment(" <seven>", 7.0, 8.0, 3, true),
+        ];
+        let cues = segments_to_cues(&segments, &options);
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[0].text, "one two three");
+        assert_eq!(cues[2].start, 7.0);
+
+        let srt = render(SubtitleFormat::Srt, &cues, &options);
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:03,000\none two three\n\n2\n"));
+        let options = SubtitleOptions {
+            vtt_settings: VttCueSettings {
+                line: Some("85%".to_string()),
+                align: Some("center".to_string()),
+                ..Default::default()
+            },
+            ..options
+        };
+        let vtt = render(SubtitleFormat::WebVtt, &cues, &options);
+        assert!(
+            vtt.starts_with("WEBVTT\n\n1\n00:00:00.000 --> 00:00:03.000 line:85% align:center\n")
+        );
+        assert!(vtt.contains("&lt;seven&gt;"));
+
+        // 长文本按行宽折行
+        let options = SubtitleOptions {
+            max_line_chars: 10,
+            ..Default::default()
+        };
+        let cues = segments_to_cues(&segments[..1], &options);
+        assert_eq!(cues[0].text, "one two\nthree four\nfive six");
+    }
+
+    #[test]
+    fn test_subtitle_writer_appends_committed_segments() {
+        let path = std::env::temp_dir().join(format!("fl_caption_{}.ttml", std::process::id()));
+        let mut writer = SubtitleWriter::create(&path, SubtitleOptions::default()).unwrap();
+        let tentative = test_segment(" not yet", 0.0, 1.0, 1, false);
+        assert_eq!(writer.append(&[tentative]).unwrap(), 0);
+        let hello = test_segment(" hello", 0.0, 1.0, 1, true);
+        assert_eq!(writer.append(&[hello]).unwrap(), 1);
+        let world = test_segment(" world", 1.0, 2.0, 2, true);
+        assert_eq!(writer.append(&[world]).unwrap(), 1);
+
+        let ttml = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(ttml.ends_with(
+            "      <p begin=\"00:00:00.000\" end=\"00:00:01.000\">hello</p>\n      \
+             <p begin=\"00:00:01.000\" end=\"00:00:02.000\">world</p>\n    \
+             </div>\n  </body>\n</tt>\n"
+        ));
+    }
+}
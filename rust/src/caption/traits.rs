@@ -0,0 +1,64 @@
+use anyhow::Result;
+
+use crate::candle_models::whisper::model::Segment;
+
+/// Trait for speech recognition backends driven by the caption engine
+pub trait SpeechRecognizer: Send {
+    /// Backend name, used as log prefix
+    fn name(&self) -> &str;
+
+    /// Compute device the model runs on, reported in [`crate::caption::CaptionEvent::Ready`]
+    fn device(&self) -> String {
+        "cpu".to_string()
+    }
+
+    /// Registered ONNX Runtime execution providers, empty for other runtimes
+    fn providers(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Transcribe a pcm window (f32, mono, 16kHz)
+    /// Returned segment timestamps are relative to the start of the window
+    fn transcribe(&mut self, pcm: &[f32], language: Option<&str>) -> Result<Vec<Segment>>;
+
+    /// The next window starts `start_sample` samples into the stream, called right before
+    /// [`SpeechRecognizer::transcribe`] so backends can reuse work on the overlap.
+    /// Without it every window is treated as new audio
+    fn set_window_start(&mut self, _start_sample: usize) {}
+
+    /// Forget what was learned from earlier windows (such as the detected language),
+    /// called before transcribing unrelated audio
+    fn reset(&mut self) {}
+
+    /// Replace the initial prompt until the next [`SpeechRecognizer::reset`],
+    /// ignored by backends without prompting
+    fn set_prompt(&mut self, _prompt: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Feed finalized text as context for the following windows
+    fn push_context(&mut self, _text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Streaming backends keep their decoding state across chunks, the caption engine feeds
+    /// them every captured chunk instead of re-transcribing sliding windows
+    fn as_streaming(&mut self) -> Option<&mut dyn StreamingRecognizer> {
+        None
+    }
+}
+
+/// Recognizer that decodes audio as it arrives, see [`SpeechRecognizer::as_streaming`].
+/// Segment times are relative to the start of the stream
+pub trait StreamingRecognizer {
+    /// Feed captured audio (f32, mono, 16kHz), returns the current utterance
+    /// when its text changed
+    fn accept_waveform(&mut self, pcm: &[f32]) -> Result<Option<Segment>>;
+
+    /// Whether the current utterance ended (trailing silence or maximum length)
+    fn is_endpoint(&self) -> bool;
+
+    /// Final result of the current utterance, the following audio starts a new one.
+    /// `input_finished` flushes the audio still buffered for the next chunk
+    fn finish_utterance(&mut self, input_finished: bool) -> Result<Segment>;
+}
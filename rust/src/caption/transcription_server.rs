@@ -0,0 +1,430 @@
+//! Local HTTP transcription server compatible with the OpenAI audio API, so tools written for
+//! `POST /v1/audio/transcriptions` can use a model loaded by fl_caption.
+//!
+//! Form fields: `file` (required), `language`, `response_format` (`json`, `text`, `srt`, `vtt`,
+//! `verbose_json`) and `timestamp_granularities[]` (`segment`, `word`). `model` and other
+//! fields are accepted and ignored, the loaded model answers every request.
+//! Requests are transcribed one at a time by a single recognizer.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio_capture::decoder;
+use crate::candle_models::whisper::model::Segment;
+use crate::caption::file::transcribe_pcm;
+use crate::caption::subtitles::{self, SubtitleFormat, SubtitleOptions};
+use crate::caption::SpeechRecognizer;
+
+const SAMPLE_RATE: u32 = 16000;
+/// 与 OpenAI 接口相同的上传大小限制
+const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    Text,
+    Srt,
+    Vtt,
+    VerboseJson,
+}
+
+impl ResponseFormat {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "json" => Some(Self::Json),
+            "text" => Some(Self::Text),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "verbose_json" => Some(Self::VerboseJson),
+            _ => None,
+        }
+    }
+}
+
+struct ServerState {
+    recognizer: Mutex<Box<dyn SpeechRecognizer>>,
+    word_timestamps: bool,
+}
+
+/// Routes of the transcription API around one loaded recognizer.
+/// `word_timestamps` tells whether the recognizer was created with word timestamps.
+pub fn router(recognizer: Box<dyn SpeechRecognizer>, word_timestamps: bool) -> Router {
+    let state = Arc::new(ServerState {
+        recognizer: Mutex::new(recognizer),
+        word_timestamps,
+    });
+    Router::new()
+        .route("/v1/audio/transcriptions", post(transcriptions))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .with_state(state)
+}
+
+/// Serve the transcription API on `listener` until `cancel_token` is cancelled
+pub async fn serve(
+    listener: TcpListener,
+    recognizer: Box<dyn SpeechRecognizer>,
+    word_timestamps: bool,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    eprintln!(
+        "Transcription server listening on http://{}/v1/audio/transcriptions",
+        listener.local_addr()?
+    );
+    axum::serve(listener, router(recognizer, word_timestamps))
+        .with_graceful_shutdown(cancel_token.cancelled_owned())
+        .await?;
+    Ok(())
+}
+
+/// Error in the OpenAI format `{"error": {"message": ..., "type": ...}}`
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl ToString) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.to_string(),
+        }
+    }
+
+    fn internal(e: anyhow::Error) -> Self {
+        eprintln!("Transcription request failed: {:?}", e);
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: format!("{e:#}"),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let kind = if self.status.is_client_error() {
+            "invalid_request_error"
+        } else {
+            "server_error"
+        };
+        let body = json!({
+            "error": { "message": self.message, "type": kind, "param": null, "code": null }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[derive(Default)]
+struct TranscriptionRequest {
+    file: Option<(Vec<u8>, Option<String>)>,
+    language: Option<String>,
+    prompt: Option<String>,
+    response_format: Option<ResponseFormat>,
+    words: bool,
+    segments: bool,
+}
+
+impl TranscriptionRequest {
+    async fn read(mut multipart: Multipart) -> Result<Self, ApiError> {
+        let mut request = Self::default();
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(ApiError::bad_request)?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+            if name == "file" {
+                let extension = field
+                    .file_name()
+                    .and_then(|name| Path::new(name).extension())
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.to_string());
+                let data = field.bytes().await.map_err(ApiError::bad_request)?;
+                request.file = Some((data.to_vec(), extension));
+                continue;
+            }
+            let value = field.text().await.map_err(ApiError::bad_request)?;
+            let value = value.trim();
+            match name.as_str() {
+                "language" if !value.is_empty() => request.language = Some(value.to_string()),
+                "prompt" if !value.is_empty() => request.prompt = Some(value.to_string()),
+                "response_format" => {
+                    let format = ResponseFormat::parse(value).ok_or_else(|| {
+                        ApiError::bad_request(format!("unsupported response_format {value}"))
+                    })?;
+                    request.response_format = Some(format);
+                }
+                "timestamp_granularities[]" | "timestamp_granularities" => match value {
+                    "word" => request.words = true,
+                    "segment" => request.segments = true,
+                    _ => {
+                        return Err(ApiError::bad_request(format!(
+                            "unsupported timestamp granularity {value}"
+                        )))
+                    }
+                },
+                // model、temperature 等字段忽略
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+}
+
+async fn transcriptions(
+    State(state): State<Arc<ServerState>>,
+    multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let request = TranscriptionRequest::read(multipart).await?;
+    let (data, extension) = request
+        .file
+        .ok_or_else(|| ApiError::bad_request("file is required"))?;
+    let format = request.response_format.unwrap_or(ResponseFormat::Json);
+    if (request.words || request.segments) && format != ResponseFormat::VerboseJson {
+        return Err(ApiError::bad_request(
+            "timestamp_granularities requires response_format verbose_json",
+        ));
+    }
+    if request.words && !state.word_timestamps {
+        return Err(ApiError::bad_request(
+            "word timestamps are not enabled on this server",
+        ));
+    }
+
+    let audio = tokio::task::spawn_blocking(move || {
+        decoder::decode_audio_bytes(data, extension.as_deref(), SAMPLE_RATE)
+    })
+    .await
+    .map_err(|e| ApiError::internal(e.into()))?
+    .map_err(|e| ApiError::bad_request(format!("failed to decode audio: {e:#}")))?;
+    let duration = audio.duration_secs();
+
+    let language = request.language.clone();
+    let prompt = request.prompt;
+    let segments = tokio::task::spawn_blocking(move || {
+        // 识别器出错 panic 后锁被污染，仍然继续使用
+        let mut recognizer = state
+            .recognizer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // 每个请求都是无关的音频，不沿用上一个请求检测到的语言
+        recognizer.reset();
+        if let Some(prompt) = &prompt {
+            recognizer.set_prompt(prompt)?;
+        }
+        transcribe_pcm(recognizer.as_mut(), &audio.pcm, language.as_deref())
+    })
+    .await
+    .map_err(|e| ApiError::internal(e.into()))?
+    .map_err(ApiError::internal)?;
+
+    let text = segments
+        .iter()
+        .map(|segment| segment.dr.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string();
+    let response = match format {
+        ResponseFormat::Json => Json(json!({ "text": text })).into_response(),
+        ResponseFormat::Text => plain_text(text),
+        ResponseFormat::Srt | ResponseFormat::Vtt => {
+            let subtitle_format = if format == ResponseFormat::Srt {
+                SubtitleFormat::Srt
+            } else {
+                SubtitleFormat::WebVtt
+            };
+            let options = SubtitleOptions {
+                language: request.language,
+                ..Default::default()
+            };
+            let cues = subtitles::segments_to_cues(&segments, &options);
+            plain_text(subtitles::render(subtitle_format, &cues, &options))
+        }
+        ResponseFormat::VerboseJson => {
+            let language = segments
+                .iter()
+                .find_map(|segment| segment.reasoning_lang.clone())
+                .or(request.language);
+            let mut body = json!({
+                "task": "transcribe",
+                "language": language,
+                "duration": duration,
+                "text": text,
+            });
+            // 未指定粒度时与 OpenAI 一样返回段级时间戳
+            if request.segments || !request.words {
+                body["segments"] = verbose_segments(&segments).into();
+            }
+            if request.words {
+                body["words"] = verbose_words(&segments).into();
+            }
+            Json(body).into_response()
+        }
+    };
+    Ok(response)
+}
+
+fn plain_text(text: String) -> Response {
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], text).into_response()
+}
+
+/// Segments of the `verbose_json` response, one per timestamped part of the model output
+fn verbose_segments(segments: &[Segment]) -> Vec<Value> {
+    let mut out = vec![];
+    for segment in segments {
+        let parts: Vec<(f64, f64, &str)> = if segment.sub_segments.is_empty() {
+            vec![(
+                segment.start,
+                segment.start + segment.duration,
+                segment.dr.text.as_str(),
+            )]
+        } else {
+            segment
+                .sub_segments
+                .iter()
+                .map(|sub| (sub.start, sub.end, sub.text.as_str()))
+                .collect()
+        };
+        // 拆分后的子段无法对应 token，只有整段时才返回 token
+        let tokens: &[u32] = if parts.len() == 1 {
+            &segment.dr.tokens
+        } else {
+            &[]
+        };
+        for (start, end, text) in parts {
+            out.push(json!({
+                "id": out.len(),
+                "seek": (segment.start * 100.0).round() as u64,
+                "start": start,
+                "end": end,
+                "text": text,
+                "tokens": tokens,
+                "temperature": segment.dr.temperature,
+                "avg_logprob": segment.dr.avg_logprob,
+                "compression_ratio": segment.dr.compression_ratio,
+                "no_speech_prob": segment.dr.no_speech_prob,
+            }));
+        }
+    }
+    out
+}
+
+fn verbose_words(segments: &[Segment]) -> Vec<Value> {
+    segments
+        .iter()
+        .flat_map(|segment| &segment.sub_segments)
+        .flat_map(|sub| &sub.words)
+        .map(|word| json!({ "word": word.word.trim(), "start": word.start, "end": word.end }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::agreement::test_segment;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    struct FixedRecognizer;
+
+    impl SpeechRecognizer for FixedRecognizer {
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+
+        fn transcribe(&mut self, pcm: &[f32], _language: Option<&str>) -> Result<Vec<Segment>> {
+            let duration = pcm.len() as f64 / SAMPLE_RATE as f64;
+            Ok(vec![test_segment(" hello world", 0.0, duration, 0, false)])
+        }
+    }
+
+    fn wav_bytes(secs: f32) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = std::io::Cursor::new(vec![]);
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for _ in 0..(secs * SAMPLE_RATE as f32) as usize {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    async fn post(router: &Router, fields: &[(&str, &str)]) -> (StatusCode, String) {
+        let boundary = "fl-caption-test";
+        let mut body = vec![];
+        for (name, value) in fields {
+            body.extend(
+                format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.wav\"\r\nContent-Type: audio/wav\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend(wav_bytes(2.0));
+        body.extend(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let request = Request::post("/v1/audio/transcriptions")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_transcriptions_response_formats() {
+        let router = router(Box::new(FixedRecognizer), false);
+
+        let (status, body) = post(&router, &[("model", "whisper-1")]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"{"text":"hello world"}"#);
+
+        let (_, body) = post(&router, &[("response_format", "verbose_json")]).await;
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["duration"], 2.0);
+        assert_eq!(body["segments"][0]["text"], "hello world");
+        assert!(body.get("words").is_none());
+
+        let (_, body) = post(&router, &[("response_format", "srt")]).await;
+        assert!(body.starts_with("1\n00:00:00,000 --> "), "{body}");
+
+        let (status, body) = post(
+            &router,
+            &[
+                ("response_format", "verbose_json"),
+                ("timestamp_granularities[]", "word"),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("invalid_request_error"));
+    }
+}
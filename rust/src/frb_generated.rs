@@ -119,23 +119,12 @@ fn wire__crate__api__whisper__launch_caption_impl(
     whisper_client: impl CstDecode<crate::api::whisper::WhisperClient>,
     stream_sink: impl CstDecode<
         StreamSink<
-            Vec<crate::candle_models::whisper::model::Segment>,
+            crate::caption::event::CaptionEvent,
             flutter_rust_bridge::for_generated::DcoCodec,
         >,
     >,
-    audio_device: impl CstDecode<Option<String>>,
-    audio_device_is_input: impl CstDecode<Option<bool>>,
-    audio_language: impl CstDecode<Option<String>>,
     cancel_token_id: impl CstDecode<String>,
-    with_timestamps: impl CstDecode<Option<bool>>,
-    verbose: impl CstDecode<Option<bool>>,
-    try_with_cuda: impl CstDecode<Option<bool>>,
-    whisper_max_audio_duration: impl CstDecode<Option<u32>>,
-    inference_interval: impl CstDecode<Option<u64>>,
-    whisper_default_max_decode_tokens: impl CstDecode<Option<usize>>,
-    whisper_temperature: impl CstDecode<Option<f32>>,
-    vad_model_path: impl CstDecode<Option<String>>,
-    vad_filters_value: impl CstDecode<Option<f32>>,
+    config: impl CstDecode<crate::caption::config::CaptionConfig>,
 ) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap_async::<flutter_rust_bridge::for_generated::DcoCodec, _, _, _>(
         flutter_rust_bridge::for_generated::TaskInfo {
@@ -146,39 +135,16 @@ fn wire__crate__api__whisper__launch_caption_impl(
         move || {
             let api_whisper_client = whisper_client.cst_decode();
             let api_stream_sink = stream_sink.cst_decode();
-            let api_audio_device = audio_device.cst_decode();
-            let api_audio_device_is_input = audio_device_is_input.cst_decode();
-            let api_audio_language = audio_language.cst_decode();
             let api_cancel_token_id = cancel_token_id.cst_decode();
-            let api_with_timestamps = with_timestamps.cst_decode();
-            let api_verbose = verbose.cst_decode();
-            let api_try_with_cuda = try_with_cuda.cst_decode();
-            let api_whisper_max_audio_duration = whisper_max_audio_duration.cst_decode();
-            let api_inference_interval = inference_interval.cst_decode();
-            let api_whisper_default_max_decode_tokens =
-                whisper_default_max_decode_tokens.cst_decode();
-            let api_whisper_temperature = whisper_temperature.cst_decode();
-            let api_vad_model_path = vad_model_path.cst_decode();
-            let api_vad_filters_value = vad_filters_value.cst_decode();
+            let api_config = config.cst_decode();
             move |context| async move {
                 transform_result_dco::<_, _, flutter_rust_bridge::for_generated::anyhow::Error>(
                     (move || async move {
                         let output_ok = crate::api::whisper::launch_caption(
                             api_whisper_client,
                             api_stream_sink,
-                            api_audio_device,
-                            api_audio_device_is_input,
-                            api_audio_language,
                             api_cancel_token_id,
-                            api_with_timestamps,
-                            api_verbose,
-                            api_try_with_cuda,
-                            api_whisper_max_audio_duration,
-                            api_inference_interval,
-                            api_whisper_default_max_decode_tokens,
-                            api_whisper_temperature,
-                            api_vad_model_path,
-                            api_vad_filters_value,
+                            api_config,
                         )
                         .await?;
                         Ok(output_ok)
@@ -189,6 +155,58 @@ fn wire__crate__api__whisper__launch_caption_impl(
         },
     )
 }
+fn wire__crate__api__audio__list_audio_devices_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::DcoCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "list_audio_devices",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            move |context| {
+                transform_result_dco::<_, _, flutter_rust_bridge::for_generated::anyhow::Error>(
+                    (move || {
+                        let output_ok = crate::api::audio::list_audio_devices()?;
+                        Ok(output_ok)
+                    })(),
+                )
+            }
+        },
+    )
+}
+fn wire__crate__api__whisper__transcribe_file_impl(
+    port_: flutter_rust_bridge::for_generated::MessagePort,
+    whisper_client: impl CstDecode<crate::api::whisper::WhisperClient>,
+    file_path: impl CstDecode<String>,
+    config: impl CstDecode<crate::caption::config::CaptionConfig>,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_normal::<flutter_rust_bridge::for_generated::DcoCodec, _, _>(
+        flutter_rust_bridge::for_generated::TaskInfo {
+            debug_name: "transcribe_file",
+            port: Some(port_),
+            mode: flutter_rust_bridge::for_generated::FfiCallMode::Normal,
+        },
+        move || {
+            let api_whisper_client = whisper_client.cst_decode();
+            let api_file_path = file_path.cst_decode();
+            let api_config = config.cst_decode();
+            move |context| {
+                transform_result_dco::<_, _, flutter_rust_bridge::for_generated::anyhow::Error>(
+                    (move || {
+                        let output_ok = crate::api::whisper::transcribe_file(
+                            api_whisper_client,
+                            api_file_path,
+                            api_config,
+                        )?;
+                        Ok(output_ok)
+                    })(),
+                )
+            }
+        },
+    )
+}
 fn wire__crate__api__whisper__whisper_client_new_impl(
     port_: flutter_rust_bridge::for_generated::MessagePort,
     models: impl CstDecode<std::collections::HashMap<String, String>>,
@@ -230,12 +248,47 @@ fn wire__crate__api__whisper__whisper_client_new_impl(
 
 // Section: dart2rust
 
+impl CstDecode<crate::audio_capture::traits::AudioDeviceDirection> for i32 {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    fn cst_decode(self) -> crate::audio_capture::traits::AudioDeviceDirection {
+        match self {
+            0 => crate::audio_capture::traits::AudioDeviceDirection::Input,
+            1 => crate::audio_capture::traits::AudioDeviceDirection::Monitor,
+            _ => unreachable!("Invalid variant for AudioDeviceDirection: {}", self),
+        }
+    }
+}
 impl CstDecode<bool> for bool {
     // Codec=Cst (C-struct based), see doc to use other codecs
     fn cst_decode(self) -> bool {
         self
     }
 }
+impl CstDecode<crate::caption::event::CaptionErrorKind> for i32 {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    fn cst_decode(self) -> crate::caption::event::CaptionErrorKind {
+        match self {
+            0 => crate::caption::event::CaptionErrorKind::ModelLoad,
+            1 => crate::caption::event::CaptionErrorKind::AudioCapture,
+            2 => crate::caption::event::CaptionErrorKind::Vad,
+            3 => crate::caption::event::CaptionErrorKind::Inference,
+            4 => crate::caption::event::CaptionErrorKind::Internal,
+            5 => crate::caption::event::CaptionErrorKind::Config,
+            _ => unreachable!("Invalid variant for CaptionErrorKind: {}", self),
+        }
+    }
+}
+impl CstDecode<crate::caption::event::CaptionExitReason> for i32 {
+    // Codec=Cst (C-struct based), see doc to use other codecs
+    fn cst_decode(self) -> crate::caption::event::CaptionExitReason {
+        match self {
+            0 => crate::caption::event::CaptionExitReason::Cancelled,
+            1 => crate::caption::event::CaptionExitReason::EndOfStream,
+            2 => crate::caption::event::CaptionExitReason::Error,
+            _ => unreachable!("Invalid variant for CaptionExitReason: {}", self),
+        }
+    }
+}
 impl CstDecode<f32> for f32 {
     // Codec=Cst (C-struct based), see doc to use other codecs
     fn cst_decode(self) -> f32 {
@@ -309,7 +362,7 @@ impl SseDecode for std::collections::HashMap<String, String> {
 
 impl SseDecode
     for StreamSink<
-        Vec<crate::candle_models::whisper::model::Segment>,
+        crate::caption::event::CaptionEvent,
         flutter_rust_bridge::for_generated::DcoCodec,
     >
 {
@@ -336,6 +389,39 @@ impl SseDecode for u128 {
     }
 }
 
+impl SseDecode for crate::audio_capture::traits::AudioDeviceDirection {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::audio_capture::traits::AudioDeviceDirection::Input,
+            1 => crate::audio_capture::traits::AudioDeviceDirection::Monitor,
+            _ => unreachable!("Invalid variant for AudioDeviceDirection: {}", inner),
+        };
+    }
+}
+
+impl SseDecode for crate::audio_capture::traits::AudioDeviceInfo {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_name = <String>::sse_decode(deserializer);
+        let mut var_id = <String>::sse_decode(deserializer);
+        let mut var_direction =
+            <crate::audio_capture::traits::AudioDeviceDirection>::sse_decode(deserializer);
+        let mut var_isDefault = <bool>::sse_decode(deserializer);
+        let mut var_sampleRate = <u32>::sse_decode(deserializer);
+        let mut var_channels = <u32>::sse_decode(deserializer);
+        return crate::audio_capture::traits::AudioDeviceInfo {
+            name: var_name,
+            id: var_id,
+            direction: var_direction,
+            is_default: var_isDefault,
+            sample_rate: var_sampleRate,
+            channels: var_channels,
+        };
+    }
+}
+
 impl SseDecode for bool {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -343,6 +429,172 @@ impl SseDecode for bool {
     }
 }
 
+impl SseDecode for crate::caption::config::CaptionConfig {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_audioDevice = <Option<String>>::sse_decode(deserializer);
+        let mut var_audioDeviceIsInput = <bool>::sse_decode(deserializer);
+        let mut var_language = <Option<String>>::sse_decode(deserializer);
+        let mut var_withTimestamps = <bool>::sse_decode(deserializer);
+        let mut var_wordTimestamps = <bool>::sse_decode(deserializer);
+        let mut var_verbose = <bool>::sse_decode(deserializer);
+        let mut var_tryWithCuda = <bool>::sse_decode(deserializer);
+        let mut var_maxAudioDurationSecs = <u32>::sse_decode(deserializer);
+        let mut var_inferenceIntervalMs = <u64>::sse_decode(deserializer);
+        let mut var_inferenceTimeoutMs = <Option<u64>>::sse_decode(deserializer);
+        let mut var_maxTokensPerSegment = <Option<u32>>::sse_decode(deserializer);
+        let mut var_temperature = <Option<f32>>::sse_decode(deserializer);
+        let mut var_vadModelPath = <Option<String>>::sse_decode(deserializer);
+        let mut var_vadThreshold = <Option<f32>>::sse_decode(deserializer);
+        let mut var_localAgreement = <Option<u32>>::sse_decode(deserializer);
+        let mut var_endpointSilenceMs = <Option<u32>>::sse_decode(deserializer);
+        let mut var_subtitlePath = <Option<String>>::sse_decode(deserializer);
+        let mut var_recordPath = <Option<String>>::sse_decode(deserializer);
+        let mut var_broadcastAddr = <Option<String>>::sse_decode(deserializer);
+        let mut var_obsTextPath = <Option<String>>::sse_decode(deserializer);
+        let mut var_obsTextLines = <u32>::sse_decode(deserializer);
+        let mut var_obsWebsocketUrl = <Option<String>>::sse_decode(deserializer);
+        let mut var_obsWebsocketPassword = <Option<String>>::sse_decode(deserializer);
+        let mut var_beamSize = <Option<u32>>::sse_decode(deserializer);
+        let mut var_beamPatience = <Option<f32>>::sse_decode(deserializer);
+        let mut var_lengthPenalty = <Option<f32>>::sse_decode(deserializer);
+        let mut var_initialPrompt = <Option<String>>::sse_decode(deserializer);
+        let mut var_conditionOnPreviousText = <Option<u32>>::sse_decode(deserializer);
+        let mut var_translate = <bool>::sse_decode(deserializer);
+        let mut var_redetectLanguage = <bool>::sse_decode(deserializer);
+        let mut var_languageSwitchThreshold = <Option<f32>>::sse_decode(deserializer);
+        let mut var_languageSwitchWindows = <Option<u32>>::sse_decode(deserializer);
+        let mut var_inlineEmoji = <bool>::sse_decode(deserializer);
+        let mut var_withItn = <bool>::sse_decode(deserializer);
+        return crate::caption::config::CaptionConfig {
+            audio_device: var_audioDevice,
+            audio_device_is_input: var_audioDeviceIsInput,
+            language: var_language,
+            with_timestamps: var_withTimestamps,
+            word_timestamps: var_wordTimestamps,
+            verbose: var_verbose,
+            try_with_cuda: var_tryWithCuda,
+            max_audio_duration_secs: var_maxAudioDurationSecs,
+            inference_interval_ms: var_inferenceIntervalMs,
+            inference_timeout_ms: var_inferenceTimeoutMs,
+            max_tokens_per_segment: var_maxTokensPerSegment,
+            temperature: var_temperature,
+            vad_model_path: var_vadModelPath,
+            vad_threshold: var_vadThreshold,
+            local_agreement: var_localAgreement,
+            endpoint_silence_ms: var_endpointSilenceMs,
+            subtitle_path: var_subtitlePath,
+            record_path: var_recordPath,
+            broadcast_addr: var_broadcastAddr,
+            obs_text_path: var_obsTextPath,
+            obs_text_lines: var_obsTextLines,
+            obs_websocket_url: var_obsWebsocketUrl,
+            obs_websocket_password: var_obsWebsocketPassword,
+            beam_size: var_beamSize,
+            beam_patience: var_beamPatience,
+            length_penalty: var_lengthPenalty,
+            initial_prompt: var_initialPrompt,
+            condition_on_previous_text: var_conditionOnPreviousText,
+            translate: var_translate,
+            redetect_language: var_redetectLanguage,
+            language_switch_threshold: var_languageSwitchThreshold,
+            language_switch_windows: var_languageSwitchWindows,
+            inline_emoji: var_inlineEmoji,
+            with_itn: var_withItn,
+        };
+    }
+}
+
+impl SseDecode for crate::caption::event::CaptionErrorKind {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::caption::event::CaptionErrorKind::ModelLoad,
+            1 => crate::caption::event::CaptionErrorKind::AudioCapture,
+            2 => crate::caption::event::CaptionErrorKind::Vad,
+            3 => crate::caption::event::CaptionErrorKind::Inference,
+            4 => crate::caption::event::CaptionErrorKind::Internal,
+            5 => crate::caption::event::CaptionErrorKind::Config,
+            _ => unreachable!("Invalid variant for CaptionErrorKind: {}", inner),
+        };
+    }
+}
+
+impl SseDecode for crate::caption::event::CaptionEvent {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut tag_ = <i32>::sse_decode(deserializer);
+        match tag_ {
+            0 => {
+                let mut var_progress = <Option<f32>>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Loading {
+                    progress: var_progress,
+                };
+            }
+            1 => {
+                let mut var_backend = <String>::sse_decode(deserializer);
+                let mut var_device = <String>::sse_decode(deserializer);
+                let mut var_providers = <Vec<String>>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Ready {
+                    backend: var_backend,
+                    device: var_device,
+                    providers: var_providers,
+                };
+            }
+            2 => {
+                let mut var_segments =
+                    <Vec<crate::candle_models::whisper::model::Segment>>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Segments {
+                    segments: var_segments,
+                };
+            }
+            3 => {
+                let mut var_speaking = <bool>::sse_decode(deserializer);
+                let mut var_probability = <f32>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::VadActivity {
+                    speaking: var_speaking,
+                    probability: var_probability,
+                };
+            }
+            4 => {
+                let mut var_msg = <String>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Warning { msg: var_msg };
+            }
+            5 => {
+                let mut var_kind =
+                    <crate::caption::event::CaptionErrorKind>::sse_decode(deserializer);
+                let mut var_msg = <String>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Error {
+                    kind: var_kind,
+                    msg: var_msg,
+                };
+            }
+            6 => {
+                let mut var_reason =
+                    <crate::caption::event::CaptionExitReason>::sse_decode(deserializer);
+                return crate::caption::event::CaptionEvent::Exit { reason: var_reason };
+            }
+            _ => {
+                unimplemented!("");
+            }
+        }
+    }
+}
+
+impl SseDecode for crate::caption::event::CaptionExitReason {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut inner = <i32>::sse_decode(deserializer);
+        return match inner {
+            0 => crate::caption::event::CaptionExitReason::Cancelled,
+            1 => crate::caption::event::CaptionExitReason::EndOfStream,
+            2 => crate::caption::event::CaptionExitReason::Error,
+            _ => unreachable!("Invalid variant for CaptionExitReason: {}", inner),
+        };
+    }
+}
+
 impl SseDecode for crate::candle_models::whisper::model::DecodingResult {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -384,6 +636,32 @@ impl SseDecode for i32 {
     }
 }
 
+impl SseDecode for Vec<String> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<String>::sse_decode(deserializer));
+        }
+        return ans_;
+    }
+}
+
+impl SseDecode for Vec<crate::audio_capture::traits::AudioDeviceInfo> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<crate::audio_capture::traits::AudioDeviceInfo>::sse_decode(
+                deserializer,
+            ));
+        }
+        return ans_;
+    }
+}
+
 impl SseDecode for Vec<u32> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -434,6 +712,42 @@ impl SseDecode for Vec<crate::candle_models::whisper::model::Segment> {
     }
 }
 
+impl SseDecode for Vec<crate::candle_models::whisper::model::SubSegment> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<crate::candle_models::whisper::model::SubSegment>::sse_decode(deserializer));
+        }
+        return ans_;
+    }
+}
+
+impl SseDecode for Vec<crate::candle_models::whisper::model::WordTiming> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<crate::candle_models::whisper::model::WordTiming>::sse_decode(deserializer));
+        }
+        return ans_;
+    }
+}
+
+impl SseDecode for Vec<crate::candle_models::whisper::model::LanguageProbability> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut len_ = <i32>::sse_decode(deserializer);
+        let mut ans_ = vec![];
+        for idx_ in 0..len_ {
+            ans_.push(<crate::candle_models::whisper::model::LanguageProbability>::sse_decode(deserializer));
+        }
+        return ans_;
+    }
+}
+
 impl SseDecode for Option<String> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
@@ -532,6 +846,13 @@ impl SseDecode for crate::candle_models::whisper::model::Segment {
         let mut var_audioDuration = <Option<u128>>::sse_decode(deserializer);
         let mut var_status =
             <crate::candle_models::whisper::model::WhisperStatus>::sse_decode(deserializer);
+        let mut var_subSegments =
+            <Vec<crate::candle_models::whisper::model::SubSegment>>::sse_decode(deserializer);
+        let mut var_id = <u64>::sse_decode(deserializer);
+        let mut var_committed = <bool>::sse_decode(deserializer);
+        let mut var_languageProbs = <Vec<crate::candle_models::whisper::model::LanguageProbability>>::sse_decode(deserializer);
+        let mut var_emotion = <Option<String>>::sse_decode(deserializer);
+        let mut var_audioEvent = <Option<String>>::sse_decode(deserializer);
         return crate::candle_models::whisper::model::Segment {
             start: var_start,
             duration: var_duration,
@@ -540,6 +861,29 @@ impl SseDecode for crate::candle_models::whisper::model::Segment {
             reasoning_lang: var_reasoningLang,
             audio_duration: var_audioDuration,
             status: var_status,
+            sub_segments: var_subSegments,
+            id: var_id,
+            committed: var_committed,
+            language_probs: var_languageProbs,
+            emotion: var_emotion,
+            audio_event: var_audioEvent,
+        };
+    }
+}
+
+impl SseDecode for crate::candle_models::whisper::model::SubSegment {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_start = <f64>::sse_decode(deserializer);
+        let mut var_end = <f64>::sse_decode(deserializer);
+        let mut var_text = <String>::sse_decode(deserializer);
+        let mut var_words =
+            <Vec<crate::candle_models::whisper::model::WordTiming>>::sse_decode(deserializer);
+        return crate::candle_models::whisper::model::SubSegment {
+            start: var_start,
+            end: var_end,
+            text: var_text,
+            words: var_words,
         };
     }
 }
@@ -612,6 +956,32 @@ impl SseDecode for crate::candle_models::whisper::model::WhisperStatus {
     }
 }
 
+impl SseDecode for crate::candle_models::whisper::model::WordTiming {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_start = <f64>::sse_decode(deserializer);
+        let mut var_end = <f64>::sse_decode(deserializer);
+        let mut var_word = <String>::sse_decode(deserializer);
+        return crate::candle_models::whisper::model::WordTiming {
+            start: var_start,
+            end: var_end,
+            word: var_word,
+        };
+    }
+}
+
+impl SseDecode for crate::candle_models::whisper::model::LanguageProbability {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_decode(deserializer: &mut flutter_rust_bridge::for_generated::SseDeserializer) -> Self {
+        let mut var_language = <String>::sse_decode(deserializer);
+        let mut var_probability = <f32>::sse_decode(deserializer);
+        return crate::candle_models::whisper::model::LanguageProbability {
+            language: var_language,
+            probability: var_probability,
+        };
+    }
+}
+
 fn pde_ffi_dispatcher_primary_impl(
     func_id: i32,
     port: flutter_rust_bridge::for_generated::MessagePort,
@@ -640,123 +1010,400 @@ fn pde_ffi_dispatcher_sync_impl(
 // Section: rust2dart
 
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::DecodingResult {
+impl flutter_rust_bridge::IntoDart for crate::audio_capture::traits::AudioDeviceDirection {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
-        [
-            self.tokens.into_into_dart().into_dart(),
-            self.text.into_into_dart().into_dart(),
-            self.avg_logprob.into_into_dart().into_dart(),
-            self.no_speech_prob.into_into_dart().into_dart(),
-            self.temperature.into_into_dart().into_dart(),
-            self.compression_ratio.into_into_dart().into_dart(),
-        ]
-        .into_dart()
+        match self {
+            Self::Input => 0.into_dart(),
+            Self::Monitor => 1.into_dart(),
+            _ => unreachable!(),
+        }
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::candle_models::whisper::model::DecodingResult
+    for crate::audio_capture::traits::AudioDeviceDirection
 {
 }
-impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::DecodingResult>
-    for crate::candle_models::whisper::model::DecodingResult
+impl flutter_rust_bridge::IntoIntoDart<crate::audio_capture::traits::AudioDeviceDirection>
+    for crate::audio_capture::traits::AudioDeviceDirection
 {
-    fn into_into_dart(self) -> crate::candle_models::whisper::model::DecodingResult {
+    fn into_into_dart(self) -> crate::audio_capture::traits::AudioDeviceDirection {
         self
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::Segment {
+impl flutter_rust_bridge::IntoDart for crate::audio_capture::traits::AudioDeviceInfo {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
         [
-            self.start.into_into_dart().into_dart(),
-            self.duration.into_into_dart().into_dart(),
-            self.dr.into_into_dart().into_dart(),
-            self.reasoning_duration.into_into_dart().into_dart(),
-            self.reasoning_lang.into_into_dart().into_dart(),
-            self.audio_duration.into_into_dart().into_dart(),
-            self.status.into_into_dart().into_dart(),
+            self.name.into_into_dart().into_dart(),
+            self.id.into_into_dart().into_dart(),
+            self.direction.into_into_dart().into_dart(),
+            self.is_default.into_into_dart().into_dart(),
+            self.sample_rate.into_into_dart().into_dart(),
+            self.channels.into_into_dart().into_dart(),
         ]
         .into_dart()
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::candle_models::whisper::model::Segment
+    for crate::audio_capture::traits::AudioDeviceInfo
 {
 }
-impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::Segment>
-    for crate::candle_models::whisper::model::Segment
+impl flutter_rust_bridge::IntoIntoDart<crate::audio_capture::traits::AudioDeviceInfo>
+    for crate::audio_capture::traits::AudioDeviceInfo
 {
-    fn into_into_dart(self) -> crate::candle_models::whisper::model::Segment {
+    fn into_into_dart(self) -> crate::audio_capture::traits::AudioDeviceInfo {
         self
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::api::whisper::WhisperClient {
+impl flutter_rust_bridge::IntoDart for crate::caption::config::CaptionConfig {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
         [
-            self.models.into_into_dart().into_dart(),
-            self.config.into_into_dart().into_dart(),
-            self.tokenizer.into_into_dart().into_dart(),
-            self.is_multilingual.into_into_dart().into_dart(),
-            self.is_quantized.into_into_dart().into_dart(),
-            self.model_type.into_into_dart().into_dart(),
+            self.audio_device.into_into_dart().into_dart(),
+            self.audio_device_is_input.into_into_dart().into_dart(),
+            self.language.into_into_dart().into_dart(),
+            self.with_timestamps.into_into_dart().into_dart(),
+            self.word_timestamps.into_into_dart().into_dart(),
+            self.verbose.into_into_dart().into_dart(),
+            self.try_with_cuda.into_into_dart().into_dart(),
+            self.max_audio_duration_secs.into_into_dart().into_dart(),
+            self.inference_interval_ms.into_into_dart().into_dart(),
+            self.inference_timeout_ms.into_into_dart().into_dart(),
+            self.max_tokens_per_segment.into_into_dart().into_dart(),
+            self.temperature.into_into_dart().into_dart(),
+            self.vad_model_path.into_into_dart().into_dart(),
+            self.vad_threshold.into_into_dart().into_dart(),
+            self.local_agreement.into_into_dart().into_dart(),
+            self.endpoint_silence_ms.into_into_dart().into_dart(),
+            self.subtitle_path.into_into_dart().into_dart(),
+            self.record_path.into_into_dart().into_dart(),
+            self.broadcast_addr.into_into_dart().into_dart(),
+            self.obs_text_path.into_into_dart().into_dart(),
+            self.obs_text_lines.into_into_dart().into_dart(),
+            self.obs_websocket_url.into_into_dart().into_dart(),
+            self.obs_websocket_password.into_into_dart().into_dart(),
+            self.beam_size.into_into_dart().into_dart(),
+            self.beam_patience.into_into_dart().into_dart(),
+            self.length_penalty.into_into_dart().into_dart(),
+            self.initial_prompt.into_into_dart().into_dart(),
+            self.condition_on_previous_text.into_into_dart().into_dart(),
+            self.translate.into_into_dart().into_dart(),
+            self.redetect_language.into_into_dart().into_dart(),
+            self.language_switch_threshold.into_into_dart().into_dart(),
+            self.language_switch_windows.into_into_dart().into_dart(),
+            self.inline_emoji.into_into_dart().into_dart(),
+            self.with_itn.into_into_dart().into_dart(),
         ]
         .into_dart()
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::api::whisper::WhisperClient
+    for crate::caption::config::CaptionConfig
 {
 }
-impl flutter_rust_bridge::IntoIntoDart<crate::api::whisper::WhisperClient>
-    for crate::api::whisper::WhisperClient
+impl flutter_rust_bridge::IntoIntoDart<crate::caption::config::CaptionConfig>
+    for crate::caption::config::CaptionConfig
 {
-    fn into_into_dart(self) -> crate::api::whisper::WhisperClient {
+    fn into_into_dart(self) -> crate::caption::config::CaptionConfig {
         self
     }
 }
 // Codec=Dco (DartCObject based), see doc to use other codecs
-impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::WhisperStatus {
+impl flutter_rust_bridge::IntoDart for crate::caption::event::CaptionErrorKind {
     fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
         match self {
-            Self::Loading => 0.into_dart(),
-            Self::Ready => 1.into_dart(),
-            Self::Error => 2.into_dart(),
-            Self::Working => 3.into_dart(),
-            Self::Exit => 4.into_dart(),
+            Self::ModelLoad => 0.into_dart(),
+            Self::AudioCapture => 1.into_dart(),
+            Self::Vad => 2.into_dart(),
+            Self::Inference => 3.into_dart(),
+            Self::Internal => 4.into_dart(),
+            Self::Config => 5.into_dart(),
             _ => unreachable!(),
         }
     }
 }
 impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
-    for crate::candle_models::whisper::model::WhisperStatus
+    for crate::caption::event::CaptionErrorKind
 {
 }
-impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::WhisperStatus>
-    for crate::candle_models::whisper::model::WhisperStatus
+impl flutter_rust_bridge::IntoIntoDart<crate::caption::event::CaptionErrorKind>
+    for crate::caption::event::CaptionErrorKind
 {
-    fn into_into_dart(self) -> crate::candle_models::whisper::model::WhisperStatus {
+    fn into_into_dart(self) -> crate::caption::event::CaptionErrorKind {
         self
     }
 }
-
-impl SseEncode for flutter_rust_bridge::for_generated::anyhow::Error {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <String>::sse_encode(format!("{:?}", self), serializer);
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::caption::event::CaptionEvent {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            crate::caption::event::CaptionEvent::Loading { progress } => {
+                [0.into_dart(), progress.into_into_dart().into_dart()].into_dart()
+            }
+            crate::caption::event::CaptionEvent::Ready {
+                backend,
+                device,
+                providers,
+            } => [
+                1.into_dart(),
+                backend.into_into_dart().into_dart(),
+                device.into_into_dart().into_dart(),
+                providers.into_into_dart().into_dart(),
+            ]
+            .into_dart(),
+            crate::caption::event::CaptionEvent::Segments { segments } => {
+                [2.into_dart(), segments.into_into_dart().into_dart()].into_dart()
+            }
+            crate::caption::event::CaptionEvent::VadActivity {
+                speaking,
+                probability,
+            } => [
+                3.into_dart(),
+                speaking.into_into_dart().into_dart(),
+                probability.into_into_dart().into_dart(),
+            ]
+            .into_dart(),
+            crate::caption::event::CaptionEvent::Warning { msg } => {
+                [4.into_dart(), msg.into_into_dart().into_dart()].into_dart()
+            }
+            crate::caption::event::CaptionEvent::Error { kind, msg } => [
+                5.into_dart(),
+                kind.into_into_dart().into_dart(),
+                msg.into_into_dart().into_dart(),
+            ]
+            .into_dart(),
+            crate::caption::event::CaptionEvent::Exit { reason } => {
+                [6.into_dart(), reason.into_into_dart().into_dart()].into_dart()
+            }
+            _ => {
+                unimplemented!("");
+            }
+        }
     }
 }
-
-impl SseEncode for std::collections::HashMap<String, String> {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
-        <Vec<(String, String)>>::sse_encode(self.into_iter().collect(), serializer);
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::caption::event::CaptionEvent
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::caption::event::CaptionEvent>
+    for crate::caption::event::CaptionEvent
+{
+    fn into_into_dart(self) -> crate::caption::event::CaptionEvent {
+        self
     }
 }
-
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::caption::event::CaptionExitReason {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::Cancelled => 0.into_dart(),
+            Self::EndOfStream => 1.into_dart(),
+            Self::Error => 2.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::caption::event::CaptionExitReason
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::caption::event::CaptionExitReason>
+    for crate::caption::event::CaptionExitReason
+{
+    fn into_into_dart(self) -> crate::caption::event::CaptionExitReason {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::DecodingResult {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.tokens.into_into_dart().into_dart(),
+            self.text.into_into_dart().into_dart(),
+            self.avg_logprob.into_into_dart().into_dart(),
+            self.no_speech_prob.into_into_dart().into_dart(),
+            self.temperature.into_into_dart().into_dart(),
+            self.compression_ratio.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::DecodingResult
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::DecodingResult>
+    for crate::candle_models::whisper::model::DecodingResult
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::DecodingResult {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::Segment {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.start.into_into_dart().into_dart(),
+            self.duration.into_into_dart().into_dart(),
+            self.dr.into_into_dart().into_dart(),
+            self.reasoning_duration.into_into_dart().into_dart(),
+            self.reasoning_lang.into_into_dart().into_dart(),
+            self.audio_duration.into_into_dart().into_dart(),
+            self.status.into_into_dart().into_dart(),
+            self.sub_segments.into_into_dart().into_dart(),
+            self.id.into_into_dart().into_dart(),
+            self.committed.into_into_dart().into_dart(),
+            self.language_probs.into_into_dart().into_dart(),
+            self.emotion.into_into_dart().into_dart(),
+            self.audio_event.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::Segment
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::Segment>
+    for crate::candle_models::whisper::model::Segment
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::Segment {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::SubSegment {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.start.into_into_dart().into_dart(),
+            self.end.into_into_dart().into_dart(),
+            self.text.into_into_dart().into_dart(),
+            self.words.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::SubSegment
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::SubSegment>
+    for crate::candle_models::whisper::model::SubSegment
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::SubSegment {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::api::whisper::WhisperClient {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.models.into_into_dart().into_dart(),
+            self.config.into_into_dart().into_dart(),
+            self.tokenizer.into_into_dart().into_dart(),
+            self.is_multilingual.into_into_dart().into_dart(),
+            self.is_quantized.into_into_dart().into_dart(),
+            self.model_type.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::api::whisper::WhisperClient
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::api::whisper::WhisperClient>
+    for crate::api::whisper::WhisperClient
+{
+    fn into_into_dart(self) -> crate::api::whisper::WhisperClient {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::WhisperStatus {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        match self {
+            Self::Loading => 0.into_dart(),
+            Self::Ready => 1.into_dart(),
+            Self::Error => 2.into_dart(),
+            Self::Working => 3.into_dart(),
+            Self::Exit => 4.into_dart(),
+            _ => unreachable!(),
+        }
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::WhisperStatus
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::WhisperStatus>
+    for crate::candle_models::whisper::model::WhisperStatus
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::WhisperStatus {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::WordTiming {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.start.into_into_dart().into_dart(),
+            self.end.into_into_dart().into_dart(),
+            self.word.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::WordTiming
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::WordTiming>
+    for crate::candle_models::whisper::model::WordTiming
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::WordTiming {
+        self
+    }
+}
+// Codec=Dco (DartCObject based), see doc to use other codecs
+impl flutter_rust_bridge::IntoDart for crate::candle_models::whisper::model::LanguageProbability {
+    fn into_dart(self) -> flutter_rust_bridge::for_generated::DartAbi {
+        [
+            self.language.into_into_dart().into_dart(),
+            self.probability.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl flutter_rust_bridge::for_generated::IntoDartExceptPrimitive
+    for crate::candle_models::whisper::model::LanguageProbability
+{
+}
+impl flutter_rust_bridge::IntoIntoDart<crate::candle_models::whisper::model::LanguageProbability>
+    for crate::candle_models::whisper::model::LanguageProbability
+{
+    fn into_into_dart(self) -> crate::candle_models::whisper::model::LanguageProbability {
+        self
+    }
+}
+
+impl SseEncode for flutter_rust_bridge::for_generated::anyhow::Error {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <String>::sse_encode(format!("{:?}", self), serializer);
+    }
+}
+
+impl SseEncode for std::collections::HashMap<String, String> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <Vec<(String, String)>>::sse_encode(self.into_iter().collect(), serializer);
+    }
+}
+
 impl SseEncode
     for StreamSink<
-        Vec<crate::candle_models::whisper::model::Segment>,
+        crate::caption::event::CaptionEvent,
         flutter_rust_bridge::for_generated::DcoCodec,
     >
 {
@@ -780,6 +1427,37 @@ impl SseEncode for u128 {
     }
 }
 
+impl SseEncode for crate::audio_capture::traits::AudioDeviceDirection {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::audio_capture::traits::AudioDeviceDirection::Input => 0,
+                crate::audio_capture::traits::AudioDeviceDirection::Monitor => 1,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
+impl SseEncode for crate::audio_capture::traits::AudioDeviceInfo {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <String>::sse_encode(self.name, serializer);
+        <String>::sse_encode(self.id, serializer);
+        <crate::audio_capture::traits::AudioDeviceDirection>::sse_encode(
+            self.direction,
+            serializer,
+        );
+        <bool>::sse_encode(self.is_default, serializer);
+        <u32>::sse_encode(self.sample_rate, serializer);
+        <u32>::sse_encode(self.channels, serializer);
+    }
+}
+
 impl SseEncode for bool {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -787,6 +1465,135 @@ impl SseEncode for bool {
     }
 }
 
+impl SseEncode for crate::caption::config::CaptionConfig {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <Option<String>>::sse_encode(self.audio_device, serializer);
+        <bool>::sse_encode(self.audio_device_is_input, serializer);
+        <Option<String>>::sse_encode(self.language, serializer);
+        <bool>::sse_encode(self.with_timestamps, serializer);
+        <bool>::sse_encode(self.word_timestamps, serializer);
+        <bool>::sse_encode(self.verbose, serializer);
+        <bool>::sse_encode(self.try_with_cuda, serializer);
+        <u32>::sse_encode(self.max_audio_duration_secs, serializer);
+        <u64>::sse_encode(self.inference_interval_ms, serializer);
+        <Option<u64>>::sse_encode(self.inference_timeout_ms, serializer);
+        <Option<u32>>::sse_encode(self.max_tokens_per_segment, serializer);
+        <Option<f32>>::sse_encode(self.temperature, serializer);
+        <Option<String>>::sse_encode(self.vad_model_path, serializer);
+        <Option<f32>>::sse_encode(self.vad_threshold, serializer);
+        <Option<u32>>::sse_encode(self.local_agreement, serializer);
+        <Option<u32>>::sse_encode(self.endpoint_silence_ms, serializer);
+        <Option<String>>::sse_encode(self.subtitle_path, serializer);
+        <Option<String>>::sse_encode(self.record_path, serializer);
+        <Option<String>>::sse_encode(self.broadcast_addr, serializer);
+        <Option<String>>::sse_encode(self.obs_text_path, serializer);
+        <u32>::sse_encode(self.obs_text_lines, serializer);
+        <Option<String>>::sse_encode(self.obs_websocket_url, serializer);
+        <Option<String>>::sse_encode(self.obs_websocket_password, serializer);
+        <Option<u32>>::sse_encode(self.beam_size, serializer);
+        <Option<f32>>::sse_encode(self.beam_patience, serializer);
+        <Option<f32>>::sse_encode(self.length_penalty, serializer);
+        <Option<String>>::sse_encode(self.initial_prompt, serializer);
+        <Option<u32>>::sse_encode(self.condition_on_previous_text, serializer);
+        <bool>::sse_encode(self.translate, serializer);
+        <bool>::sse_encode(self.redetect_language, serializer);
+        <Option<f32>>::sse_encode(self.language_switch_threshold, serializer);
+        <Option<u32>>::sse_encode(self.language_switch_windows, serializer);
+        <bool>::sse_encode(self.inline_emoji, serializer);
+        <bool>::sse_encode(self.with_itn, serializer);
+    }
+}
+
+impl SseEncode for crate::caption::event::CaptionErrorKind {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::caption::event::CaptionErrorKind::ModelLoad => 0,
+                crate::caption::event::CaptionErrorKind::AudioCapture => 1,
+                crate::caption::event::CaptionErrorKind::Vad => 2,
+                crate::caption::event::CaptionErrorKind::Inference => 3,
+                crate::caption::event::CaptionErrorKind::Internal => 4,
+                crate::caption::event::CaptionErrorKind::Config => 5,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
+impl SseEncode for crate::caption::event::CaptionEvent {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        match self {
+            crate::caption::event::CaptionEvent::Loading { progress } => {
+                <i32>::sse_encode(0, serializer);
+                <Option<f32>>::sse_encode(progress, serializer);
+            }
+            crate::caption::event::CaptionEvent::Ready {
+                backend,
+                device,
+                providers,
+            } => {
+                <i32>::sse_encode(1, serializer);
+                <String>::sse_encode(backend, serializer);
+                <String>::sse_encode(device, serializer);
+                <Vec<String>>::sse_encode(providers, serializer);
+            }
+            crate::caption::event::CaptionEvent::Segments { segments } => {
+                <i32>::sse_encode(2, serializer);
+                <Vec<crate::candle_models::whisper::model::Segment>>::sse_encode(
+                    segments, serializer,
+                );
+            }
+            crate::caption::event::CaptionEvent::VadActivity {
+                speaking,
+                probability,
+            } => {
+                <i32>::sse_encode(3, serializer);
+                <bool>::sse_encode(speaking, serializer);
+                <f32>::sse_encode(probability, serializer);
+            }
+            crate::caption::event::CaptionEvent::Warning { msg } => {
+                <i32>::sse_encode(4, serializer);
+                <String>::sse_encode(msg, serializer);
+            }
+            crate::caption::event::CaptionEvent::Error { kind, msg } => {
+                <i32>::sse_encode(5, serializer);
+                <crate::caption::event::CaptionErrorKind>::sse_encode(kind, serializer);
+                <String>::sse_encode(msg, serializer);
+            }
+            crate::caption::event::CaptionEvent::Exit { reason } => {
+                <i32>::sse_encode(6, serializer);
+                <crate::caption::event::CaptionExitReason>::sse_encode(reason, serializer);
+            }
+            _ => {
+                unimplemented!("");
+            }
+        }
+    }
+}
+
+impl SseEncode for crate::caption::event::CaptionExitReason {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(
+            match self {
+                crate::caption::event::CaptionExitReason::Cancelled => 0,
+                crate::caption::event::CaptionExitReason::EndOfStream => 1,
+                crate::caption::event::CaptionExitReason::Error => 2,
+                _ => {
+                    unimplemented!("");
+                }
+            },
+            serializer,
+        );
+    }
+}
+
 impl SseEncode for crate::candle_models::whisper::model::DecodingResult {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -820,6 +1627,26 @@ impl SseEncode for i32 {
     }
 }
 
+impl SseEncode for Vec<String> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <String>::sse_encode(item, serializer);
+        }
+    }
+}
+
+impl SseEncode for Vec<crate::audio_capture::traits::AudioDeviceInfo> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::audio_capture::traits::AudioDeviceInfo>::sse_encode(item, serializer);
+        }
+    }
+}
+
 impl SseEncode for Vec<u32> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -860,6 +1687,36 @@ impl SseEncode for Vec<crate::candle_models::whisper::model::Segment> {
     }
 }
 
+impl SseEncode for Vec<crate::candle_models::whisper::model::SubSegment> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::candle_models::whisper::model::SubSegment>::sse_encode(item, serializer);
+        }
+    }
+}
+
+impl SseEncode for Vec<crate::candle_models::whisper::model::WordTiming> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::candle_models::whisper::model::WordTiming>::sse_encode(item, serializer);
+        }
+    }
+}
+
+impl SseEncode for Vec<crate::candle_models::whisper::model::LanguageProbability> {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <i32>::sse_encode(self.len() as _, serializer);
+        for item in self {
+            <crate::candle_models::whisper::model::LanguageProbability>::sse_encode(item, serializer);
+        }
+    }
+}
+
 impl SseEncode for Option<String> {
     // Codec=Sse (Serialization based), see doc to use other codecs
     fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
@@ -948,6 +1805,25 @@ impl SseEncode for crate::candle_models::whisper::model::Segment {
         <Option<String>>::sse_encode(self.reasoning_lang, serializer);
         <Option<u128>>::sse_encode(self.audio_duration, serializer);
         <crate::candle_models::whisper::model::WhisperStatus>::sse_encode(self.status, serializer);
+        <Vec<crate::candle_models::whisper::model::SubSegment>>::sse_encode(
+            self.sub_segments,
+            serializer,
+        );
+        <u64>::sse_encode(self.id, serializer);
+        <bool>::sse_encode(self.committed, serializer);
+        <Vec<crate::candle_models::whisper::model::LanguageProbability>>::sse_encode(self.language_probs, serializer);
+        <Option<String>>::sse_encode(self.emotion, serializer);
+        <Option<String>>::sse_encode(self.audio_event, serializer);
+    }
+}
+
+impl SseEncode for crate::candle_models::whisper::model::SubSegment {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <f64>::sse_encode(self.start, serializer);
+        <f64>::sse_encode(self.end, serializer);
+        <String>::sse_encode(self.text, serializer);
+        <Vec<crate::candle_models::whisper::model::WordTiming>>::sse_encode(self.words, serializer);
     }
 }
 
@@ -1018,6 +1894,23 @@ impl SseEncode for crate::candle_models::whisper::model::WhisperStatus {
     }
 }
 
+impl SseEncode for crate::candle_models::whisper::model::WordTiming {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <f64>::sse_encode(self.start, serializer);
+        <f64>::sse_encode(self.end, serializer);
+        <String>::sse_encode(self.word, serializer);
+    }
+}
+
+impl SseEncode for crate::candle_models::whisper::model::LanguageProbability {
+    // Codec=Sse (Serialization based), see doc to use other codecs
+    fn sse_encode(self, serializer: &mut flutter_rust_bridge::for_generated::SseSerializer) {
+        <String>::sse_encode(self.language, serializer);
+        <f32>::sse_encode(self.probability, serializer);
+    }
+}
+
 #[cfg(not(target_family = "wasm"))]
 mod io {
     // This file is automatically generated, so please do not edit it.
@@ -1058,7 +1951,7 @@ mod io {
     impl
         CstDecode<
             StreamSink<
-                Vec<crate::candle_models::whisper::model::Segment>,
+                crate::caption::event::CaptionEvent,
                 flutter_rust_bridge::for_generated::DcoCodec,
             >,
         > for *mut wire_cst_list_prim_u_8_strict
@@ -1067,7 +1960,7 @@ mod io {
         fn cst_decode(
             self,
         ) -> StreamSink<
-            Vec<crate::candle_models::whisper::model::Segment>,
+            crate::caption::event::CaptionEvent,
             flutter_rust_bridge::for_generated::DcoCodec,
         > {
             let raw: String = self.cst_decode();
@@ -1093,6 +1986,13 @@ mod io {
             unsafe { *flutter_rust_bridge::for_generated::box_from_leak_ptr(self) }
         }
     }
+    impl CstDecode<crate::caption::config::CaptionConfig> for *mut wire_cst_caption_config {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::caption::config::CaptionConfig {
+            let wrap = unsafe { flutter_rust_bridge::for_generated::box_from_leak_ptr(self) };
+            CstDecode::<crate::caption::config::CaptionConfig>::cst_decode(*wrap).into()
+        }
+    }
     impl CstDecode<f32> for *mut f32 {
         // Codec=Cst (C-struct based), see doc to use other codecs
         fn cst_decode(self) -> f32 {
@@ -1124,6 +2024,114 @@ mod io {
             CstDecode::<crate::api::whisper::WhisperClient>::cst_decode(*wrap).into()
         }
     }
+    impl CstDecode<crate::audio_capture::traits::AudioDeviceInfo> for wire_cst_audio_device_info {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::audio_capture::traits::AudioDeviceInfo {
+            crate::audio_capture::traits::AudioDeviceInfo {
+                name: self.name.cst_decode(),
+                id: self.id.cst_decode(),
+                direction: self.direction.cst_decode(),
+                is_default: self.is_default.cst_decode(),
+                sample_rate: self.sample_rate.cst_decode(),
+                channels: self.channels.cst_decode(),
+            }
+        }
+    }
+    impl CstDecode<crate::caption::config::CaptionConfig> for wire_cst_caption_config {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::caption::config::CaptionConfig {
+            crate::caption::config::CaptionConfig {
+                audio_device: self.audio_device.cst_decode(),
+                audio_device_is_input: self.audio_device_is_input.cst_decode(),
+                language: self.language.cst_decode(),
+                with_timestamps: self.with_timestamps.cst_decode(),
+                word_timestamps: self.word_timestamps.cst_decode(),
+                verbose: self.verbose.cst_decode(),
+                try_with_cuda: self.try_with_cuda.cst_decode(),
+                max_audio_duration_secs: self.max_audio_duration_secs.cst_decode(),
+                inference_interval_ms: self.inference_interval_ms.cst_decode(),
+                inference_timeout_ms: self.inference_timeout_ms.cst_decode(),
+                max_tokens_per_segment: self.max_tokens_per_segment.cst_decode(),
+                temperature: self.temperature.cst_decode(),
+                vad_model_path: self.vad_model_path.cst_decode(),
+                vad_threshold: self.vad_threshold.cst_decode(),
+                local_agreement: self.local_agreement.cst_decode(),
+                endpoint_silence_ms: self.endpoint_silence_ms.cst_decode(),
+                subtitle_path: self.subtitle_path.cst_decode(),
+                record_path: self.record_path.cst_decode(),
+                broadcast_addr: self.broadcast_addr.cst_decode(),
+                obs_text_path: self.obs_text_path.cst_decode(),
+                obs_text_lines: self.obs_text_lines.cst_decode(),
+                obs_websocket_url: self.obs_websocket_url.cst_decode(),
+                obs_websocket_password: self.obs_websocket_password.cst_decode(),
+                beam_size: self.beam_size.cst_decode(),
+                beam_patience: self.beam_patience.cst_decode(),
+                length_penalty: self.length_penalty.cst_decode(),
+                initial_prompt: self.initial_prompt.cst_decode(),
+                condition_on_previous_text: self.condition_on_previous_text.cst_decode(),
+                translate: self.translate.cst_decode(),
+                redetect_language: self.redetect_language.cst_decode(),
+                language_switch_threshold: self.language_switch_threshold.cst_decode(),
+                language_switch_windows: self.language_switch_windows.cst_decode(),
+                inline_emoji: self.inline_emoji.cst_decode(),
+                with_itn: self.with_itn.cst_decode(),
+            }
+        }
+    }
+    impl CstDecode<crate::caption::event::CaptionEvent> for wire_cst_caption_event {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::caption::event::CaptionEvent {
+            match self.tag {
+                0 => {
+                    let ans = unsafe { self.kind.Loading };
+                    crate::caption::event::CaptionEvent::Loading {
+                        progress: ans.progress.cst_decode(),
+                    }
+                }
+                1 => {
+                    let ans = unsafe { self.kind.Ready };
+                    crate::caption::event::CaptionEvent::Ready {
+                        backend: ans.backend.cst_decode(),
+                        device: ans.device.cst_decode(),
+                        providers: ans.providers.cst_decode(),
+                    }
+                }
+                2 => {
+                    let ans = unsafe { self.kind.Segments };
+                    crate::caption::event::CaptionEvent::Segments {
+                        segments: ans.segments.cst_decode(),
+                    }
+                }
+                3 => {
+                    let ans = unsafe { self.kind.VadActivity };
+                    crate::caption::event::CaptionEvent::VadActivity {
+                        speaking: ans.speaking.cst_decode(),
+                        probability: ans.probability.cst_decode(),
+                    }
+                }
+                4 => {
+                    let ans = unsafe { self.kind.Warning };
+                    crate::caption::event::CaptionEvent::Warning {
+                        msg: ans.msg.cst_decode(),
+                    }
+                }
+                5 => {
+                    let ans = unsafe { self.kind.Error };
+                    crate::caption::event::CaptionEvent::Error {
+                        kind: ans.kind.cst_decode(),
+                        msg: ans.msg.cst_decode(),
+                    }
+                }
+                6 => {
+                    let ans = unsafe { self.kind.Exit };
+                    crate::caption::event::CaptionEvent::Exit {
+                        reason: ans.reason.cst_decode(),
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
     impl CstDecode<crate::candle_models::whisper::model::DecodingResult> for wire_cst_decoding_result {
         // Codec=Cst (C-struct based), see doc to use other codecs
         fn cst_decode(self) -> crate::candle_models::whisper::model::DecodingResult {
@@ -1137,6 +2145,28 @@ mod io {
             }
         }
     }
+    impl CstDecode<Vec<String>> for *mut wire_cst_list_String {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> Vec<String> {
+            let vec = unsafe {
+                let wrap = flutter_rust_bridge::for_generated::box_from_leak_ptr(self);
+                flutter_rust_bridge::for_generated::vec_from_leak_ptr(wrap.ptr, wrap.len)
+            };
+            vec.into_iter().map(CstDecode::cst_decode).collect()
+        }
+    }
+    impl CstDecode<Vec<crate::audio_capture::traits::AudioDeviceInfo>>
+        for *mut wire_cst_list_audio_device_info
+    {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> Vec<crate::audio_capture::traits::AudioDeviceInfo> {
+            let vec = unsafe {
+                let wrap = flutter_rust_bridge::for_generated::box_from_leak_ptr(self);
+                flutter_rust_bridge::for_generated::vec_from_leak_ptr(wrap.ptr, wrap.len)
+            };
+            vec.into_iter().map(CstDecode::cst_decode).collect()
+        }
+    }
     impl CstDecode<Vec<u32>> for *mut wire_cst_list_prim_u_32_strict {
         // Codec=Cst (C-struct based), see doc to use other codecs
         fn cst_decode(self) -> Vec<u32> {
@@ -1184,6 +2214,42 @@ mod io {
             vec.into_iter().map(CstDecode::cst_decode).collect()
         }
     }
+    impl CstDecode<Vec<crate::candle_models::whisper::model::SubSegment>>
+        for *mut wire_cst_list_sub_segment
+    {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> Vec<crate::candle_models::whisper::model::SubSegment> {
+            let vec = unsafe {
+                let wrap = flutter_rust_bridge::for_generated::box_from_leak_ptr(self);
+                flutter_rust_bridge::for_generated::vec_from_leak_ptr(wrap.ptr, wrap.len)
+            };
+            vec.into_iter().map(CstDecode::cst_decode).collect()
+        }
+    }
+    impl CstDecode<Vec<crate::candle_models::whisper::model::WordTiming>>
+        for *mut wire_cst_list_word_timing
+    {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> Vec<crate::candle_models::whisper::model::WordTiming> {
+            let vec = unsafe {
+                let wrap = flutter_rust_bridge::for_generated::box_from_leak_ptr(self);
+                flutter_rust_bridge::for_generated::vec_from_leak_ptr(wrap.ptr, wrap.len)
+            };
+            vec.into_iter().map(CstDecode::cst_decode).collect()
+        }
+    }
+    impl CstDecode<Vec<crate::candle_models::whisper::model::LanguageProbability>>
+        for *mut wire_cst_list_language_probability
+    {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> Vec<crate::candle_models::whisper::model::LanguageProbability> {
+            let vec = unsafe {
+                let wrap = flutter_rust_bridge::for_generated::box_from_leak_ptr(self);
+                flutter_rust_bridge::for_generated::vec_from_leak_ptr(wrap.ptr, wrap.len)
+            };
+            vec.into_iter().map(CstDecode::cst_decode).collect()
+        }
+    }
     impl CstDecode<(String, String)> for wire_cst_record_string_string {
         // Codec=Cst (C-struct based), see doc to use other codecs
         fn cst_decode(self) -> (String, String) {
@@ -1201,6 +2267,23 @@ mod io {
                 reasoning_lang: self.reasoning_lang.cst_decode(),
                 audio_duration: self.audio_duration.cst_decode(),
                 status: self.status.cst_decode(),
+                sub_segments: self.sub_segments.cst_decode(),
+                id: self.id.cst_decode(),
+                committed: self.committed.cst_decode(),
+                language_probs: self.language_probs.cst_decode(),
+                emotion: self.emotion.cst_decode(),
+                audio_event: self.audio_event.cst_decode(),
+            }
+        }
+    }
+    impl CstDecode<crate::candle_models::whisper::model::SubSegment> for wire_cst_sub_segment {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::candle_models::whisper::model::SubSegment {
+            crate::candle_models::whisper::model::SubSegment {
+                start: self.start.cst_decode(),
+                end: self.end.cst_decode(),
+                text: self.text.cst_decode(),
+                words: self.words.cst_decode(),
             }
         }
     }
@@ -1217,6 +2300,100 @@ mod io {
             }
         }
     }
+    impl CstDecode<crate::candle_models::whisper::model::WordTiming> for wire_cst_word_timing {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::candle_models::whisper::model::WordTiming {
+            crate::candle_models::whisper::model::WordTiming {
+                start: self.start.cst_decode(),
+                end: self.end.cst_decode(),
+                word: self.word.cst_decode(),
+            }
+        }
+    }
+    impl CstDecode<crate::candle_models::whisper::model::LanguageProbability> for wire_cst_language_probability {
+        // Codec=Cst (C-struct based), see doc to use other codecs
+        fn cst_decode(self) -> crate::candle_models::whisper::model::LanguageProbability {
+            crate::candle_models::whisper::model::LanguageProbability {
+                language: self.language.cst_decode(),
+                probability: self.probability.cst_decode(),
+            }
+        }
+    }
+    impl NewWithNullPtr for wire_cst_audio_device_info {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                name: core::ptr::null_mut(),
+                id: core::ptr::null_mut(),
+                direction: Default::default(),
+                is_default: Default::default(),
+                sample_rate: Default::default(),
+                channels: Default::default(),
+            }
+        }
+    }
+    impl Default for wire_cst_audio_device_info {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
+    impl NewWithNullPtr for wire_cst_caption_config {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                audio_device: core::ptr::null_mut(),
+                audio_device_is_input: Default::default(),
+                language: core::ptr::null_mut(),
+                with_timestamps: Default::default(),
+                word_timestamps: Default::default(),
+                verbose: Default::default(),
+                try_with_cuda: Default::default(),
+                max_audio_duration_secs: Default::default(),
+                inference_interval_ms: Default::default(),
+                inference_timeout_ms: core::ptr::null_mut(),
+                max_tokens_per_segment: core::ptr::null_mut(),
+                temperature: core::ptr::null_mut(),
+                vad_model_path: core::ptr::null_mut(),
+                vad_threshold: core::ptr::null_mut(),
+                local_agreement: core::ptr::null_mut(),
+                endpoint_silence_ms: core::ptr::null_mut(),
+                subtitle_path: core::ptr::null_mut(),
+                record_path: core::ptr::null_mut(),
+                broadcast_addr: core::ptr::null_mut(),
+                obs_text_path: core::ptr::null_mut(),
+                obs_text_lines: Default::default(),
+                obs_websocket_url: core::ptr::null_mut(),
+                obs_websocket_password: core::ptr::null_mut(),
+                beam_size: core::ptr::null_mut(),
+                beam_patience: core::ptr::null_mut(),
+                length_penalty: core::ptr::null_mut(),
+                initial_prompt: core::ptr::null_mut(),
+                condition_on_previous_text: core::ptr::null_mut(),
+                translate: Default::default(),
+                redetect_language: Default::default(),
+                language_switch_threshold: core::ptr::null_mut(),
+                language_switch_windows: core::ptr::null_mut(),
+                inline_emoji: Default::default(),
+                with_itn: Default::default(),
+            }
+        }
+    }
+    impl Default for wire_cst_caption_config {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
+    impl NewWithNullPtr for wire_cst_caption_event {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                tag: -1,
+                kind: CaptionEventKind { nil__: () },
+            }
+        }
+    }
+    impl Default for wire_cst_caption_event {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
     impl NewWithNullPtr for wire_cst_decoding_result {
         fn new_with_null_ptr() -> Self {
             Self {
@@ -1257,6 +2434,12 @@ mod io {
                 reasoning_lang: core::ptr::null_mut(),
                 audio_duration: core::ptr::null_mut(),
                 status: Default::default(),
+                sub_segments: core::ptr::null_mut(),
+                id: Default::default(),
+                committed: Default::default(),
+                language_probs: core::ptr::null_mut(),
+                emotion: core::ptr::null_mut(),
+                audio_event: core::ptr::null_mut(),
             }
         }
     }
@@ -1265,6 +2448,21 @@ mod io {
             Self::new_with_null_ptr()
         }
     }
+    impl NewWithNullPtr for wire_cst_sub_segment {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                start: Default::default(),
+                end: Default::default(),
+                text: core::ptr::null_mut(),
+                words: core::ptr::null_mut(),
+            }
+        }
+    }
+    impl Default for wire_cst_sub_segment {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
     impl NewWithNullPtr for wire_cst_whisper_client {
         fn new_with_null_ptr() -> Self {
             Self {
@@ -1282,6 +2480,33 @@ mod io {
             Self::new_with_null_ptr()
         }
     }
+    impl NewWithNullPtr for wire_cst_word_timing {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                start: Default::default(),
+                end: Default::default(),
+                word: core::ptr::null_mut(),
+            }
+        }
+    }
+    impl Default for wire_cst_word_timing {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
+    impl NewWithNullPtr for wire_cst_language_probability {
+        fn new_with_null_ptr() -> Self {
+            Self {
+                language: core::ptr::null_mut(),
+                probability: Default::default(),
+            }
+        }
+    }
+    impl Default for wire_cst_language_probability {
+        fn default() -> Self {
+            Self::new_with_null_ptr()
+        }
+    }
 
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_fl_caption_wire__crate__api__whisper__cancel_cancellation_token(
@@ -1311,40 +2536,33 @@ mod io {
         port_: i64,
         whisper_client: *mut wire_cst_whisper_client,
         stream_sink: *mut wire_cst_list_prim_u_8_strict,
-        audio_device: *mut wire_cst_list_prim_u_8_strict,
-        audio_device_is_input: *mut bool,
-        audio_language: *mut wire_cst_list_prim_u_8_strict,
         cancel_token_id: *mut wire_cst_list_prim_u_8_strict,
-        with_timestamps: *mut bool,
-        verbose: *mut bool,
-        try_with_cuda: *mut bool,
-        whisper_max_audio_duration: *mut u32,
-        inference_interval: *mut u64,
-        whisper_default_max_decode_tokens: *mut usize,
-        whisper_temperature: *mut f32,
-        vad_model_path: *mut wire_cst_list_prim_u_8_strict,
-        vad_filters_value: *mut f32,
+        config: *mut wire_cst_caption_config,
     ) {
         wire__crate__api__whisper__launch_caption_impl(
             port_,
             whisper_client,
             stream_sink,
-            audio_device,
-            audio_device_is_input,
-            audio_language,
             cancel_token_id,
-            with_timestamps,
-            verbose,
-            try_with_cuda,
-            whisper_max_audio_duration,
-            inference_interval,
-            whisper_default_max_decode_tokens,
-            whisper_temperature,
-            vad_model_path,
-            vad_filters_value,
+            config,
         )
     }
 
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_wire__crate__api__audio__list_audio_devices(port_: i64) {
+        wire__crate__api__audio__list_audio_devices_impl(port_)
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_wire__crate__api__whisper__transcribe_file(
+        port_: i64,
+        whisper_client: *mut wire_cst_whisper_client,
+        file_path: *mut wire_cst_list_prim_u_8_strict,
+        config: *mut wire_cst_caption_config,
+    ) {
+        wire__crate__api__whisper__transcribe_file_impl(port_, whisper_client, file_path, config)
+    }
+
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_fl_caption_wire__crate__api__whisper__whisper_client_new(
         port_: i64,
@@ -1371,6 +2589,14 @@ mod io {
         flutter_rust_bridge::for_generated::new_leak_box_ptr(value)
     }
 
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_box_autoadd_caption_config(
+    ) -> *mut wire_cst_caption_config {
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(
+            wire_cst_caption_config::new_with_null_ptr(),
+        )
+    }
+
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_fl_caption_cst_new_box_autoadd_f_32(value: f32) -> *mut f32 {
         flutter_rust_bridge::for_generated::new_leak_box_ptr(value)
@@ -1399,6 +2625,32 @@ mod io {
         )
     }
 
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_list_String(len: i32) -> *mut wire_cst_list_String {
+        let wrap = wire_cst_list_String {
+            ptr: flutter_rust_bridge::for_generated::new_leak_vec_ptr(
+                <*mut wire_cst_list_prim_u_8_strict>::new_with_null_ptr(),
+                len,
+            ),
+            len,
+        };
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_list_audio_device_info(
+        len: i32,
+    ) -> *mut wire_cst_list_audio_device_info {
+        let wrap = wire_cst_list_audio_device_info {
+            ptr: flutter_rust_bridge::for_generated::new_leak_vec_ptr(
+                <wire_cst_audio_device_info>::new_with_null_ptr(),
+                len,
+            ),
+            len,
+        };
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
+    }
+
     #[unsafe(no_mangle)]
     pub extern "C" fn frbgen_fl_caption_cst_new_list_prim_u_32_strict(
         len: i32,
@@ -1460,6 +2712,153 @@ mod io {
         flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
     }
 
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_audio_device_info {
+        name: *mut wire_cst_list_prim_u_8_strict,
+        id: *mut wire_cst_list_prim_u_8_strict,
+        direction: i32,
+        is_default: bool,
+        sample_rate: u32,
+        channels: u32,
+    }
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_list_sub_segment(
+        len: i32,
+    ) -> *mut wire_cst_list_sub_segment {
+        let wrap = wire_cst_list_sub_segment {
+            ptr: flutter_rust_bridge::for_generated::new_leak_vec_ptr(
+                <wire_cst_sub_segment>::new_with_null_ptr(),
+                len,
+            ),
+            len,
+        };
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_list_word_timing(
+        len: i32,
+    ) -> *mut wire_cst_list_word_timing {
+        let wrap = wire_cst_list_word_timing {
+            ptr: flutter_rust_bridge::for_generated::new_leak_vec_ptr(
+                <wire_cst_word_timing>::new_with_null_ptr(),
+                len,
+            ),
+            len,
+        };
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn frbgen_fl_caption_cst_new_list_language_probability(
+        len: i32,
+    ) -> *mut wire_cst_list_language_probability {
+        let wrap = wire_cst_list_language_probability {
+            ptr: flutter_rust_bridge::for_generated::new_leak_vec_ptr(
+                <wire_cst_language_probability>::new_with_null_ptr(),
+                len,
+            ),
+            len,
+        };
+        flutter_rust_bridge::for_generated::new_leak_box_ptr(wrap)
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_caption_config {
+        audio_device: *mut wire_cst_list_prim_u_8_strict,
+        audio_device_is_input: bool,
+        language: *mut wire_cst_list_prim_u_8_strict,
+        with_timestamps: bool,
+        word_timestamps: bool,
+        verbose: bool,
+        try_with_cuda: bool,
+        max_audio_duration_secs: u32,
+        inference_interval_ms: u64,
+        inference_timeout_ms: *mut u64,
+        max_tokens_per_segment: *mut u32,
+        temperature: *mut f32,
+        vad_model_path: *mut wire_cst_list_prim_u_8_strict,
+        vad_threshold: *mut f32,
+        local_agreement: *mut u32,
+        endpoint_silence_ms: *mut u32,
+        subtitle_path: *mut wire_cst_list_prim_u_8_strict,
+        record_path: *mut wire_cst_list_prim_u_8_strict,
+        broadcast_addr: *mut wire_cst_list_prim_u_8_strict,
+        obs_text_path: *mut wire_cst_list_prim_u_8_strict,
+        obs_text_lines: u32,
+        obs_websocket_url: *mut wire_cst_list_prim_u_8_strict,
+        obs_websocket_password: *mut wire_cst_list_prim_u_8_strict,
+        beam_size: *mut u32,
+        beam_patience: *mut f32,
+        length_penalty: *mut f32,
+        initial_prompt: *mut wire_cst_list_prim_u_8_strict,
+        condition_on_previous_text: *mut u32,
+        translate: bool,
+        redetect_language: bool,
+        language_switch_threshold: *mut f32,
+        language_switch_windows: *mut u32,
+        inline_emoji: bool,
+        with_itn: bool,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_caption_event {
+        tag: i32,
+        kind: CaptionEventKind,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub union CaptionEventKind {
+        Loading: wire_cst_CaptionEvent_Loading,
+        Ready: wire_cst_CaptionEvent_Ready,
+        Segments: wire_cst_CaptionEvent_Segments,
+        VadActivity: wire_cst_CaptionEvent_VadActivity,
+        Warning: wire_cst_CaptionEvent_Warning,
+        Error: wire_cst_CaptionEvent_Error,
+        Exit: wire_cst_CaptionEvent_Exit,
+        nil__: (),
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Loading {
+        progress: *mut f32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Ready {
+        backend: *mut wire_cst_list_prim_u_8_strict,
+        device: *mut wire_cst_list_prim_u_8_strict,
+        providers: *mut wire_cst_list_String,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Segments {
+        segments: *mut wire_cst_list_segment,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_VadActivity {
+        speaking: bool,
+        probability: f32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Warning {
+        msg: *mut wire_cst_list_prim_u_8_strict,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Error {
+        kind: i32,
+        msg: *mut wire_cst_list_prim_u_8_strict,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_CaptionEvent_Exit {
+        reason: i32,
+    }
     #[repr(C)]
     #[derive(Clone, Copy)]
     pub struct wire_cst_decoding_result {
@@ -1472,6 +2871,18 @@ mod io {
     }
     #[repr(C)]
     #[derive(Clone, Copy)]
+    pub struct wire_cst_list_String {
+        ptr: *mut *mut wire_cst_list_prim_u_8_strict,
+        len: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_list_audio_device_info {
+        ptr: *mut wire_cst_audio_device_info,
+        len: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
     pub struct wire_cst_list_prim_u_32_strict {
         ptr: *mut u32,
         len: i32,
@@ -1502,6 +2913,24 @@ mod io {
     }
     #[repr(C)]
     #[derive(Clone, Copy)]
+    pub struct wire_cst_list_sub_segment {
+        ptr: *mut wire_cst_sub_segment,
+        len: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_list_word_timing {
+        ptr: *mut wire_cst_word_timing,
+        len: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_list_language_probability {
+        ptr: *mut wire_cst_language_probability,
+        len: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
     pub struct wire_cst_record_string_string {
         field0: *mut wire_cst_list_prim_u_8_strict,
         field1: *mut wire_cst_list_prim_u_8_strict,
@@ -1516,6 +2945,20 @@ mod io {
         reasoning_lang: *mut wire_cst_list_prim_u_8_strict,
         audio_duration: *mut wire_cst_list_prim_u_8_strict,
         status: i32,
+        sub_segments: *mut wire_cst_list_sub_segment,
+        id: u64,
+        committed: bool,
+        language_probs: *mut wire_cst_list_language_probability,
+        emotion: *mut wire_cst_list_prim_u_8_strict,
+        audio_event: *mut wire_cst_list_prim_u_8_strict,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_sub_segment {
+        start: f64,
+        end: f64,
+        text: *mut wire_cst_list_prim_u_8_strict,
+        words: *mut wire_cst_list_word_timing,
     }
     #[repr(C)]
     #[derive(Clone, Copy)]
@@ -1527,6 +2970,19 @@ mod io {
         is_quantized: bool,
         model_type: *mut wire_cst_list_prim_u_8_strict,
     }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_word_timing {
+        start: f64,
+        end: f64,
+        word: *mut wire_cst_list_prim_u_8_strict,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct wire_cst_language_probability {
+        language: *mut wire_cst_list_prim_u_8_strict,
+        probability: f32,
+    }
 }
 #[cfg(not(target_family = "wasm"))]
 pub use io::*;
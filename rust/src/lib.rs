@@ -3,6 +3,7 @@ use std::panic;
 
 pub mod api;
 pub mod audio_capture;
+pub mod caption;
 mod frb_generated;
 pub mod candle_models;
 pub mod onnx_models;
@@ -32,14 +33,16 @@ fn _try_get_cuda() -> candle_core::Device {
         } else {
             "Unknow CUDA device initialization error".to_string()
         };
-        // show dialog
-        DialogBuilder::message()
+        // show dialog，无显示环境（如服务器上的 CLI）时弹窗会失败，只打印错误
+        if let Err(e) = DialogBuilder::message()
             .set_level(MessageLevel::Error)
             .set_title("CUDA device initialization error , fall back to CPU")
             .set_text(&panic_info)
             .alert()
             .show()
-            .unwrap();
+        {
+            eprintln!("Failed to show dialog: {e}");
+        }
         eprintln!("CUDA device initialization error {}", panic_info);
         // 返回 CPU 设备
         candle_core::Device::Cpu
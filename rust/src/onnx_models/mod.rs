@@ -5,6 +5,7 @@ use std::collections::HashMap;
 pub mod sense_voice;
 pub mod vad;
 pub mod whisper;
+pub mod zipformer;
 
 #[cfg(target_os = "macos")]
 use ort::execution_providers::CoreMLExecutionProvider;
@@ -19,15 +20,15 @@ use ort::execution_providers::DirectMLExecutionProvider;
 
 use ort::session::builder::{GraphOptimizationLevel, SessionBuilder};
 
-use crate::candle_models::whisper::model::Segment;
 use crate::candle_models::whisper::LaunchCaptionParams;
+use crate::caption::{CaptionEvent, SpeechRecognizer};
 
 pub async fn launch_caption<F>(
     params: LaunchCaptionParams,
     result_callback: F,
 ) -> anyhow::Result<()>
 where
-    F: FnMut(Vec<Segment>) + Send + 'static,
+    F: FnMut(CaptionEvent) + Send + 'static,
 {
     if params.model_type == "sense-voice_onnx" {
         // https://github.com/k2-fsa/sherpa-onnx/tree/master/scripts/sense-voice
@@ -35,6 +36,9 @@ where
     } else if params.model_type == "whisper-olive_onnx" {
         // https://github.com/microsoft/Olive/tree/d4d424f9b370e736e79b17487c037d5aad766315/examples/whisper
         whisper::launch_caption(params, result_callback).await?
+    } else if params.model_type == "zipformer_onnx" {
+        // https://k2-fsa.github.io/sherpa/onnx/pretrained_models/online-transducer/zipformer-transducer-models.html
+        zipformer::launch_caption(params, result_callback).await?
     } else {
         Err(anyhow::anyhow!(
             "Unsupported model configuration: {}",
@@ -44,20 +48,53 @@ where
     Ok(())
 }
 
-pub fn init_model(model_path: String, try_gpu: bool) -> anyhow::Result<Session> {
+/// Create the onnx recognizer matching `params.model_type`
+pub fn new_recognizer(params: &LaunchCaptionParams) -> anyhow::Result<Box<dyn SpeechRecognizer>> {
+    if params.model_type == "sense-voice_onnx" {
+        Ok(Box::new(sense_voice::SenseVoiceRecognizer::new(params)?))
+    } else if params.model_type == "whisper-olive_onnx" {
+        Ok(Box::new(whisper::WhisperOnnxRecognizer::new(params)?))
+    } else if params.model_type == "zipformer_onnx" {
+        Ok(Box::new(zipformer::ZipformerRecognizer::new(params)?))
+    } else {
+        Err(anyhow::anyhow!(
+            "Unsupported model configuration: {}",
+            params.model_type
+        ))
+    }
+}
+
+/// Create a session for `model_path`, also returns the registered execution providers
+pub fn init_model(model_path: String, try_gpu: bool) -> anyhow::Result<(Session, Vec<String>)> {
     let mut session_builder = Session::builder()?;
-    register_execution_providers(&mut session_builder, try_gpu, model_path.clone())?;
+    let providers =
+        register_execution_providers(&mut session_builder, try_gpu, model_path.clone())?;
     session_builder = session_builder.with_optimization_level(GraphOptimizationLevel::Level3)?;
     session_builder = session_builder.with_intra_threads(4)?;
     session_builder = register_operator_library(session_builder)?;
-    Ok(session_builder.commit_from_file(model_path)?)
+    Ok((session_builder.commit_from_file(model_path)?, providers))
+}
+
+/// Device name for the registered execution providers
+pub fn providers_device(providers: &[String]) -> String {
+    if providers.iter().any(|p| p == "CUDA" || p == "TensorRT") {
+        "cuda"
+    } else if providers.iter().any(|p| p == "CoreML") {
+        "coreml"
+    } else if providers.iter().any(|p| p == "DirectML") {
+        "directml"
+    } else {
+        "cpu"
+    }
+    .to_string()
 }
 
 pub fn register_execution_providers(
     builder: &mut SessionBuilder,
     try_gpu: bool,
     model_print_name: String,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<String>> {
+    let mut providers = vec![];
     #[allow(unused_variables)]
     #[allow(unused_mut)]
     let mut is_gpu_available = false;
@@ -71,7 +108,8 @@ pub fn register_execution_providers(
             let mut core_ml = CoreMLExecutionProvider::default();
             core_ml = core_ml.with_compute_units(CoreMLComputeUnits::All);
             if core_ml.register(builder).is_ok() {
-                println!(
+                providers.push("CoreML".to_string());
+                eprintln!(
                     "[{}] Registered CoreML execution provider",
                     model_print_name
                 );
@@ -87,7 +125,8 @@ pub fn register_execution_providers(
         {
             let tensor_rt = TensorRTExecutionProvider::default();
             if tensor_rt.register(builder).is_ok() {
-                println!(
+                providers.push("TensorRT".to_string());
+                eprintln!(
                     "[{}] Registered TensorRT execution provider",
                     model_print_name
                 );
@@ -104,8 +143,9 @@ pub fn register_execution_providers(
                 .with_skip_layer_norm_strict_mode(true)
                 .with_prefer_nhwc(true);
             if cuda.register(builder).is_ok() {
+                providers.push("CUDA".to_string());
                 is_gpu_available = true;
-                println!("[{}] Registered CUDA execution provider", model_print_name);
+                eprintln!("[{}] Registered CUDA execution provider", model_print_name);
             } else {
                 eprintln!(
                     "[{}] Failed to register CUDA execution provider",
@@ -120,8 +160,9 @@ pub fn register_execution_providers(
             if !is_gpu_available {
                 let direct_ml = DirectMLExecutionProvider::default();
                 if direct_ml.register(builder).is_ok() {
+                    providers.push("DirectML".to_string());
                     is_dml_available = true;
-                    println!(
+                    eprintln!(
                         "[{}] Registered DirectML execution provider",
                         model_print_name
                     );
@@ -139,7 +180,8 @@ pub fn register_execution_providers(
     if !is_dml_available {
         let xnn_pack = XNNPACKExecutionProvider::default();
         if xnn_pack.register(builder).is_ok() {
-            println!(
+            providers.push("XNNPACK".to_string());
+            eprintln!(
                 "[{}] Registered XNNPACK execution provider",
                 model_print_name
             );
@@ -151,7 +193,7 @@ pub fn register_execution_providers(
         }
     }
 
-    Ok(())
+    Ok(providers)
 }
 
 pub fn find_model_path(
@@ -187,10 +229,10 @@ fn register_operator_library(builder: SessionBuilder) -> anyhow::Result<SessionB
         .unwrap_or_else(|| std::path::PathBuf::from(lib_name));
 
     if lib_path.exists() {
-        println!("Registering operator library: {}", lib_path.display());
+        eprintln!("Registering operator library: {}", lib_path.display());
         Ok(builder.with_operator_library(lib_path)?)
     } else {
-        println!(
+        eprintln!(
             "Operator library not found at: {}, proceeding without it",
             lib_path.display()
         );
@@ -43,6 +43,14 @@ pub enum SenseVoiceTextNorm {
     WithoutITN, // <|woitn|>
 }
 
+// 文本 token 及其在 CTC 输出中连续出现的帧范围 [start_frame, end_frame)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SenseVoiceToken {
+    pub text: String,
+    pub start_frame: usize,
+    pub end_frame: usize,
+}
+
 // 解析后的SenseVoice输出结构
 #[derive(Debug, Clone)]
 pub struct SenseVoiceOutput {
@@ -52,6 +60,8 @@ pub struct SenseVoiceOutput {
     pub text_norm: Option<SenseVoiceTextNorm>,
     pub text: String,
     pub emoji: String,
+    /// Text tokens (without the special tags) in output order
+    pub tokens: Vec<SenseVoiceToken>,
 }
 
 impl SenseVoiceLanguage {
@@ -77,6 +87,18 @@ impl SenseVoiceLanguage {
             Self::NoSpeech => "",
         }
     }
+
+    /// Language code such as "yue", None for no speech
+    pub(crate) fn code(&self) -> Option<&'static str> {
+        match self {
+            Self::Chinese => Some("zh"),
+            Self::English => Some("en"),
+            Self::Cantonese => Some("yue"),
+            Self::Japanese => Some("ja"),
+            Self::Korean => Some("ko"),
+            Self::NoSpeech => None,
+        }
+    }
 }
 
 impl SenseVoiceEmotion {
@@ -106,6 +128,20 @@ impl SenseVoiceEmotion {
             Self::Unknown => "",
         }
     }
+
+    /// Name reported in `Segment::emotion`, None when unknown
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Happy => Some("happy"),
+            Self::Sad => Some("sad"),
+            Self::Angry => Some("angry"),
+            Self::Neutral => Some("neutral"),
+            Self::Fearful => Some("fearful"),
+            Self::Disgusted => Some("disgusted"),
+            Self::Surprised => Some("surprised"),
+            Self::Unknown => None,
+        }
+    }
 }
 
 impl SenseVoiceEvent {
@@ -143,6 +179,24 @@ impl SenseVoiceEvent {
             Self::EventUnknown => "",
         }
     }
+
+    /// Name reported in `Segment::audio_event`, None when unknown
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Speech => Some("speech"),
+            Self::BGM => Some("bgm"),
+            Self::Applause => Some("applause"),
+            Self::Laughter => Some("laughter"),
+            Self::Cry => Some("cry"),
+            Self::Sneeze => Some("sneeze"),
+            Self::Breath => Some("breath"),
+            Self::Cough => Some("cough"),
+            Self::Sing => Some("sing"),
+            Self::SpeechNoise => Some("speech_noise"),
+            Self::GBG => Some("gbg"),
+            Self::EventUnknown => None,
+        }
+    }
 }
 
 impl SenseVoiceTextNorm {
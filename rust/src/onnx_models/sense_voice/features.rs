@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+
+use kaldi_fbank_rust::{FbankOptions, OnlineFbank};
+use ndarray::Array2;
+
+const SAMPLE_RATE: f32 = 16000.0;
+/// fbank 帧移 10ms、帧长 25ms（采样点数）
+const FRAME_SHIFT: usize = 160;
+const FRAME_LENGTH: usize = 400;
+const NUM_BINS: usize = 80;
+/// 在线 fbank 保留所有已计算的帧，约 10 分钟后在当前窗口处重新开始
+const MAX_STREAM_FRAMES: usize = 10 * 60 * 100;
+
+/// Normalized LFR features of an audio stream.
+///
+/// One online fbank (`snip_edges = false` like the non-streaming SenseVoice frontend) runs over
+/// the stream and LFR rows sit on a grid anchored at its start, so a window overlapping the
+/// previous one only pays for the new audio.
+pub struct FeatureCache {
+    window_size: usize,  // lfr_m
+    window_shift: usize, // lfr_n
+    neg_mean: Vec<f32>,
+    inv_stddev: Vec<f32>,
+    /// Stream sample where the online fbank starts, frame `t` is centred on
+    /// `origin + t * FRAME_SHIFT + FRAME_SHIFT / 2`
+    origin: usize,
+    fbank: StreamFbank,
+    /// fbank frames already read from `fbank`
+    frames_read: usize,
+    /// Stream index of `rows[0]`
+    first_row: usize,
+    rows: VecDeque<Vec<f32>>,
+    /// fbank frames from the first frame of the next LFR row on
+    pending_frames: Vec<Vec<f32>>,
+    /// Stream sample after the last accepted sample
+    end: usize,
+}
+
+/// Online fbank of the current stream
+struct StreamFbank(OnlineFbank);
+
+// SAFETY: the kaldi fbank has no thread-affine state, it is owned by one `FeatureCache` and
+// only used through `&mut self`
+unsafe impl Send for StreamFbank {}
+
+impl FeatureCache {
+    pub fn new(
+        window_size: usize,
+        window_shift: usize,
+        neg_mean: Vec<f32>,
+        inv_stddev: Vec<f32>,
+    ) -> Self {
+        Self {
+            window_size,
+            window_shift,
+            neg_mean,
+            inv_stddev,
+            origin: 0,
+            fbank: StreamFbank(new_fbank()),
+            frames_read: 0,
+            first_row: 0,
+            rows: VecDeque::new(),
+            pending_frames: vec![],
+            end: 0,
+        }
+    }
+
+    /// Features of a window on its own, as the first window of a new stream
+    pub fn window_features(&mut self, pcm: &[f32]) -> anyhow::Result<Array2<f32>> {
+        self.clear();
+        Ok(self.features(pcm, 0)?.0)
+    }
+
+    /// Forget the cached audio, the next window starts a new stream
+    pub fn clear(&mut self) {
+        self.restart(0);
+    }
+
+    /// Features of `pcm` (f32, mono, 16kHz) starting `start` samples into the stream, computed
+    /// only for the audio after the cached end. Returns the features and the offset of the
+    /// first row from `start` in seconds, rows stay on the grid of the stream.
+    pub fn features(&mut self, pcm: &[f32], start: usize) -> anyhow::Result<(Array2<f32>, f64)> {
+        let end = start + pcm.len();
+        // 窗口早于已缓存的特征、与缓存不连续或流过长时重新开始
+        if start < self.row_start(self.first_row)
+            || start > self.end
+            || self.frames_read >= MAX_STREAM_FRAMES
+        {
+            self.restart(start);
+        }
+        if end > self.end {
+            self.push_samples(&pcm[self.end - start..]);
+            self.end = end;
+        }
+
+        let total_rows = self.first_row + self.rows.len();
+        let Some(first) = (self.first_row..total_rows).find(|&row| self.row_start(row) >= start)
+        else {
+            return Err(anyhow::anyhow!("Not enough frames for LFR processing"));
+        };
+        // 之后的窗口不会早于当前窗口，丢弃之前的行
+        self.rows.drain(..first - self.first_row);
+        self.first_row = first;
+
+        let count = (first..total_rows)
+            .take_while(|&row| self.row_end(row) <= end)
+            .count();
+        if count == 0 {
+            return Err(anyhow::anyhow!("Not enough frames for LFR processing"));
+        }
+        let data: Vec<f32> = self.rows.iter().take(count).flatten().copied().collect();
+        let features = Array2::from_shape_vec((count, self.window_size * NUM_BINS), data)?;
+        let offset = (self.row_start(first) - start) as f64 / SAMPLE_RATE as f64;
+        Ok((features, offset))
+    }
+
+    fn restart(&mut self, origin: usize) {
+        self.origin = origin;
+        self.fbank = StreamFbank(new_fbank());
+        self.frames_read = 0;
+        self.first_row = 0;
+        self.rows.clear();
+        self.pending_frames.clear();
+        self.end = origin;
+    }
+
+    fn row_start(&self, row: usize) -> usize {
+        self.origin + row * self.window_shift * FRAME_SHIFT
+    }
+
+    /// Stream sample after the audio of the last frame of `row`
+    fn row_end(&self, row: usize) -> usize {
+        self.row_start(row)
+            + (self.window_size - 1) * FRAME_SHIFT
+            + (FRAME_SHIFT + FRAME_LENGTH) / 2
+    }
+
+    /// Read the fbank frames completed by the new samples and build the LFR rows
+    fn push_samples(&mut self, samples: &[f32]) {
+        // 将样本缩放到16位整数范围
+        let samples: Vec<f32> = samples.iter().map(|&x| x * 32768.0).collect();
+        let fbank = &mut self.fbank.0;
+        fbank.accept_waveform(SAMPLE_RATE, &samples);
+        // 不调用 input_finished，末尾不完整的帧等后续音频到达后再计算
+        let ready = fbank.num_ready_frames().max(0) as usize;
+        while self.frames_read < ready {
+            let Some(frame) = fbank.get_frame(self.frames_read as i32) else {
+                break;
+            };
+            self.pending_frames.push(frame.to_vec());
+            self.frames_read += 1;
+        }
+
+        while self.pending_frames.len() >= self.window_size {
+            let row = self.lfr_row(&self.pending_frames);
+            self.rows.push_back(row);
+            self.pending_frames.drain(..self.window_shift);
+        }
+    }
+
+    /// LFR：拼接从 `frames[0]` 开始的 window_size 帧，并归一化
+    fn lfr_row(&self, frames: &[Vec<f32>]) -> Vec<f32> {
+        frames[..self.window_size]
+            .iter()
+            .flatten()
+            .zip(self.neg_mean.iter().zip(&self.inv_stddev))
+            .map(|(&value, (&neg_mean, &inv_stddev))| (value + neg_mean) * inv_stddev)
+            .collect()
+    }
+}
+
+fn new_fbank() -> OnlineFbank {
+    let mut fbank_opts = FbankOptions::default();
+    fbank_opts.frame_opts.dither = 0.0;
+    fbank_opts.frame_opts.snip_edges = false;
+    fbank_opts.frame_opts.samp_freq = SAMPLE_RATE;
+    // 设置hamming窗口
+    fbank_opts.frame_opts.window_type = c"hamming".as_ptr();
+    fbank_opts.mel_opts.num_bins = NUM_BINS as i32;
+    OnlineFbank::new(fbank_opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> FeatureCache {
+        let dim = 7 * NUM_BINS;
+        FeatureCache::new(7, 6, vec![-1.0; dim], vec![0.5; dim])
+    }
+
+    #[test]
+    fn test_overlapping_window_matches_fresh_cache() {
+        let stream: Vec<f32> = (0..16000 * 3)
+            .map(|i| (i as f32 * 0.01).sin() * 0.1)
+            .collect();
+        // 第二个窗口与第一个重叠 1.0625 秒，起点不在 LFR 网格上
+        let (first, second) = (0..16000 * 2, 15000..16000 * 3);
+
+        let mut cached = cache();
+        cached
+            .features(&stream[first.clone()], first.start)
+            .unwrap();
+        let (features, offset) = cached
+            .features(&stream[second.clone()], second.start)
+            .unwrap();
+
+        // 一次算完到第二个窗口结束的音频，取从窗口起点之后开始的行
+        let mut fresh = cache();
+        let (all, _) = fresh.features(&stream[..second.end], 0).unwrap();
+        let row_samples = 6 * FRAME_SHIFT;
+        let skip = second.start.div_ceil(row_samples);
+        assert!(features.nrows() > 0);
+        assert_eq!(features.nrows(), all.nrows() - skip);
+        assert_eq!(features.view(), all.slice(ndarray::s![skip.., ..]));
+        let expected_offset = (skip * row_samples - second.start) as f64 / 16000.0;
+        assert_eq!(offset, expected_offset);
+
+        // 单独的窗口不受缓存影响
+        let window = cached.window_features(&stream[second.clone()]).unwrap();
+        let (expected, _) = cache().features(&stream[second], 0).unwrap();
+        assert_eq!(window, expected);
+    }
+}
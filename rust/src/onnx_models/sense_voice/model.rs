@@ -1,21 +1,51 @@
-use kaldi_fbank_rust::{FbankOptions, OnlineFbank};
 use ndarray::{Array1, Array2, Axis};
 use ort::session::Session;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::{candle_models::whisper::model::{DecodingResult, Segment, WhisperStatus}, onnx_models::sense_voice::def};
-
+use crate::{
+    candle_models::whisper::model::{
+        DecodingResult, Segment, SubSegment, WhisperStatus, WordTiming,
+    },
+    caption::agreement,
+    onnx_models::sense_voice::{def, features::FeatureCache},
+};
+
+/// Embedding ids of the official SenseVoice model, used when the model has no `lang_<code>`
+/// metadata
+const DEFAULT_LANGUAGE_IDS: [(&str, i32); 7] = [
+    ("auto", 0),
+    ("zh", 3),
+    ("en", 4),
+    ("yue", 7),
+    ("ja", 11),
+    ("ko", 12),
+    ("nospeech", 13),
+];
+/// fbank 帧移 10ms，LFR 之后每帧为 window_shift 个 fbank 帧
+const FBANK_FRAME_SECS: f64 = 0.01;
+/// Output frames of the language / emotion / event / text norm queries in front of the audio
+const QUERY_FRAMES: usize = 4;
+
+/// Output switches of `run_sensevoice_inference`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SenseVoiceOptions {
+    /// Inverse text normalization (written-form numbers and punctuation)
+    pub use_itn: bool,
+    /// Put the event / emotion emoji into the text
+    pub inline_emoji: bool,
+    /// Word timings from the CTC frames of the tokens
+    pub timestamps: bool,
+}
 
 pub struct SenseVoiceModel {
     session: Session,
-    window_size: i32,  // lfr_m
     window_shift: i32, // lfr_n
     lang_id: HashMap<String, i32>,
     with_itn: i32,
     without_itn: i32,
-    neg_mean: Vec<f32>,
-    inv_stddev: Vec<f32>,
+    /// fbank / LFR features cached across overlapping windows
+    features: FeatureCache,
 }
 
 impl SenseVoiceModel {
@@ -25,8 +55,9 @@ impl SenseVoiceModel {
         // 从模型元数据中获取参数
         let mut window_size = 7; // 默认值
         let mut window_shift = 6; // 默认值
-        let mut with_itn = 1; // 默认值
-        let without_itn = 0;
+        // 官方模型的 text_norm 编号
+        let mut with_itn = 14;
+        let mut without_itn = 15;
 
         // 尝试从metadata获取实际参数
         if let Ok(metadata) = session.metadata() {
@@ -45,15 +76,23 @@ impl SenseVoiceModel {
                     with_itn = itn;
                 }
             }
+            if let Ok(Some(without_itn_str)) = metadata.custom("without_itn") {
+                if let Ok(itn) = without_itn_str.parse::<i32>() {
+                    without_itn = itn;
+                }
+            }
         }
 
-        // 语言ID映射
-        let mut lang_id = HashMap::new();
-        lang_id.insert("zh".to_string(), 0);
-        lang_id.insert("en".to_string(), 1);
-        lang_id.insert("ja".to_string(), 2);
-        lang_id.insert("ko".to_string(), 3);
-        lang_id.insert("auto".to_string(), 4);
+        // 语言ID映射，优先使用模型元数据中的 lang_<code>（sherpa-onnx 导出）
+        let mut metadata_entries = vec![];
+        if let Ok(metadata) = session.metadata() {
+            for key in metadata.custom_keys().unwrap_or_default() {
+                if let Ok(Some(value)) = metadata.custom(&key) {
+                    metadata_entries.push((key, value));
+                }
+            }
+        }
+        let lang_id = language_ids(&metadata_entries);
 
         // 归一化参数 - 根据window_size动态计算维度
         let feature_dim = 80 * window_size as usize;
@@ -82,56 +121,76 @@ impl SenseVoiceModel {
             }
         }
 
-        println!("SenseVoice model parameters loaded:");
-        println!("  - window_size (lfr_m): {}", window_size);
-        println!("  - window_shift (lfr_n): {}", window_shift);
-        println!("  - with_itn: {}, without_itn: {}", with_itn, without_itn);
-        println!(
+        eprintln!("SenseVoice model parameters loaded:");
+        eprintln!("  - window_size (lfr_m): {}", window_size);
+        eprintln!("  - window_shift (lfr_n): {}", window_shift);
+        eprintln!("  - with_itn: {}, without_itn: {}", with_itn, without_itn);
+        eprintln!(
             "  - neg_mean length: {}, inv_stddev length: {}",
             neg_mean.len(),
             inv_stddev.len()
         );
-        println!("  - language mappings: {:?}", lang_id);
+        eprintln!("  - language mappings: {:?}", lang_id);
 
         // 尝试打印可用的元数据信息用于调试
         if let Ok(metadata) = session.metadata() {
-            println!("Available metadata:");
-            println!("  - version: {:?}", metadata.version());
-            println!("  - with_itn: {:?}", metadata.custom("with_itn"));
-            println!(
+            eprintln!("Available metadata:");
+            eprintln!("  - version: {:?}", metadata.version());
+            eprintln!("  - with_itn: {:?}", metadata.custom("with_itn"));
+            eprintln!(
                 "  - lfr_window_size: {:?}",
                 metadata.custom("lfr_window_size")
             );
-            println!(
+            eprintln!(
                 "  - lfr_window_shift: {:?}",
                 metadata.custom("lfr_window_shift")
             );
         }
 
         // 打印模型的输入信息用于调试
-        println!("Model inputs:");
+        eprintln!("Model inputs:");
         for input in session.inputs.iter() {
-            println!("  - name: {}, type: {:?}", input.name, input.input_type);
+            eprintln!("  - name: {}, type: {:?}", input.name, input.input_type);
         }
 
         // 打印模型的输出信息用于调试
-        println!("Model outputs:");
+        eprintln!("Model outputs:");
         for output in session.outputs.iter() {
-            println!("  - name: {}, type: {:?}", output.name, output.output_type);
+            eprintln!("  - name: {}, type: {:?}", output.name, output.output_type);
         }
 
         Ok(Self {
             session,
-            window_size,
             window_shift,
             lang_id,
             with_itn,
             without_itn,
-            neg_mean,
-            inv_stddev,
+            features: FeatureCache::new(
+                window_size as usize,
+                window_shift as usize,
+                neg_mean,
+                inv_stddev,
+            ),
         })
     }
 
+    /// Whether `language` (a code such as "yue", or "auto") can be passed to `inference`
+    pub fn supports_language(&self, language: &str) -> bool {
+        self.lang_id.contains_key(language)
+    }
+
+    /// Language codes accepted by `inference`, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.lang_id.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// Drop the cached features, e.g. when the stream restarts
+    pub fn clear_features(&mut self) {
+        self.features.clear();
+    }
+
     #[allow(dead_code)]
     pub fn inference(
         &mut self,
@@ -153,7 +212,7 @@ impl SenseVoiceModel {
             .lang_id
             .get(language)
             .copied()
-            .unwrap_or(self.lang_id.get("auto").copied().unwrap_or(4));
+            .unwrap_or(self.lang_id.get("auto").copied().unwrap_or(0));
         let language_tensor = Array1::from_vec(vec![language_id]);
 
         let text_norm_id = if use_itn {
@@ -182,7 +241,7 @@ impl SenseVoiceModel {
             return Err(anyhow::anyhow!("No outputs from model"));
         }
 
-        println!("Available output keys: {:?}", output_keys);
+        eprintln!("Available output keys: {:?}", output_keys);
 
         // 尝试获取输出 - 常见的输出名称
         let logits_value = outputs
@@ -240,116 +299,6 @@ impl SenseVoiceModel {
     }
 }
 
-fn compute_features(
-    samples: &[f32],
-    sample_rate: f32,
-    neg_mean: &[f32],
-    inv_stddev: &[f32],
-    window_size: i32,
-    window_shift: i32,
-) -> anyhow::Result<Array2<f32>> {
-    // 配置fbank参数
-    let mut fbank_opts = FbankOptions::default();
-    fbank_opts.frame_opts.dither = 0.0;
-    fbank_opts.frame_opts.snip_edges = false;
-    fbank_opts.frame_opts.samp_freq = sample_rate;
-    // 设置hamming窗口
-    fbank_opts.frame_opts.window_type = std::ffi::CStr::from_bytes_with_nul(b"hamming\0")
-        .unwrap()
-        .as_ptr(); // "hamming";
-    fbank_opts.mel_opts.num_bins = 80;
-
-    let mut online_fbank = OnlineFbank::new(fbank_opts);
-
-    // 将样本缩放到16位整数范围然后转换为f32
-    let scaled_samples: Vec<f32> = samples.iter().map(|&x| x * 32768.0).collect();
-    online_fbank.accept_waveform(sample_rate, &scaled_samples);
-    online_fbank.input_finished();
-
-    let num_frames = online_fbank.num_ready_frames();
-    if num_frames == 0 {
-        return Err(anyhow::anyhow!("No frames ready"));
-    }
-
-    // 提取所有帧的特征
-    let mut features = Vec::with_capacity(num_frames as usize);
-    for i in 0..num_frames {
-        if let Some(frame) = online_fbank.get_frame(i) {
-            features.push(frame.to_vec());
-        }
-    }
-
-    if features.is_empty() {
-        return Err(anyhow::anyhow!("No features extracted"));
-    }
-
-    let feature_dim = features[0].len();
-    let mut feature_matrix = Array2::zeros((features.len(), feature_dim));
-
-    for (i, frame) in features.iter().enumerate() {
-        for (j, &val) in frame.iter().enumerate() {
-            feature_matrix[[i, j]] = val;
-        }
-    }
-
-    // 应用LFR (Low Frame Rate) 处理
-    let lfr_features = apply_lfr(&feature_matrix, window_size, window_shift)?;
-
-    // 应用归一化
-    let normalized_features = apply_normalization(&lfr_features, neg_mean, inv_stddev)?;
-
-    Ok(normalized_features)
-}
-
-#[allow(dead_code)]
-fn apply_lfr(
-    features: &Array2<f32>,
-    window_size: i32,
-    window_shift: i32,
-) -> anyhow::Result<Array2<f32>> {
-    let (num_frames, feature_dim) = features.dim();
-
-    if num_frames < window_size as usize {
-        return Err(anyhow::anyhow!("Not enough frames for LFR processing"));
-    }
-
-    let t = (num_frames - window_size as usize) / window_shift as usize + 1;
-    let output_dim = feature_dim * window_size as usize;
-
-    let mut lfr_features = Array2::zeros((t, output_dim));
-
-    for i in 0..t {
-        let start_frame = i * window_shift as usize;
-        for j in 0..window_size as usize {
-            let frame_idx = start_frame + j;
-            let output_start = j * feature_dim;
-            for k in 0..feature_dim {
-                lfr_features[[i, output_start + k]] = features[[frame_idx, k]];
-            }
-        }
-    }
-
-    Ok(lfr_features)
-}
-
-fn apply_normalization(
-    features: &Array2<f32>,
-    neg_mean: &[f32],
-    inv_stddev: &[f32],
-) -> anyhow::Result<Array2<f32>> {
-    let mut normalized = features.clone();
-
-    for mut row in normalized.rows_mut() {
-        for (i, val) in row.iter_mut().enumerate() {
-            if i < neg_mean.len() && i < inv_stddev.len() {
-                *val = (*val + neg_mean[i]) * inv_stddev[i];
-            }
-        }
-    }
-
-    Ok(normalized)
-}
-
 fn parse_sensevoice_output(
     logits: &Array2<f32>,
     tokens: &HashMap<usize, String>,
@@ -367,19 +316,43 @@ fn parse_sensevoice_output(
         })
         .collect();
 
-    // 去除连续重复的token
+    // 去除连续重复的token，同时记录每个token出现的帧范围
     let mut unique_indices = Vec::new();
+    let mut frame_ranges: Vec<(usize, usize)> = Vec::new();
     let mut prev_idx = None;
-    for idx in indices {
+    for (frame, idx) in indices.into_iter().enumerate() {
         if prev_idx != Some(idx) {
             unique_indices.push(idx);
+            frame_ranges.push((frame, frame + 1));
             prev_idx = Some(idx);
+        } else if let Some(range) = frame_ranges.last_mut() {
+            range.1 = frame + 1;
         }
     }
 
     // 去除blank token (通常是0)
     let blank_id = 0;
-    unique_indices.retain(|&idx| idx != blank_id);
+    let (unique_indices, frame_ranges): (Vec<usize>, Vec<(usize, usize)>) = unique_indices
+        .into_iter()
+        .zip(frame_ranges)
+        .filter(|&(idx, _)| idx != blank_id)
+        .unzip();
+
+    // 文本token及其帧范围，用于时间戳
+    let timed_tokens: Vec<def::SenseVoiceToken> = unique_indices
+        .iter()
+        .zip(&frame_ranges)
+        .filter_map(|(idx, &(start_frame, end_frame))| {
+            tokens
+                .get(idx)
+                .filter(|token| !token.starts_with("<|"))
+                .map(|token| def::SenseVoiceToken {
+                    text: token.to_string(),
+                    start_frame,
+                    end_frame,
+                })
+        })
+        .collect();
 
     // 转换为token字符串
     let token_strings: Vec<String> = unique_indices
@@ -469,61 +442,149 @@ fn parse_sensevoice_output(
         text_norm,
         text,
         emoji,
+        tokens: timed_tokens,
     }
 }
 
+/// Text with the event emoji in front and the emotion emoji behind, as FunASR renders it
+fn text_with_emoji(output: &def::SenseVoiceOutput) -> String {
+    let event = output
+        .event
+        .as_ref()
+        .map(|e| e.to_emoji())
+        .unwrap_or_default();
+    let emotion = output
+        .emotion
+        .as_ref()
+        .map(|e| e.to_emoji())
+        .unwrap_or_default();
+    format!("{event}{}{emotion}", output.text)
+}
+
+/// Word timings in seconds from the CTC frames of the text tokens. A "▁" token or a CJK
+/// character starts a new word, punctuation stays with the previous word. A word ends where
+/// the next one starts, the last one where its last token stops firing.
+fn token_words(tokens: &[def::SenseVoiceToken], frame_secs: f64) -> Vec<WordTiming> {
+    let time = |frame: usize| frame.saturating_sub(QUERY_FRAMES) as f64 * frame_secs;
+    let mut words: Vec<WordTiming> = vec![];
+    for token in tokens {
+        let text = token.text.replace('▁', " ");
+        let attach = match (words.last(), text.trim_start().chars().next()) {
+            (Some(_), Some(c)) if !c.is_alphanumeric() => true,
+            (Some(last), Some(c)) => {
+                !token.text.starts_with('▁')
+                    && !agreement::is_cjk(c)
+                    && !last.word.ends_with(agreement::is_cjk)
+            }
+            _ => false,
+        };
+        match words.last_mut() {
+            Some(last) if attach => {
+                last.word.push_str(&text);
+                last.end = time(token.end_frame);
+            }
+            _ => words.push(WordTiming {
+                start: time(token.start_frame),
+                end: time(token.end_frame),
+                word: text,
+            }),
+        }
+    }
+    for i in 1..words.len() {
+        words[i - 1].end = words[i].start;
+    }
+    words
+}
 
 // SenseVoice推理辅助函数
 pub fn run_sensevoice_inference(
     model: &mut SenseVoiceModel,
     pcm: &[f32],
+    window_start: Option<usize>,
     language: &str,
     tokens: &HashMap<usize, String>,
+    options: SenseVoiceOptions,
     #[allow(unused_variables)] timeout: Option<Duration>,
 ) -> anyhow::Result<Vec<Segment>> {
-    // 计算特征
-    let features = compute_features(
-        pcm,
-        16000.0,
-        &model.neg_mean,
-        &model.inv_stddev,
-        model.window_size,
-        model.window_shift,
-    )?;
+    // 计算特征，已知窗口在音频流中的位置时复用与上一窗口重叠部分的特征
+    let (features, feature_offset) = match window_start {
+        Some(start) => model.features.features(pcm, start)?,
+        None => (model.features.window_features(pcm)?, 0.0),
+    };
 
     // 运行推理
-    let use_itn = false; // 可以根据需要配置
-    let logits = model.inference(features, language, use_itn)?;
+    let logits = model.inference(features, language, options.use_itn)?;
 
     // 解析SenseVoice输出
     let parsed_output = parse_sensevoice_output(&logits, tokens);
 
     // 打印调试信息
-    println!("SenseVoice parsed output:");
-    println!("  Language: {:?}", parsed_output.language);
-    println!("  Emotion: {:?}", parsed_output.emotion);
-    println!("  Event: {:?}", parsed_output.event);
-    println!("  Text norm: {:?}", parsed_output.text_norm);
-    println!("  Emoji: {}", parsed_output.emoji);
-    println!("  Clean text: {}", parsed_output.text);
-
-    // 创建segment，只返回纯文本
+    eprintln!("SenseVoice parsed output:");
+    eprintln!("  Language: {:?}", parsed_output.language);
+    eprintln!("  Emotion: {:?}", parsed_output.emotion);
+    eprintln!("  Event: {:?}", parsed_output.event);
+    eprintln!("  Text norm: {:?}", parsed_output.text_norm);
+    eprintln!("  Emoji: {}", parsed_output.emoji);
+    eprintln!("  Clean text: {}", parsed_output.text);
+
+    // 创建segment，语言、情感和事件作为结构化字段返回
+    let text = if options.inline_emoji {
+        text_with_emoji(&parsed_output)
+    } else {
+        parsed_output.text.clone()
+    };
     let duration = pcm.len() as f64 / 16000.0;
+    let mut sub_segments = vec![];
+    if options.timestamps {
+        let frame_secs = model.window_shift as f64 * FBANK_FRAME_SECS;
+        let mut words = token_words(&parsed_output.tokens, frame_secs);
+        // 特征的第一行对齐到音频流的网格，可能晚于窗口起点
+        for word in words.iter_mut() {
+            word.start += feature_offset;
+            word.end += feature_offset;
+        }
+        if let (Some(first), Some(last)) = (words.first(), words.last()) {
+            sub_segments.push(SubSegment {
+                start: first.start,
+                end: last.end.min(duration),
+                text: parsed_output.text.clone(),
+                words,
+            });
+        }
+    }
     let segment = Segment {
         start: 0.0,
         duration,
         dr: DecodingResult {
-            tokens: vec![],           // SenseVoice暂不返回token序列
-            text: parsed_output.text, // 只返回纯文本，不包含特殊标记
-            avg_logprob: 0.0,
+            tokens: vec![],        // SenseVoice暂不返回token序列
+            text,
+            avg_logprob: f64::NAN, // CTC 输出不提供置信度
             no_speech_prob: 0.0,
             temperature: 0.0,
             compression_ratio: 1.0,
         },
         reasoning_duration: None,
-        reasoning_lang: None,
+        reasoning_lang: parsed_output
+            .language
+            .as_ref()
+            .and_then(|l| l.code())
+            .map(|s| s.to_string()),
         audio_duration: None,
         status: WhisperStatus::Working,
+        sub_segments,
+        id: 0,
+        committed: false,
+        language_probs: vec![],
+        emotion: parsed_output
+            .emotion
+            .as_ref()
+            .and_then(|e| e.name())
+            .map(|s| s.to_string()),
+        audio_event: parsed_output
+            .event
+            .as_ref()
+            .and_then(|e| e.name())
+            .map(|s| s.to_string()),
     };
 
     Ok(vec![segment])
@@ -540,3 +601,107 @@ pub fn load_tokens_from_data(data: &str) -> anyhow::Result<HashMap<usize, String
 
     Ok(tokens)
 }
+
+/// Language ids from the `lang_<code>` metadata entries, the official ids without them
+fn language_ids(metadata: &[(String, String)]) -> HashMap<String, i32> {
+    let lang_id: HashMap<String, i32> = metadata
+        .iter()
+        .filter_map(|(key, value)| {
+            let code = key.strip_prefix("lang_")?;
+            Some((code.to_string(), value.parse().ok()?))
+        })
+        .collect();
+    if !lang_id.is_empty() {
+        return lang_id;
+    }
+    DEFAULT_LANGUAGE_IDS
+        .iter()
+        .map(|&(code, id)| (code.to_string(), id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_ids_from_metadata() {
+        let entry = |key: &str, value: &str| (key.to_string(), value.to_string());
+        let metadata = [
+            entry("lfr_window_size", "7"),
+            entry("lang_auto", "0"),
+            entry("lang_yue", "7"),
+            entry("lang_de", "21"),
+        ];
+        let ids = language_ids(&metadata);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids["de"], 21);
+        assert_eq!(ids["yue"], 7);
+
+        // 没有 lang_* 元数据时使用官方编号
+        let ids = language_ids(&metadata[..1]);
+        assert_eq!(ids["auto"], 0);
+        assert_eq!(ids["zh"], 3);
+        assert_eq!(ids["en"], 4);
+        assert_eq!(ids["yue"], 7);
+        assert_eq!(ids["ko"], 12);
+    }
+
+    #[test]
+    fn test_parse_sensevoice_output_tags() {
+        let tokens: HashMap<usize, String> = [
+            "<blank>",
+            "<|en|>",
+            "<|HAPPY|>",
+            "<|Applause|>",
+            "<|woitn|>",
+            "▁thank",
+            "▁you",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (i, t.to_string()))
+        .collect();
+        // 每帧取最大值：en HAPPY Applause woitn thank thank <blank> you
+        let frames = [1, 2, 3, 4, 5, 5, 0, 6];
+        let mut logits = Array2::zeros((frames.len(), tokens.len()));
+        for (i, &token) in frames.iter().enumerate() {
+            logits[[i, token]] = 1.0;
+        }
+        let output = parse_sensevoice_output(&logits, &tokens);
+        assert_eq!(output.text, "thank you");
+        assert_eq!(output.language.as_ref().and_then(|l| l.code()), Some("en"));
+        assert_eq!(
+            output.emotion.as_ref().and_then(|e| e.name()),
+            Some("happy")
+        );
+        assert_eq!(
+            output.event.as_ref().and_then(|e| e.name()),
+            Some("applause")
+        );
+        assert_eq!(text_with_emoji(&output), "👏thank you😊");
+
+        // 前 4 帧为查询标签，之后每帧 60ms
+        let words: Vec<_> = token_words(&output.tokens, 0.06)
+            .iter()
+            .map(|w| format!("{}@{:.2}-{:.2}", w.word, w.start, w.end))
+            .collect();
+        assert_eq!(words, vec![" thank@0.00-0.18", " you@0.18-0.24"]);
+
+        // 每个汉字单独成词，标点跟随前一个字
+        let tokens: Vec<_> = ["你", "好", "，"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| def::SenseVoiceToken {
+                text: t.to_string(),
+                start_frame: QUERY_FRAMES + i,
+                end_frame: QUERY_FRAMES + i + 1,
+            })
+            .collect();
+        let words: Vec<_> = token_words(&tokens, 0.06)
+            .into_iter()
+            .map(|w| w.word)
+            .collect();
+        assert_eq!(words, vec!["你", "好，"]);
+    }
+}
@@ -4,6 +4,10 @@ use ort::{inputs, session::Session, value::Value};
 
 use crate::onnx_models;
 
+mod streaming;
+
+pub use streaming::*;
+
 struct VadModelState {
     frame_size: usize,
     sample_rate: i64,
@@ -13,6 +17,11 @@ pub struct VadDevice {
     session: Session,
     state: VadModelState,
     context_size: usize,
+    /// check_vad 的循环状态，在多次调用之间保留
+    check_state: Array3<f32>,
+    check_context: Array2<f32>,
+    /// 上次调用末尾不足一帧的样本，与下次调用的音频拼成完整帧
+    check_pending: Vec<f32>,
 }
 
 pub struct VadResult {
@@ -24,85 +33,57 @@ pub struct VadResult {
 }
 
 impl VadDevice {
+    /// Score `audio_sample`, the audio following the previous call: the recurrent state is
+    /// kept between calls until [`VadDevice::reset`]. With `filters_value`, frames below it are
+    /// zeroed in `pcm_results`
     pub fn check_vad(
         &mut self,
         audio_sample: Vec<f32>,
         filters_value: Option<f32>,
     ) -> Result<VadResult> {
         let start = std::time::Instant::now();
-        let context_size = self.context_size;
 
-        let mut state = Array3::<f32>::zeros((2, 1, 128));
-        let mut context = Array2::<f32>::zeros((1, context_size));
+        let mut state = std::mem::take(&mut self.check_state);
+        let mut context = std::mem::take(&mut self.check_context);
 
-        let chunks: Vec<Vec<f32>> = audio_sample
-            .chunks(self.state.frame_size)
-            .map(|chunk| chunk.to_vec())
-            .collect();
+        // 帧网格从上次调用剩下的样本开始，这些样本已在上次调用中输出
+        let carried = self.check_pending.len();
+        let mut samples = std::mem::take(&mut self.check_pending);
+        samples.extend_from_slice(&audio_sample);
 
         let mut filtered_count: usize = 0;
         let mut res = vec![];
         let mut pcm_res: Vec<f32> = vec![];
 
-        for chunk_value in chunks {
-            let mut chunk = chunk_value.clone();
-            if chunk.len() < self.state.frame_size {
-                chunk.resize(self.state.frame_size, 0.0);
-                assert_eq!(chunk.len(), self.state.frame_size);
-            }
-
-            let next_context_data = &chunk[self.state.frame_size - context_size..];
-            let mut next_context = Array2::<f32>::zeros((1, context_size));
-            for (i, &val) in next_context_data.iter().enumerate() {
-                next_context[[0, i]] = val;
-            }
-
-            let mut chunk_array = Array2::<f32>::zeros((1, self.state.frame_size));
-            for (i, &val) in chunk.iter().enumerate() {
-                chunk_array[[0, i]] = val;
-            }
-
-            let full_chunk = ndarray::concatenate![Axis(1), context, chunk_array];
-
-            let sr_array = Array1::<i64>::from_elem(1, self.state.sample_rate);
-
-            let outputs = self.session.run(inputs![
-                "input" => Value::from_array(full_chunk)?,
-                "sr" => Value::from_array(sr_array)?,
-                "state" => Value::from_array(state.clone())?
-            ])?;
-
-            let output_keys: Vec<_> = outputs.keys().collect();
-            if output_keys.len() < 2 {
-                return Err(anyhow::anyhow!(
-                    "Expected at least 2 outputs, got {}",
-                    output_keys.len()
-                ));
-            }
-
-            let output = outputs.get(&output_keys[0]).unwrap();
-            let new_state = outputs.get(&output_keys[1]).unwrap();
-
-            let output_tensor = output.try_extract_tensor::<f32>()?;
-            let state_tensor = new_state.try_extract_tensor::<f32>()?;
-
-            let prediction_value = output_tensor.1[0]; // 获取第一个值
+        for (index, chunk_value) in samples.chunks(self.state.frame_size).enumerate() {
+            let prediction_value = if chunk_value.len() < self.state.frame_size {
+                // 不足一帧的尾部补零评分，但不写回状态，留到下次调用补成完整帧
+                let (mut tail_state, mut tail_context) = (state.clone(), context.clone());
+                self.check_pending = chunk_value.to_vec();
+                self.score_frame(chunk_value, &mut tail_state, &mut tail_context)?
+            } else {
+                self.score_frame(chunk_value, &mut state, &mut context)?
+            };
             res.push(prediction_value);
 
-            state = Array3::<f32>::from_shape_vec((2, 1, 128), state_tensor.1.to_vec())?;
-            context = next_context;
-
             if let Some(value) = filters_value {
+                let new_samples = if index == 0 {
+                    &chunk_value[carried.min(chunk_value.len())..]
+                } else {
+                    chunk_value
+                };
                 if prediction_value > value {
-                    pcm_res.extend(chunk_value);
+                    pcm_res.extend_from_slice(new_samples);
                 } else {
-                    pcm_res.extend(vec![0.0; chunk_value.len()]);
-                    filtered_count += chunk_value.len();
+                    pcm_res.extend(vec![0.0; new_samples.len()]);
+                    filtered_count += new_samples.len();
                 }
             }
         }
 
-        println!("VAD calculated prediction in {:?}", start.elapsed());
+        self.check_state = state;
+        self.check_context = context;
+        eprintln!("VAD calculated prediction in {:?}", start.elapsed());
         let res_len = res.len() as f32;
         let prediction = res.iter().sum::<f32>() / res_len;
 
@@ -114,6 +95,72 @@ impl VadDevice {
             filtered_count,
         })
     }
+
+    /// Samples per VAD frame (512 at 16kHz)
+    pub fn frame_size(&self) -> usize {
+        self.state.frame_size
+    }
+
+    pub fn new_frame_state(&self) -> (Array3<f32>, Array2<f32>) {
+        (
+            Array3::<f32>::zeros((2, 1, 128)),
+            Array2::<f32>::zeros((1, self.context_size)),
+        )
+    }
+
+    /// Score one frame (zero padded if short), updating the recurrent `state` and `context`
+    pub fn score_frame(
+        &mut self,
+        frame: &[f32],
+        state: &mut Array3<f32>,
+        context: &mut Array2<f32>,
+    ) -> Result<f32> {
+        let context_size = self.context_size;
+        let mut chunk = frame.to_vec();
+        if chunk.len() < self.state.frame_size {
+            chunk.resize(self.state.frame_size, 0.0);
+        }
+
+        let next_context_data = &chunk[self.state.frame_size - context_size..];
+        let mut next_context = Array2::<f32>::zeros((1, context_size));
+        for (i, &val) in next_context_data.iter().enumerate() {
+            next_context[[0, i]] = val;
+        }
+
+        let mut chunk_array = Array2::<f32>::zeros((1, self.state.frame_size));
+        for (i, &val) in chunk.iter().enumerate() {
+            chunk_array[[0, i]] = val;
+        }
+
+        let full_chunk = ndarray::concatenate![Axis(1), context.view(), chunk_array];
+
+        let sr_array = Array1::<i64>::from_elem(1, self.state.sample_rate);
+
+        let outputs = self.session.run(inputs![
+            "input" => Value::from_array(full_chunk)?,
+            "sr" => Value::from_array(sr_array)?,
+            "state" => Value::from_array(state.clone())?
+        ])?;
+
+        let output_keys: Vec<_> = outputs.keys().collect();
+        if output_keys.len() < 2 {
+            return Err(anyhow::anyhow!(
+                "Expected at least 2 outputs, got {}",
+                output_keys.len()
+            ));
+        }
+
+        let output = outputs.get(&output_keys[0]).unwrap();
+        let new_state = outputs.get(&output_keys[1]).unwrap();
+
+        let output_tensor = output.try_extract_tensor::<f32>()?;
+        let state_tensor = new_state.try_extract_tensor::<f32>()?;
+
+        *state = Array3::<f32>::from_shape_vec((2, 1, 128), state_tensor.1.to_vec())?;
+        *context = next_context;
+
+        Ok(output_tensor.1[0]) // 获取第一个值
+    }
 }
 
 pub fn new_vad_model(model_path: String, _try_with_gpu: bool) -> Result<VadDevice> {
@@ -133,6 +180,9 @@ pub fn new_vad_model(model_path: String, _try_with_gpu: bool) -> Result<VadDevic
         session,
         state,
         context_size,
+        check_state: Array3::<f32>::zeros((2, 1, 128)),
+        check_context: Array2::<f32>::zeros((1, context_size)),
+        check_pending: vec![],
     };
 
     Ok(device)
@@ -0,0 +1,263 @@
+use anyhow::Result;
+use ndarray::{Array2, Array3};
+
+use super::VadDevice;
+
+const SAMPLE_RATE: usize = 16000;
+
+/// Parameters of the streaming VAD, same meaning as Silero's `VADIterator`
+#[derive(Debug, Clone, Copy)]
+pub struct VadOptions {
+    /// Speech starts when a frame probability reaches this value
+    pub threshold: f32,
+    /// Speech may end once probabilities fall below this value, defaults to `threshold - 0.15`
+    pub neg_threshold: Option<f32>,
+    /// Shorter speech is discarded without any event
+    pub min_speech_duration_ms: u32,
+    /// Silence needed before speech is considered finished
+    pub min_silence_duration_ms: u32,
+    /// Padding added before the start and after the end of speech
+    pub speech_pad_ms: u32,
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            neg_threshold: None,
+            min_speech_duration_ms: 250,
+            min_silence_duration_ms: 100,
+            speech_pad_ms: 30,
+        }
+    }
+}
+
+/// Sample positions count every sample given to the VAD since it was created (or reset)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart { sample: usize },
+    SpeechEnd { sample: usize },
+}
+
+/// Hysteresis state machine fed with one probability per frame
+pub struct VadStateMachine {
+    threshold: f32,
+    neg_threshold: f32,
+    min_speech_samples: usize,
+    min_silence_samples: usize,
+    speech_pad_samples: usize,
+    current_sample: usize,
+    /// 语音候选的起点（未加 padding），None 表示当前是静音
+    speech_start: Option<usize>,
+    /// 已发出 SpeechStart 事件
+    started: bool,
+    /// 疑似结束的位置，静音持续 min_silence 后才真正结束
+    temp_end: Option<usize>,
+}
+
+impl VadStateMachine {
+    pub fn new(options: VadOptions) -> Self {
+        let ms_to_samples = |ms: u32| ms as usize * SAMPLE_RATE / 1000;
+        Self {
+            threshold: options.threshold,
+            neg_threshold: options
+                .neg_threshold
+                .unwrap_or((options.threshold - 0.15).max(0.01)),
+            min_speech_samples: ms_to_samples(options.min_speech_duration_ms),
+            min_silence_samples: ms_to_samples(options.min_silence_duration_ms),
+            speech_pad_samples: ms_to_samples(options.speech_pad_ms),
+            current_sample: 0,
+            speech_start: None,
+            started: false,
+            temp_end: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.current_sample = 0;
+        self.speech_start = None;
+        self.started = false;
+        self.temp_end = None;
+    }
+
+    /// Speech has started and not ended yet
+    pub fn is_speaking(&self) -> bool {
+        self.started
+    }
+
+    /// Samples processed so far
+    pub fn current_sample(&self) -> usize {
+        self.current_sample
+    }
+
+    /// Feed the probability of the next frame of `frame_samples` samples
+    pub fn process(&mut self, probability: f32, frame_samples: usize) -> Option<VadEvent> {
+        let frame_start = self.current_sample;
+        self.current_sample += frame_samples;
+
+        if probability >= self.threshold {
+            self.temp_end = None;
+            let speech_start = *self.speech_start.get_or_insert(frame_start);
+            if !self.started && self.current_sample - speech_start >= self.min_speech_samples {
+                self.started = true;
+                return Some(VadEvent::SpeechStart {
+                    sample: speech_start.saturating_sub(self.speech_pad_samples),
+                });
+            }
+            return None;
+        }
+
+        self.speech_start?;
+        if probability >= self.neg_threshold {
+            // 处于两个阈值之间：保持当前状态
+            return None;
+        }
+        let temp_end = *self.temp_end.get_or_insert(frame_start);
+        if self.started && self.current_sample - temp_end < self.min_silence_samples {
+            return None;
+        }
+
+        let started = self.started;
+        self.speech_start = None;
+        self.temp_end = None;
+        self.started = false;
+        // 未达到最短语音长度的片段直接丢弃
+        started.then(|| VadEvent::SpeechEnd {
+            sample: (temp_end + self.speech_pad_samples).min(self.current_sample),
+        })
+    }
+
+    /// End the current speech at the end of the stream
+    pub fn flush(&mut self) -> Option<VadEvent> {
+        let started = self.started;
+        let end = self.temp_end.unwrap_or(self.current_sample);
+        self.speech_start = None;
+        self.temp_end = None;
+        self.started = false;
+        started.then_some(VadEvent::SpeechEnd { sample: end })
+    }
+}
+
+/// Silero VAD that keeps its recurrent state between calls and only scores new frames
+pub struct StreamingVad {
+    device: VadDevice,
+    machine: VadStateMachine,
+    state: Array3<f32>,
+    context: Array2<f32>,
+    /// 不足一帧的剩余采样，下次调用时补齐
+    pending: Vec<f32>,
+    last_probability: f32,
+}
+
+impl StreamingVad {
+    pub fn new(device: VadDevice, options: VadOptions) -> Self {
+        let (state, context) = device.new_frame_state();
+        Self {
+            device,
+            machine: VadStateMachine::new(options),
+            state,
+            context,
+            pending: vec![],
+            last_probability: 0.0,
+        }
+    }
+
+    /// Consume new 16kHz mono samples, returns the events of the completed frames
+    pub fn accept(&mut self, pcm: &[f32]) -> Result<Vec<VadEvent>> {
+        let frame_size = self.device.frame_size();
+        self.pending.extend_from_slice(pcm);
+
+        let mut events = vec![];
+        let frames = self.pending.len() / frame_size;
+        for i in 0..frames {
+            let frame = &self.pending[i * frame_size..(i + 1) * frame_size];
+            let probability = self
+                .device
+                .score_frame(frame, &mut self.state, &mut self.context)?;
+            self.last_probability = probability;
+            if let Some(event) = self.machine.process(probability, frame_size) {
+                events.push(event);
+            }
+        }
+        self.pending.drain(..frames * frame_size);
+        Ok(events)
+    }
+
+    /// End the current speech (if any), e.g. when the audio source is finished
+    pub fn flush(&mut self) -> Option<VadEvent> {
+        self.machine.flush()
+    }
+
+    /// Drop all state, sample positions restart at zero
+    pub fn reset(&mut self) {
+        let (state, context) = self.device.new_frame_state();
+        self.state = state;
+        self.context = context;
+        self.pending.clear();
+        self.machine.reset();
+        self.last_probability = 0.0;
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.machine.is_speaking()
+    }
+
+    /// Speech probability of the last scored frame
+    pub fn last_probability(&self) -> f32 {
+        self.last_probability
+    }
+
+    /// Samples scored so far (excluding the pending partial frame)
+    pub fn current_sample(&self) -> usize {
+        self.machine.current_sample()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 512;
+
+    fn run(machine: &mut VadStateMachine, probabilities: &[f32]) -> Vec<VadEvent> {
+        probabilities
+            .iter()
+            .filter_map(|&p| machine.process(p, FRAME))
+            .collect()
+    }
+
+    #[test]
+    fn test_vad_state_machine_hysteresis() {
+        let mut machine = VadStateMachine::new(VadOptions {
+            min_speech_duration_ms: 64,
+            ..Default::default()
+        });
+        // 两帧静音，十帧语音（其中夹着介于两个阈值之间的帧），再八帧静音
+        let mut probabilities = vec![0.1, 0.1];
+        probabilities.extend([0.9, 0.9, 0.4, 0.9, 0.9, 0.4, 0.9, 0.9, 0.9, 0.9]);
+        probabilities.extend([0.1; 8]);
+        let events = run(&mut machine, &probabilities);
+
+        let pad = 30 * 16;
+        assert_eq!(
+            events,
+            vec![
+                VadEvent::SpeechStart {
+                    sample: 2 * FRAME - pad
+                },
+                VadEvent::SpeechEnd {
+                    sample: 12 * FRAME + pad
+                },
+            ]
+        );
+        assert!(!machine.is_speaking());
+    }
+
+    #[test]
+    fn test_vad_state_machine_drops_short_speech() {
+        let mut machine = VadStateMachine::new(VadOptions::default());
+        let events = run(&mut machine, &[0.1, 0.9, 0.9, 0.1, 0.1, 0.1, 0.1, 0.1]);
+        assert!(events.is_empty());
+        assert_eq!(machine.flush(), None);
+    }
+}
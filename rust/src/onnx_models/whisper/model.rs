@@ -2,7 +2,10 @@ use ndarray::{Array, Array1, Axis};
 use ort::session::Session;
 use std::time::Instant;
 
-use crate::candle_models::whisper::model::{DecodingResult, Segment, WhisperStatus};
+use crate::candle_models::whisper::model::{DecodingResult, Segment, Task, WhisperStatus};
+
+/// Decoder context length of every Whisper model
+pub const MAX_TARGET_POSITIONS: usize = 448;
 
 pub struct WhisperModel {
     session: Session,
@@ -21,13 +24,13 @@ pub struct WhisperModel {
 
 impl WhisperModel {
     pub fn from_session(session: Session) -> anyhow::Result<Self> {
-        println!("Whisper Model inputs:");
+        eprintln!("Whisper Model inputs:");
         for input in session.inputs.iter() {
-            println!("  - name: {}, type: {:?}", input.name, input.input_type);
+            eprintln!("  - name: {}, type: {:?}", input.name, input.input_type);
         }
-        println!("Whisper Model outputs:");
+        eprintln!("Whisper Model outputs:");
         for output in session.outputs.iter() {
-            println!("  - name: {}, type: {:?}", output.name, output.output_type);
+            eprintln!("  - name: {}, type: {:?}", output.name, output.output_type);
         }
 
         // const values
@@ -75,17 +78,17 @@ impl WhisperModel {
             .parse::<i32>()
             .unwrap_or(50258);
 
-        println!("Whisper model parameters loaded:");
-        println!("  - max_length: {}", max_length);
-        println!("  - min_length: {}", min_length);
-        println!("  - num_beams: {}", num_beams);
-        println!("  - num_return_sequences: {}", num_return_sequences);
-        println!("  - length_penalty: {}", length_penalty);
-        println!("  - repetition_penalty: {}", repetition_penalty);
-        println!("  - num_mel_bins: {}", num_mel_bins);
-        println!("  - n_frames: {}", N_FRAMES);
-        println!("  - decoder_start_token_id: {}", decoder_start_token_id);
-        println!("  - predict_timestamps: {}", predict_timestamps);
+        eprintln!("Whisper model parameters loaded:");
+        eprintln!("  - max_length: {}", max_length);
+        eprintln!("  - min_length: {}", min_length);
+        eprintln!("  - num_beams: {}", num_beams);
+        eprintln!("  - num_return_sequences: {}", num_return_sequences);
+        eprintln!("  - length_penalty: {}", length_penalty);
+        eprintln!("  - repetition_penalty: {}", repetition_penalty);
+        eprintln!("  - num_mel_bins: {}", num_mel_bins);
+        eprintln!("  - n_frames: {}", N_FRAMES);
+        eprintln!("  - decoder_start_token_id: {}", decoder_start_token_id);
+        eprintln!("  - predict_timestamps: {}", predict_timestamps);
 
         Ok(Self {
             session,
@@ -102,10 +105,14 @@ impl WhisperModel {
         })
     }
 
+    /// `prompt_tokens` (without `<|startofprev|>`) are fed before the sot sequence, the
+    /// returned text then starts with the prompt text
     pub fn inference(
         &mut self,
         audio_data: &[f32],
         language: Option<&str>,
+        task: Task,
+        prompt_tokens: &[u32],
     ) -> anyhow::Result<String> {
         use ort::value::Value;
 
@@ -114,30 +121,20 @@ impl WhisperModel {
         let audio = Array1::from_iter(audio_bytes.iter().copied());
         let audio = audio.into_owned().insert_axis(Axis(0));
 
-        // 使用模型属性中的参数
-        let max_length = Array::from_shape_vec((1,), vec![self.max_length])?;
+        let input_ids =
+            decoder_input_ids(self.decoder_start_token_id, language, task, prompt_tokens);
+        let decoder_input_ids = Array::from_shape_vec((1, input_ids.len()), input_ids)?;
+
+        // 使用模型属性中的参数，max_length 包含输入的提示
+        let max_length =
+            (self.max_length + prompt_tokens.len() as i32).min(MAX_TARGET_POSITIONS as i32);
+        let max_length = Array::from_shape_vec((1,), vec![max_length])?;
         let min_length = Array::from_shape_vec((1,), vec![self.min_length])?;
         let num_beams = Array::from_shape_vec((1,), vec![self.num_beams])?;
         let num_return_sequences = Array::from_shape_vec((1,), vec![self.num_return_sequences])?;
         let length_penalty = Array::from_shape_vec((1,), vec![self.length_penalty])?;
         let repetition_penalty = Array::from_shape_vec((1,), vec![self.repetition_penalty])?;
 
-        // 构建 decoder_input_ids
-        let language_token = self.language_to_token(language.unwrap_or("en"));
-        let task_token = super::multilingual::get_language_token_id("transcribe").unwrap() as i32;
-        let timestamp_token =
-            super::multilingual::get_language_token_id("notimestamps").unwrap() as i32;
-
-        let decoder_input_ids = Array::from_shape_vec(
-            (1, 4),
-            vec![
-                self.decoder_start_token_id,
-                language_token as i32,
-                task_token,
-                timestamp_token,
-            ],
-        )?;
-
         // 转换为 Value
         let audio_value = Value::from_array(audio)?;
         let max_length_value = Value::from_array(max_length)?;
@@ -162,19 +159,19 @@ impl WhisperModel {
         ])?;
 
         let inference_duration = start.elapsed();
-        println!(
+        eprintln!(
             "Whisper inference took {} seconds",
             inference_duration.as_secs_f32()
         );
 
         // 获取输出
         let output_keys: Vec<_> = outputs.keys().collect();
-        println!("Available output keys: {:?}", output_keys);
+        eprintln!("Available output keys: {:?}", output_keys);
 
         // 尝试获取字符串输出
         if let Some(str_output) = outputs.get("str") {
-            println!("Found 'str' output, type: {:?}", str_output.dtype());
-            println!("Output shape: {:?}", str_output.shape());
+            eprintln!("Found 'str' output, type: {:?}", str_output.dtype());
+            eprintln!("Output shape: {:?}", str_output.shape());
 
             // 使用 try_extract_string_array 提取字符串数组
             match str_output.try_extract_string_array() {
@@ -187,7 +184,7 @@ impl WhisperModel {
                     }
                 }
                 Err(e) => {
-                    println!("Failed to extract string array: {:?}", e);
+                    eprintln!("Failed to extract string array: {:?}", e);
                     Ok("Failed to extract string from model output".to_string())
                 }
             }
@@ -196,10 +193,6 @@ impl WhisperModel {
         }
     }
 
-    fn language_to_token(&self, language: &str) -> u32 {
-        super::multilingual::get_language_token_id(language).unwrap_or(50259)
-    }
-
     fn convert_audio_to_wav(&self, audio_data: &[f32]) -> Vec<u8> {
         let pcm_data: Vec<u8> = audio_data
             .iter()
@@ -244,6 +237,35 @@ impl WhisperModel {
     }
 }
 
+/// `[<|startofprev|> prompt] sot language task <|notimestamps|>`, English when no language
+/// is given
+fn decoder_input_ids(
+    decoder_start_token_id: i32,
+    language: Option<&str>,
+    task: Task,
+    prompt_tokens: &[u32],
+) -> Vec<i32> {
+    use super::multilingual::{get_language_token_id, get_token_id};
+
+    let mut input_ids = vec![];
+    if !prompt_tokens.is_empty() {
+        input_ids.push(get_token_id("<|startofprev|>").unwrap() as i32);
+        input_ids.extend(prompt_tokens.iter().map(|&t| t as i32));
+    }
+    let language_token = get_language_token_id(language.unwrap_or("en")).unwrap_or(50259);
+    let task_token = match task {
+        Task::Transcribe => get_token_id("<|transcribe|>"),
+        Task::Translate => get_token_id("<|translate|>"),
+    };
+    input_ids.extend([
+        decoder_start_token_id,
+        language_token as i32,
+        task_token.unwrap() as i32,
+        get_token_id("<|notimestamps|>").unwrap() as i32,
+    ]);
+    input_ids
+}
+
 pub fn create_whisper_segment(
     text: String,
     audio_duration_secs: f64,
@@ -256,7 +278,7 @@ pub fn create_whisper_segment(
         dr: DecodingResult {
             tokens: vec![],
             text,
-            avg_logprob: 0.0,
+            avg_logprob: f64::NAN, // ONNX 解码不输出 token 概率
             no_speech_prob: 0.0,
             temperature: 0.0,
             compression_ratio: 1.0,
@@ -265,5 +287,29 @@ pub fn create_whisper_segment(
         reasoning_lang: language,
         audio_duration: Some((audio_duration_secs * 1000.0) as u128),
         status: WhisperStatus::Working,
+        sub_segments: vec![],
+        id: 0,
+        committed: false,
+        language_probs: vec![],
+        emotion: None,
+        audio_event: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder_input_ids_task_token() {
+        // large-v3 词表：<|translate|> = 50359, <|transcribe|> = 50360
+        assert_eq!(
+            decoder_input_ids(50258, Some("fr"), Task::Translate, &[]),
+            vec![50258, 50265, 50359, 50364]
+        );
+        assert_eq!(
+            decoder_input_ids(50258, None, Task::Transcribe, &[1, 2]),
+            vec![50362, 1, 2, 50258, 50259, 50360, 50364]
+        );
     }
 }
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use crate::{
+    candle_models::whisper::{
+        model::{Segment, Task},
+        LaunchCaptionParams,
+    },
+    caption::{self, CaptionEvent, SpeechRecognizer, StreamingRecognizer},
+    onnx_models::whisper::model::create_whisper_segment,
+    onnx_models::zipformer::model::{
+        StreamToken, ZipformerModel, ZipformerStream, ENCODER_FRAME_SECS,
+    },
+};
+mod model;
+
+/// 流结束时补的静音，让最后一个 chunk 的右侧上下文完整
+const TAIL_PADDING_SAMPLES: usize = 16000 * 66 / 100;
+/// 一句话还没有识别出文字时，静音超过该时长即重新开始（秒）
+const SILENCE_WITHOUT_TEXT_SECS: f64 = 2.4;
+
+pub async fn launch_caption<F>(
+    params: LaunchCaptionParams,
+    result_callback: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(CaptionEvent) + Send + 'static,
+{
+    caption::engine::run_caption(
+        params,
+        |params| Ok(Box::new(ZipformerRecognizer::new(params)?)),
+        result_callback,
+    )
+    .await
+}
+
+/// Streaming zipformer transducer backend, decodes captured audio chunk by chunk
+pub struct ZipformerRecognizer {
+    model: ZipformerModel,
+    symbols: HashMap<usize, String>,
+    providers: Vec<String>,
+    /// Live stream, created on the first audio
+    stream: Option<ZipformerStream>,
+    /// Stream encoder frame where the current utterance starts
+    utterance_start: usize,
+    /// Text of the last reported partial result
+    partial_text: String,
+    /// 句尾静音达到该帧数即断句
+    endpoint_silence_frames: usize,
+    max_utterance_frames: usize,
+}
+
+impl ZipformerRecognizer {
+    pub fn new(params: &LaunchCaptionParams) -> anyhow::Result<Self> {
+        if params.whisper_task == Some(Task::Translate) {
+            anyhow::bail!("translation is not supported by zipformer");
+        }
+        let find = |part: &str| {
+            super::find_model_path(&params.models, Some(part))
+                .ok_or_else(|| anyhow::anyhow!("zipformer {part} onnx model not found"))
+        };
+        let (encoder, providers) = super::init_model(find("encoder")?, params.try_with_cuda)?;
+        let (decoder, _) = super::init_model(find("decoder")?, params.try_with_cuda)?;
+        let (joiner, _) = super::init_model(find("joiner")?, params.try_with_cuda)?;
+
+        // 加载tokens映射
+        let tokens_str = std::str::from_utf8(&params.tokenizer_data)?;
+        let symbols = model::load_tokens_from_data(tokens_str)?;
+        let model = ZipformerModel::from_sessions(encoder, decoder, joiner, &symbols)?;
+
+        // 断句规则与 sherpa-onnx 相同：有文字后静音 1.2 秒，或一句话超过最大时长
+        let endpoint_silence_secs = params.endpoint_silence_ms.unwrap_or(1200) as f64 / 1000.0;
+        let max_utterance_secs = params.whisper_max_audio_duration.unwrap_or(20) as f64;
+        Ok(Self {
+            model,
+            symbols,
+            providers,
+            stream: None,
+            utterance_start: 0,
+            partial_text: String::new(),
+            endpoint_silence_frames: (endpoint_silence_secs / ENCODER_FRAME_SECS) as usize,
+            max_utterance_frames: (max_utterance_secs / ENCODER_FRAME_SECS) as usize,
+        })
+    }
+
+    /// Create the live stream on the first audio
+    fn start_stream(&mut self) -> anyhow::Result<()> {
+        if self.stream.is_none() {
+            self.stream = Some(ZipformerStream::new(&mut self.model)?);
+            self.utterance_start = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Segment of `tokens`, spanning the stream encoder frames `start..end`
+fn utterance_segment(
+    symbols: &HashMap<usize, String>,
+    tokens: &[StreamToken],
+    start: usize,
+    end: usize,
+) -> Segment {
+    let text = model::tokens_to_text(tokens, symbols);
+    let duration = end.saturating_sub(start) as f64 * ENCODER_FRAME_SECS;
+    let mut segment = create_whisper_segment(text, duration, 0, None);
+    segment.start = start as f64 * ENCODER_FRAME_SECS;
+    // 推理耗时由 caption engine 统一填充
+    segment.reasoning_duration = None;
+    segment
+}
+
+/// Whether an utterance of `num_frames` encoder frames, whose last token was decoded on
+/// frame `last_token`, has ended
+fn is_endpoint(
+    num_frames: usize,
+    last_token: Option<usize>,
+    endpoint_silence_frames: usize,
+    max_utterance_frames: usize,
+) -> bool {
+    if num_frames >= max_utterance_frames {
+        return true;
+    }
+    match last_token {
+        Some(frame) => num_frames - frame > endpoint_silence_frames,
+        None => num_frames as f64 * ENCODER_FRAME_SECS >= SILENCE_WITHOUT_TEXT_SECS,
+    }
+}
+
+impl SpeechRecognizer for ZipformerRecognizer {
+    fn name(&self) -> &str {
+        "Zipformer"
+    }
+
+    fn device(&self) -> String {
+        super::providers_device(&self.providers)
+    }
+
+    fn providers(&self) -> Vec<String> {
+        self.providers.clone()
+    }
+
+    /// Decode a whole window with a fresh stream, for file transcription and the server
+    fn transcribe(&mut self, pcm: &[f32], _language: Option<&str>) -> anyhow::Result<Vec<Segment>> {
+        let mut stream = ZipformerStream::new(&mut self.model)?;
+        stream.accept_waveform(&mut self.model, pcm)?;
+        stream.accept_waveform(&mut self.model, &[0.0; TAIL_PADDING_SAMPLES])?;
+        let mut segment = utterance_segment(&self.symbols, stream.tokens(), 0, 0);
+        segment.duration = pcm.len() as f64 / 16000.0; // 音频时长（秒）
+        Ok(vec![segment])
+    }
+
+    fn reset(&mut self) {
+        self.stream = None;
+        self.utterance_start = 0;
+        self.partial_text.clear();
+    }
+
+    fn as_streaming(&mut self) -> Option<&mut dyn StreamingRecognizer> {
+        Some(self)
+    }
+}
+
+impl StreamingRecognizer for ZipformerRecognizer {
+    fn accept_waveform(&mut self, pcm: &[f32]) -> anyhow::Result<Option<Segment>> {
+        self.start_stream()?;
+        let Some(stream) = self.stream.as_mut() else {
+            return Ok(None);
+        };
+        if !stream.accept_waveform(&mut self.model, pcm)? {
+            return Ok(None);
+        }
+        let end = stream.num_frames();
+        let segment = utterance_segment(&self.symbols, stream.tokens(), self.utterance_start, end);
+        if segment.dr.text == self.partial_text {
+            return Ok(None);
+        }
+        self.partial_text = segment.dr.text.clone();
+        Ok(Some(segment))
+    }
+
+    fn is_endpoint(&self) -> bool {
+        let Some(stream) = self.stream.as_ref() else {
+            return false;
+        };
+        is_endpoint(
+            stream.num_frames() - self.utterance_start,
+            stream
+                .tokens()
+                .last()
+                .map(|token| token.frame - self.utterance_start),
+            self.endpoint_silence_frames,
+            self.max_utterance_frames,
+        )
+    }
+
+    fn finish_utterance(&mut self, input_finished: bool) -> anyhow::Result<Segment> {
+        self.start_stream()?;
+        let Some(stream) = self.stream.as_mut() else {
+            anyhow::bail!("zipformer stream is not started");
+        };
+        if input_finished {
+            stream.accept_waveform(&mut self.model, &[0.0; TAIL_PADDING_SAMPLES])?;
+        }
+        let end = stream.num_frames();
+        let tokens = stream.take_tokens();
+        let segment = utterance_segment(&self.symbols, &tokens, self.utterance_start, end);
+        self.utterance_start = end;
+        self.partial_text.clear();
+        Ok(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_rules() {
+        // 1.2 秒静音、最长 20 秒
+        let endpoint = |num_frames, last_token| is_endpoint(num_frames, last_token, 30, 500);
+        // 有文字后静音超过 1.2 秒
+        assert!(!endpoint(40, Some(10)));
+        assert!(endpoint(41, Some(10)));
+        // 没有文字时静音 2.4 秒才重新开始
+        assert!(!endpoint(59, None));
+        assert!(endpoint(60, None));
+        // 一直在说话也会在最大时长处断句
+        assert!(!endpoint(499, Some(498)));
+        assert!(endpoint(500, Some(499)));
+    }
+}
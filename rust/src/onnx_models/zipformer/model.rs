@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+
+use kaldi_fbank_rust::{FbankOptions, OnlineFbank};
+use ndarray::Array3;
+use ort::session::{Session, SessionInputValue};
+use ort::tensor::TensorElementType;
+use ort::value::{DynValue, Tensor, ValueType};
+
+const SAMPLE_RATE: f32 = 16000.0;
+const NUM_BINS: usize = 80;
+/// 编码器输出帧的时长，zipformer 下采样 4 倍
+pub const ENCODER_FRAME_SECS: f64 = 0.04;
+/// 空白符 id，sherpa-onnx 导出的模型固定为 0
+const BLANK_ID: usize = 0;
+/// kaldi fbank 保留所有算过的帧，超过 10 分钟后在断句处重新开始
+const MAX_STREAM_FRAMES: usize = 10 * 60 * 100;
+
+/// Streaming transducer exported by sherpa-onnx / icefall (`export-onnx-streaming.py`):
+/// the encoder carries its caches from chunk to chunk, decoding is a greedy search over
+/// the stateless decoder and the joiner.
+pub struct ZipformerModel {
+    encoder: Session,
+    decoder: Session,
+    joiner: Session,
+    /// fbank frames fed to the encoder per chunk (`T`, includes the right context)
+    chunk_frames: usize,
+    /// fbank frames the encoder advances per chunk (`decode_chunk_len`)
+    chunk_shift: usize,
+    /// Tokens the decoder looks back on
+    context_size: usize,
+    /// `<unk>` is decoded like blank
+    unk_id: Option<usize>,
+}
+
+impl ZipformerModel {
+    pub fn from_sessions(
+        encoder: Session,
+        decoder: Session,
+        joiner: Session,
+        tokens: &HashMap<usize, String>,
+    ) -> anyhow::Result<Self> {
+        // 编码器输入为 x 和各层缓存，输出为 encoder_out 和同样顺序的新缓存
+        if encoder.inputs.is_empty() || encoder.inputs.len() != encoder.outputs.len() {
+            anyhow::bail!(
+                "not a streaming transducer encoder: {} inputs, {} outputs",
+                encoder.inputs.len(),
+                encoder.outputs.len()
+            );
+        }
+        let chunk_frames = match metadata_usize(&encoder, "T") {
+            Some(t) => t,
+            // 没有元数据时从 x 的形状 [N, T, 80] 读取
+            None => match &encoder.inputs[0].input_type {
+                ValueType::Tensor { shape, .. } if shape.len() == 3 && shape[1] > 0 => {
+                    shape[1] as usize
+                }
+                _ => anyhow::bail!("the encoder has no chunk size (metadata `T`)"),
+            },
+        };
+        let chunk_shift = metadata_usize(&encoder, "decode_chunk_len")
+            .ok_or_else(|| anyhow::anyhow!("the encoder has no `decode_chunk_len` metadata"))?;
+        if chunk_shift == 0 || chunk_shift > chunk_frames {
+            anyhow::bail!(
+                "invalid encoder chunk: T {chunk_frames}, decode_chunk_len {chunk_shift}"
+            );
+        }
+        let context_size = metadata_usize(&decoder, "context_size").unwrap_or(2);
+        let unk_id = tokens
+            .iter()
+            .find(|(_, token)| token.as_str() == "<unk>")
+            .map(|(&id, _)| id);
+
+        eprintln!("Zipformer model loaded:");
+        eprintln!("  - T (chunk frames): {}", chunk_frames);
+        eprintln!("  - decode_chunk_len: {}", chunk_shift);
+        eprintln!("  - context_size: {}", context_size);
+        eprintln!("  - encoder caches: {}", encoder.inputs.len() - 1);
+
+        Ok(Self {
+            encoder,
+            decoder,
+            joiner,
+            chunk_frames,
+            chunk_shift,
+            context_size,
+            unk_id,
+        })
+    }
+
+    /// Zeroed encoder caches for a new stream
+    fn init_states(&self) -> anyhow::Result<Vec<DynValue>> {
+        // 除第一个输入 x 以外都是缓存，只有 batch 维度是动态的
+        self.encoder
+            .inputs
+            .iter()
+            .skip(1)
+            .map(|input| {
+                let ValueType::Tensor { ty, shape, .. } = &input.input_type else {
+                    anyhow::bail!("encoder input {} is not a tensor", input.name)
+                };
+                let shape: Vec<usize> = shape.iter().map(|&dim| dim.max(1) as usize).collect();
+                let len = shape.iter().product();
+                Ok(match ty {
+                    TensorElementType::Float32 => {
+                        Tensor::from_array((shape, vec![0f32; len]))?.into_dyn()
+                    }
+                    TensorElementType::Int64 => {
+                        Tensor::from_array((shape, vec![0i64; len]))?.into_dyn()
+                    }
+                    TensorElementType::Int32 => {
+                        Tensor::from_array((shape, vec![0i32; len]))?.into_dyn()
+                    }
+                    _ => anyhow::bail!("unsupported type {ty:?} of encoder input {}", input.name),
+                })
+            })
+            .collect()
+    }
+
+    /// Encode one chunk of `chunk_frames` fbank frames, `states` are replaced by the new caches.
+    /// Returns the encoder frames of the chunk
+    fn run_encoder(
+        &mut self,
+        frames: &[Vec<f32>],
+        states: &mut Vec<DynValue>,
+    ) -> anyhow::Result<Vec<Vec<f32>>> {
+        let x = Array3::from_shape_vec(
+            (1, frames.len(), NUM_BINS),
+            frames.iter().flatten().copied().collect(),
+        )?;
+        let mut inputs: Vec<(String, SessionInputValue)> = Vec::with_capacity(states.len() + 1);
+        inputs.push((
+            self.encoder.inputs[0].name.clone(),
+            Tensor::from_array(x)?.into(),
+        ));
+        for (input, state) in self.encoder.inputs.iter().skip(1).zip(states.iter()) {
+            inputs.push((input.name.clone(), state.into()));
+        }
+        let output_names: Vec<String> = self
+            .encoder
+            .outputs
+            .iter()
+            .map(|output| output.name.clone())
+            .collect();
+
+        let mut outputs = self.encoder.run(inputs)?;
+        // encoder_out: [1, T', D]
+        let (shape, data) = outputs[0].try_extract_tensor::<f32>()?;
+        if shape.len() != 3 {
+            anyhow::bail!("expected a 3D encoder output, got {:?}", shape);
+        }
+        let dim = shape[2] as usize;
+        let encoder_out = data.chunks(dim).map(|frame| frame.to_vec()).collect();
+        *states = output_names[1..]
+            .iter()
+            .map(|name| {
+                outputs
+                    .remove(name)
+                    .ok_or_else(|| anyhow::anyhow!("encoder output {name} is missing"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(encoder_out)
+    }
+
+    /// Decoder output for the last `context_size` tokens
+    fn run_decoder(&mut self, context: &[i64]) -> anyhow::Result<Vec<f32>> {
+        let y = Tensor::from_array(([1, context.len()], context.to_vec()))?;
+        let outputs = self.decoder.run(ort::inputs![y])?;
+        let (_, data) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(data.to_vec())
+    }
+
+    /// Most likely token for one encoder frame
+    fn run_joiner(&mut self, encoder_frame: &[f32], decoder_out: &[f32]) -> anyhow::Result<usize> {
+        let encoder_out = Tensor::from_array(([1, encoder_frame.len()], encoder_frame.to_vec()))?;
+        let decoder_out = Tensor::from_array(([1, decoder_out.len()], decoder_out.to_vec()))?;
+        let outputs = self.joiner.run(ort::inputs![encoder_out, decoder_out])?;
+        let (_, logits) = outputs[0].try_extract_tensor::<f32>()?;
+        let token = logits
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(id, _)| id)
+            .unwrap_or(BLANK_ID);
+        Ok(token)
+    }
+}
+
+/// A decoded token and the stream encoder frame it was emitted on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamToken {
+    pub id: usize,
+    pub frame: usize,
+}
+
+/// Decoding state of one audio stream: encoder caches, audio not yet encoded and the
+/// greedy search hypothesis
+pub struct ZipformerStream {
+    states: Vec<DynValue>,
+    fbank: StreamFbank,
+    /// fbank frames already read from `fbank`
+    frames_read: usize,
+    /// fbank frames from the start of the next chunk on
+    pending_frames: Vec<Vec<f32>>,
+    /// Last `context_size` tokens, blanks at the start of the stream
+    context: Vec<i64>,
+    decoder_out: Vec<f32>,
+    /// Encoder frames decoded since the start of the stream
+    num_frames: usize,
+    /// Tokens decoded since the last `take_tokens`
+    tokens: Vec<StreamToken>,
+}
+
+impl ZipformerStream {
+    pub fn new(model: &mut ZipformerModel) -> anyhow::Result<Self> {
+        let context = vec![BLANK_ID as i64; model.context_size];
+        let decoder_out = model.run_decoder(&context)?;
+        Ok(Self {
+            states: model.init_states()?,
+            fbank: StreamFbank(new_fbank()),
+            frames_read: 0,
+            pending_frames: vec![],
+            context,
+            decoder_out,
+            num_frames: 0,
+            tokens: vec![],
+        })
+    }
+
+    /// Feed audio (f32, mono, 16kHz) and decode every chunk it completes,
+    /// returns whether new tokens were decoded
+    pub fn accept_waveform(
+        &mut self,
+        model: &mut ZipformerModel,
+        pcm: &[f32],
+    ) -> anyhow::Result<bool> {
+        let fbank = &mut self.fbank.0;
+        fbank.accept_waveform(SAMPLE_RATE, pcm);
+        let ready = fbank.num_ready_frames() as usize;
+        self.pending_frames.extend(
+            (self.frames_read..ready)
+                .map_while(|i| fbank.get_frame(i as i32).map(|frame| frame.to_vec())),
+        );
+        self.frames_read = ready;
+
+        let num_tokens = self.tokens.len();
+        let encoder_out = encode_chunks(
+            &mut self.pending_frames,
+            model.chunk_frames,
+            model.chunk_shift,
+            &mut self.states,
+            |frames, states| model.run_encoder(frames, states),
+        )?;
+        for frame in &encoder_out {
+            self.decode_frame(model, frame)?;
+        }
+        Ok(self.tokens.len() > num_tokens)
+    }
+
+    /// Greedy search step: at most one token per encoder frame
+    fn decode_frame(&mut self, model: &mut ZipformerModel, frame: &[f32]) -> anyhow::Result<()> {
+        let token = model.run_joiner(frame, &self.decoder_out)?;
+        if token != BLANK_ID && Some(token) != model.unk_id {
+            self.tokens.push(StreamToken {
+                id: token,
+                frame: self.num_frames,
+            });
+            self.context.remove(0);
+            self.context.push(token as i64);
+            self.decoder_out = model.run_decoder(&self.context)?;
+        }
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    /// Encoder frames decoded since the start of the stream
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    /// Tokens decoded since the last call to [`ZipformerStream::take_tokens`]
+    pub fn tokens(&self) -> &[StreamToken] {
+        &self.tokens
+    }
+
+    /// Hand over the decoded tokens at the end of an utterance, the encoder caches and
+    /// decoder context are kept
+    pub fn take_tokens(&mut self) -> Vec<StreamToken> {
+        if self.frames_read >= MAX_STREAM_FRAMES {
+            // 丢弃的只有不到两帧的句尾静音
+            self.fbank = StreamFbank(new_fbank());
+            self.frames_read = 0;
+        }
+        std::mem::take(&mut self.tokens)
+    }
+}
+
+/// Encode every complete chunk of `pending` fbank frames, each chunk starts `chunk_shift`
+/// frames after the previous one and `encode` carries the encoder caches in `states` over.
+/// The frames of the next chunk stay in `pending`
+fn encode_chunks<S>(
+    pending: &mut Vec<Vec<f32>>,
+    chunk_frames: usize,
+    chunk_shift: usize,
+    states: &mut S,
+    mut encode: impl FnMut(&[Vec<f32>], &mut S) -> anyhow::Result<Vec<Vec<f32>>>,
+) -> anyhow::Result<Vec<Vec<f32>>> {
+    let mut encoder_out = vec![];
+    while pending.len() >= chunk_frames {
+        encoder_out.extend(encode(&pending[..chunk_frames], states)?);
+        pending.drain(..chunk_shift);
+    }
+    Ok(encoder_out)
+}
+
+/// Online fbank of a stream, which moves to the caption thread with the recognizer
+struct StreamFbank(OnlineFbank);
+
+// SAFETY: the fbank state is plain memory owned by this value, nothing ties it to the
+// thread that created it and it is only reached through `&mut ZipformerStream`
+unsafe impl Send for StreamFbank {}
+
+/// Text of decoded tokens, "▁" marks the start of a word
+pub fn tokens_to_text(tokens: &[StreamToken], symbols: &HashMap<usize, String>) -> String {
+    let text: String = tokens
+        .iter()
+        .filter_map(|token| symbols.get(&token.id))
+        .map(|symbol| symbol.as_str())
+        .collect();
+    text.replace('▁', " ").trim().to_string()
+}
+
+/// Load `tokens.txt` as written by sherpa-onnx, one `<symbol> <id>` per line
+pub fn load_tokens_from_data(data: &str) -> anyhow::Result<HashMap<usize, String>> {
+    let mut tokens = HashMap::new();
+    for line in data.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(symbol), Some(id)) = (parts.next(), parts.next_back()) else {
+            continue;
+        };
+        let id = id
+            .parse::<usize>()
+            .map_err(|e| anyhow::anyhow!("invalid token line {line:?}: {e}"))?;
+        tokens.insert(id, symbol.to_string());
+    }
+    if tokens.is_empty() {
+        anyhow::bail!("tokens.txt is empty");
+    }
+    Ok(tokens)
+}
+
+fn metadata_usize(session: &Session, key: &str) -> Option<usize> {
+    let metadata = session.metadata().ok()?;
+    let value = metadata.custom(key).ok()??;
+    value.trim().parse().ok()
+}
+
+/// fbank like sherpa-onnx's online feature extractor (f32 in [-1, 1], icefall 模型不缩放到16位整数范围)
+fn new_fbank() -> OnlineFbank {
+    let mut fbank_opts = FbankOptions::default();
+    fbank_opts.frame_opts.dither = 0.0;
+    fbank_opts.frame_opts.snip_edges = false;
+    fbank_opts.frame_opts.samp_freq = SAMPLE_RATE;
+    fbank_opts.mel_opts.num_bins = NUM_BINS as i32;
+    OnlineFbank::new(fbank_opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_to_text() {
+        let symbols = load_tokens_from_data(
+            "<blk> 0\n<sos/eos> 1\n<unk> 2\n▁HE 3\nLLO 4\n▁WORLD 5\n你 6\n好 7\n",
+        )
+        .unwrap();
+        assert_eq!(symbols.len(), 8);
+        let tokens: Vec<StreamToken> = [3, 4, 5, 6, 7]
+            .into_iter()
+            .enumerate()
+            .map(|(frame, id)| StreamToken { id, frame })
+            .collect();
+        assert_eq!(tokens_to_text(&tokens, &symbols), "HELLO WORLD你好");
+    }
+
+    #[test]
+    fn test_encode_chunks_carries_caches_over() {
+        // 每帧只有一个值：帧序号
+        let frames: Vec<Vec<f32>> = (0..40).map(|i| vec![i as f32]).collect();
+        // 模拟编码器：缓存是已编码的 chunk 数，输出 chunk 第一帧和当时的缓存
+        let encode = |chunk: &[Vec<f32>], states: &mut usize| {
+            assert_eq!(chunk.len(), 16);
+            let out = vec![vec![chunk[0][0], *states as f32]];
+            *states += 1;
+            Ok(out)
+        };
+
+        // 音频分几次到达，结果与一次到达相同
+        let mut pending = vec![];
+        let mut states = 0;
+        let mut encoder_out = vec![];
+        for piece in [&frames[..10], &frames[10..27], &frames[27..]] {
+            pending.extend_from_slice(piece);
+            encoder_out.extend(encode_chunks(&mut pending, 16, 12, &mut states, encode).unwrap());
+        }
+        let expected: Vec<Vec<f32>> = [[0.0, 0.0], [12.0, 1.0], [24.0, 2.0]]
+            .iter()
+            .map(|out| out.to_vec())
+            .collect();
+        assert_eq!(encoder_out, expected);
+        assert_eq!(states, 3);
+        // 下一个 chunk 从第 36 帧开始
+        assert_eq!(pending, frames[36..]);
+
+        let mut pending = frames.clone();
+        let mut states = 0;
+        let encoder_out = encode_chunks(&mut pending, 16, 12, &mut states, encode).unwrap();
+        assert_eq!(encoder_out, expected);
+    }
+}